@@ -79,6 +79,12 @@ no-icons-found=No icons found. Try a different search term or upload a custom ic
 # icons_installator.rs
 icons-installer-header=Please wait. Downloading icons...
 icons-installer-message=This app requires icons to work with. In case we don't have access to your installed icons, we are installing Papirus icon pack to local directory so you can choose one icon for your web app from this pack.
+icons-installer-starting=Starting download...
+icons-installer-downloading=Downloading Papirus icon pack... { $size }
+icons-installer-verifying=Verifying download...
+icons-installer-extracting=Extracting icons... { $files } files
+icons-installer-installing=Installing icons...
+icons-installer-failed=Installing icons failed: { $error }
 icons-installer-finished-waiting=Downloading finished. Waiting 3 seconds to close this window..
 
 # warning.rs
@@ -95,13 +101,36 @@ warning-app-url = Please enter a valid HTTP or HTTPS URL
 
 # import/export
 export-apps=Export all apps
+export-bundle=Export bundle with icons
 import-apps=Import apps
+import-from-browsers=Import from other browsers
+import-from-browsers-empty=No web apps found in Chromium, Firefox PWA or GNOME Web
 toast-export-success=Apps exported successfully
+toast-export-generated-icons=Apps exported, icons not found were replaced by monograms: { $apps }
 toast-import-success=Apps imported successfully
 toast-export-error=Failed to export apps
 toast-import-error=Failed to import apps
 duplicate=Duplicate
 
+# import preview
+import=Import
+import-empty=The file contains no valid web apps
+import-conflict-strategy=When an app already exists
+import-conflict-skip=Skip it
+import-conflict-overwrite=Overwrite it
+import-conflict-rename=Import as a copy
+import-status-new=New
+import-status-skip=Already installed, will be skipped
+import-status-overwrite=Will replace the installed app
+import-status-rename=Will be imported as a copy
+import-result-installed=Installed
+import-result-overwritten=Replaced
+import-result-renamed=Imported as { $id }
+import-result-skipped=Skipped
+import-result-failed=Failed: { $error }
+import-shared-profiles=Shared profiles
+import-shared-profiles-description=Apps from the same browser profile share a profile, so you sign in once for all of them. Cookies and other browser data are not imported.
+
 # file dialogs
 file-dialog-export-title=Export Web Apps
 file-dialog-import-title=Import Web Apps
@@ -110,6 +139,7 @@ file-dialog-open-icons=Open multiple images
 file-dialog-save=Save
 file-dialog-import=Import
 file-filter-ron=RON export
+file-filter-bundle=Web app bundle
 file-filter-ron-theme=Ron Theme
 file-filter-png=PNG Image
 file-filter-svg=SVG Images
@@ -134,6 +164,14 @@ user-agent-custom=Custom
 user-agent-custom-label=Custom User Agent
 user-agent-custom-placeholder=Mozilla/5.0 ...
 
+# icon badge
+icon-overlay=Icon Badge
+icon-overlay-none=None
+icon-overlay-dot=Coloured Dot
+icon-overlay-initial=Letter
+icon-overlay-private=Private Mode
+icon-overlay-letter=Letter
+
 # permissions
 permission-camera=Allow Camera
 permission-microphone=Allow Microphone
@@ -145,6 +183,52 @@ clear-data=Clear Data
 toast-data-cleared=Website data cleared successfully
 toast-data-clear-error=Failed to clear website data
 profile-data-size=Profile Data Size
+site-data=Site Data
+site-data-cache=Cache
+site-data-cookies=Cookies
+site-data-storage=Site Storage
+site-data-service-workers=Service Workers
+site-data-clear=Clear
+toast-site-data-cleared={ $data } cleared
+site-data-inspector=Cookies and Storage
+inspect-site-data=Inspect
+site-data-empty=No cookies or site storage
+cookie-session=Session
+cookie-expires=Expires { $time }
+local-storage=Local Storage
+indexed-db=IndexedDB
+indexed-db-databases={ $count } databases, { $size }
+delete-domain-data=Delete Data
+toast-domain-data-cleared=Data of { $domain } deleted
+
+# profiles
+profile=Profile
+profile-temporary=Temporary
+profile-own=This App Only
+profiles=Profiles
+profile-name=Profile name
+create-profile=Create Profile
+rename-profile=Rename
+save-profile-name=Save
+profile-used-by=Used by { $apps }
+profile-unused=Not used by any web app
+confirm-delete-profile=Are you sure you want to delete the profile { $profile } and all of its website data?
+toast-profile-error=Failed to update profile
+
+# backups
+backups=Backups
+back-up-profile=Back up cookies and site storage
+back-up=Back Up
+backups-empty=No backups yet
+restore-backup=Restore
+prune-backups=Delete older backups
+prune-backups-keep=Keep Latest { $count }
+confirm-restore-backup=Are you sure you want to replace the website data of this profile with the backup from { $time }? Sign-ins made since then are lost.
+toast-backup-created=Backup created
+toast-backup-restored=Backup restored
+toast-backup-deleted=Backup deleted
+toast-backups-pruned={ $count } old backups deleted
+toast-backup-error=Backup failed: { $error }
 
 # URL scheme handlers
 url-schemes=URL Schemes
@@ -168,17 +252,123 @@ fetch-thumbnail=Load Preview
 
 # privacy features (#53, #60, #61)
 content-blocking=Block Ads & Trackers
-block-third-party-cookies=Block Third-Party Cookies
-block-webrtc=Block WebRTC IP Leak
+cookie-policy=Accept Cookies
+cookie-policy-always=Always
+cookie-policy-no-third-party=Only From This Site
+cookie-policy-never=Never
+cookie-exceptions=Allow Third-Party Cookies From
+cookie-exceptions-placeholder=login.example.com, sso.example.org
+webrtc=WebRTC
+webrtc-disabled=Disabled
+webrtc-disabled-description=Calls, video chat and screen sharing do not work.
+webrtc-enabled=Enabled
+webrtc-enabled-description=Calls and screen sharing work. Addresses in your local network are hidden behind random names, peers still see your public IP address. WebKit does not offer a mode that reveals local addresses or only connects through relays.
 
 # proxy (#54)
-proxy-url=Proxy URL
-proxy-url-placeholder=http://proxy.example.com:8080
+proxy=Proxy
+proxy-system=System Settings
+proxy-direct=No Proxy
+proxy-manual=Manual
+proxy-pac=Automatic Configuration
+proxy-url=Proxy for All Protocols
+proxy-url-placeholder=socks5://proxy.example.com:1080
+proxy-http=HTTP Proxy
+proxy-https=HTTPS Proxy
+proxy-ignore=Connect Directly To
+proxy-ignore-placeholder=localhost, *.example.com, 10.0.0.0/8
+proxy-pac-url=Configuration URL
+proxy-pac-url-placeholder=http://wpad.example.com/proxy.pac
+proxy-pac-description=The system proxy service (libproxy) evaluates the file for each request.
+warning-proxy=Invalid proxy: { $error }
+warning-shared-network=Shares its profile with { $app }, which uses another proxy or other languages. Apps of one profile need the same.
+
+# request headers
+request-headers=Request Headers
+request-headers-description=Headers are added to the start page, pages opened through links and GET forms, and requests made by scripts of matching URLs. An empty pattern matches all pages, but only requests scripts make to the site of the page.
+request-headers-limits=Images, style sheets, frames, form submissions with POST, redirects and pages a script opens without a click are loaded without the headers.
+accept-language=Languages
+accept-language-placeholder=de-CH, de, en
+header-pattern=https://tools.example.com/*
+header-name=Header
+header-value=Value
+add-header=Add Header
+warning-header=Invalid header: { $error }
+
+# certificates
+certificates=Certificates
+ca-bundle=Extra CA Certificates
+ca-bundle-placeholder=/etc/pki/tls/certs/corporate-ca.pem
+warning-ca-bundle=Invalid CA certificates: { $error }
+trusted-certificates-empty=No certificates trusted from an error page.
+tls-error-title=This Connection Is Not Secure
+tls-error-description={ $host } presented a certificate that cannot be trusted. Someone could be impersonating the site.
+tls-error-unknown-ca=The certificate is not signed by a trusted authority.
+tls-error-bad-identity=The certificate belongs to a different site.
+tls-error-not-activated=The certificate is not valid yet.
+tls-error-expired=The certificate has expired.
+tls-error-revoked=The certificate has been revoked.
+tls-error-insecure=The certificate uses an insecure algorithm.
+tls-error-generic=The certificate could not be verified.
+tls-error-subject=Issued to
+tls-error-issuer=Issued by
+tls-error-valid-from=Valid from
+tls-error-valid-until=Valid until
+tls-error-fingerprint=SHA-256 fingerprint
+tls-error-trust-description=Only trust this certificate if your administrator confirmed its fingerprint for { $host }. The app will accept it from now on.
+tls-error-trust=Trust This Certificate
+tls-error-retry=Try Again
+client-certificate=Client Certificate
+client-certificate-placeholder=/home/user/certificate.p12
+warning-client-certificate=Invalid client certificate: { $error }
+
+# authentication
+auth-title=Sign In
+auth-description={ $host } asks you to sign in to “{ $realm }”.
+auth-retry=The user name or password for { $host } was not accepted. Try again.
+auth-username=User name
+auth-password=Password
+auth-remember=Remember in the keyring
+auth-sign-in=Sign In
+client-certificate-password=Enter the password of the client certificate { $file } to sign in to { $host }.
+client-certificate-retry=The password of { $file } is not correct. Try again.
+saved-passwords=Saved passwords in the keyring
+forget-passwords=Forget
+toast-passwords-forgotten={ $count } saved passwords removed
+toast-passwords-error=Failed to remove saved passwords: { $error }
+
+# offline and error pages
+offline-title=You Are Offline
+offline-description={ $app } needs a network connection.
+load-error-title=Cannot Reach { $host }
+offline-retry=Try Again
+offline-note=The page reloads by itself when the network is back.
+offline-copy-banner=You are offline. This is a saved copy of the page, it reloads when the network is back.
 
 # zoom & session (#55, #56)
 zoom-level=Zoom Level
 zoom-level-placeholder=1.0
 restore-session=Restore Last Session
+offline-copy=Show Saved Copy When Offline
+
+# automatic reloads
+reload-interval=Reload Every (Minutes)
+reload-on-resume=Reload After Waking From Suspend
+reload-on-focus=Reload When Focused After (Minutes)
+reload-never=Never
+
+# kiosk mode
+kiosk=Kiosk
+kiosk-mode=Fullscreen Kiosk
+kiosk-exit=Exit Shortcut
+kiosk-idle=Back to Start Page When Idle (Minutes)
+kiosk-idle-never=Never
+kiosk-domains=Other Allowed Domains
+kiosk-domains-placeholder=login.example.com, cdn.example.com
+warning-kiosk-exit=Invalid exit shortcut: { $error }
+
+# printing
+pdf-saved=Saved the page as { $file }
+pdf-failed=Could not save the page as PDF: { $error }
 
 # usage statistics (#57)
 launch-count=Launch Count
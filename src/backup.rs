@@ -0,0 +1,175 @@
+//! Snapshots of profile data.
+//!
+//! A snapshot is a gzip compressed tar archive of a profile directory, stored
+//! as `backups/<profile id>/<unix time>.tar.gz`. Restoring one replaces the
+//! whole profile directory, so the login state is exactly as it was when the
+//! snapshot was taken.
+//!
+//! WebKit keeps its databases open while an app runs, so snapshots are neither
+//! taken nor restored while an app using the profile is running.
+
+use flate2::{Compression, write::GzEncoder};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{APP_ID, browser::sanitize_app_id, bundle, profile};
+
+const BACKUPS_DIR: &str = "backups";
+const SNAPSHOT_EXT: &str = ".tar.gz";
+/// Top level directory of the profile inside a snapshot.
+const ARCHIVE_ROOT: &str = "profile";
+/// Snapshots kept by [`prune`] unless asked otherwise.
+pub const DEFAULT_KEEP: usize = 3;
+
+type BackupResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// ID of the backed up profile.
+    pub profile: String,
+    /// Unix time the snapshot was taken at, unique per profile.
+    pub created: u64,
+    /// Size of the archive in bytes.
+    pub size: u64,
+    path: PathBuf,
+}
+
+/// Directory holding the snapshots of the profile `id`.
+fn backups_path(id: &str) -> Option<PathBuf> {
+    let id = sanitize_app_id(id);
+    if id.is_empty() {
+        return None;
+    }
+
+    dirs::data_dir().map(|dir| dir.join(APP_ID).join(BACKUPS_DIR).join(id))
+}
+
+fn read_snapshot(id: &str, path: &Path) -> Option<Snapshot> {
+    let created = path
+        .file_name()?
+        .to_str()?
+        .strip_suffix(SNAPSHOT_EXT)?
+        .parse()
+        .ok()?;
+    let metadata = fs::metadata(path).ok().filter(|m| m.is_file())?;
+
+    Some(Snapshot {
+        profile: id.to_string(),
+        created,
+        size: metadata.len(),
+        path: path.to_path_buf(),
+    })
+}
+
+/// Snapshots of the profile `id`, newest first.
+pub fn list(id: &str) -> Vec<Snapshot> {
+    let Some(entries) = backups_path(id).and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+
+    let mut snapshots: Vec<Snapshot> = entries
+        .flatten()
+        .filter_map(|entry| read_snapshot(id, &entry.path()))
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created));
+    snapshots
+}
+
+/// Fail if an app using the profile `id` is running.
+fn ensure_stopped(id: &str) -> BackupResult<()> {
+    let running = crate::running_webview_app_ids();
+    let names: Vec<String> = profile::users(id)
+        .into_iter()
+        .filter(|app| running.contains(app.browser.app_id.as_ref()))
+        .map(|app| app.name)
+        .collect();
+
+    if names.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("profile is in use by {}", names.join(", ")).into())
+    }
+}
+
+/// Take a snapshot of the profile `id`.
+pub fn create(id: &str) -> BackupResult<Snapshot> {
+    ensure_stopped(id)?;
+
+    let source = profile::path(id)
+        .filter(|dir| dir.is_dir())
+        .ok_or("profile has no data to back up")?;
+    let dir = backups_path(id).ok_or("failed to resolve backup directory")?;
+    fs::create_dir_all(&dir)?;
+
+    let mut created = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    while dir.join(format!("{created}{SNAPSHOT_EXT}")).exists() {
+        created += 1;
+    }
+    let path = dir.join(format!("{created}{SNAPSHOT_EXT}"));
+
+    // written next to the snapshot so an interrupted backup never shows up in the list
+    let file = tempfile::NamedTempFile::new_in(&dir)?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file.as_file(), Compression::default()));
+    builder.follow_symlinks(false);
+    builder.append_dir_all(ARCHIVE_ROOT, &source)?;
+    builder.into_inner()?.finish()?;
+    file.persist(&path)?;
+
+    read_snapshot(id, &path).ok_or_else(|| "failed to read the written snapshot".into())
+}
+
+/// Replace the data of the snapshot's profile with the snapshot.
+pub fn restore(snapshot: &Snapshot) -> BackupResult<()> {
+    ensure_stopped(&snapshot.profile)?;
+
+    let target = profile::path(&snapshot.profile).ok_or("failed to resolve profile directory")?;
+    let parent = target
+        .parent()
+        .ok_or("failed to resolve profile directory")?;
+    fs::create_dir_all(parent)?;
+
+    // unpacked beside the profile, so swapping it in is a rename on the same filesystem
+    let staging = tempfile::Builder::new()
+        .prefix(".restore-")
+        .tempdir_in(parent)?;
+    bundle::unpack(&snapshot.path, staging.path())?;
+
+    let restored = staging.path().join(ARCHIVE_ROOT);
+    if !restored.is_dir() {
+        return Err("snapshot contains no profile".into());
+    }
+
+    let previous = staging.path().join("previous");
+    if target.exists() {
+        fs::rename(&target, &previous)?;
+    }
+    if let Err(e) = fs::rename(&restored, &target) {
+        if previous.exists() {
+            let _ = fs::rename(&previous, &target);
+        }
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+/// Delete a snapshot.
+pub fn delete(snapshot: &Snapshot) -> std::io::Result<()> {
+    fs::remove_file(&snapshot.path)
+}
+
+/// Delete all but the `keep` newest snapshots of the profile `id`. Returns the
+/// number of deleted snapshots.
+pub fn prune(id: &str, keep: usize) -> std::io::Result<usize> {
+    let old = list(id).into_iter().skip(keep).collect::<Vec<_>>();
+    for snapshot in &old {
+        delete(snapshot)?;
+    }
+
+    Ok(old.len())
+}
@@ -0,0 +1,874 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::{Parser, Subcommand};
+use strum::IntoEnumIterator;
+use webapps::{
+    Category, WindowSize, backup,
+    browser::{Browser, CookiePolicy, HeaderRule, PermissionPolicy, UserAgent, WebRtcPolicy},
+    icon_pipeline::IconOverlay,
+    inspector,
+    kiosk::Shortcut,
+    launcher::{ConflictStrategy, ImportOutcome, WebAppLauncher, webapplauncher_is_valid},
+    manifest::{Manifest, SyncOptions, SyncStatus},
+    migrate::Source,
+    profile,
+    proxy::{self, ProxyConfig},
+    site_data::{self, SiteData},
+};
+
+/// Manage Quick Web Apps from the command line.
+#[derive(Parser, Debug)]
+#[command(name = "webapps", author, version, about, long_about = None)]
+#[command(propagate_version = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List installed web apps
+    List,
+    /// Create a new web app
+    Add {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        url: String,
+        /// One of the freedesktop main categories, e.g. Network or Office
+        #[arg(long)]
+        category: String,
+        /// Path to an icon file; the site favicon is downloaded if omitted
+        #[arg(long)]
+        icon: Option<PathBuf>,
+        /// Keep cookies and site data between launches
+        #[arg(long)]
+        persistent: bool,
+        /// Additional settings, see `edit --help` for the available keys
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        settings: Vec<String>,
+        /// Only write the web app to the database
+        #[arg(long)]
+        no_desktop_entry: bool,
+    },
+    /// Change settings of a web app
+    #[command(after_help = SETTINGS_HELP)]
+    Edit {
+        id: String,
+        #[arg(long = "set", value_name = "KEY=VALUE", required = true)]
+        settings: Vec<String>,
+        /// Only update the database, leaving the installed desktop entry as is
+        #[arg(long)]
+        no_desktop_entry: bool,
+    },
+    /// Remove a web app and its desktop entry
+    Remove { id: String },
+    /// Export all web apps to a RON file, or to stdout if no path is given
+    Export {
+        path: Option<PathBuf>,
+        /// Write a portable .tar.gz bundle that includes the icons
+        #[arg(long, requires = "path")]
+        bundle: bool,
+        /// Include the data of this profile in the bundle, may be repeated
+        #[arg(long = "profile", value_name = "ID", requires = "bundle")]
+        profiles: Vec<String>,
+    },
+    /// Import web apps from a RON file or bundle, or from other browsers
+    Import {
+        #[arg(required_unless_present = "from")]
+        path: Option<PathBuf>,
+        /// Import the web apps of another tool instead, may be repeated
+        #[arg(long, value_enum, conflicts_with = "path")]
+        from: Vec<Source>,
+        /// What to do with apps whose ID is already installed
+        #[arg(long, value_enum, default_value_t)]
+        on_conflict: ConflictStrategy,
+        /// Only write the web apps to the database
+        #[arg(long)]
+        no_desktop_entry: bool,
+    },
+    /// Create, update and optionally remove web apps to match a manifest file
+    Sync {
+        manifest: PathBuf,
+        /// Remove web apps that are not listed in the manifest
+        #[arg(long)]
+        prune: bool,
+        /// Only print what would change
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip installing and removing desktop entries
+        #[arg(long)]
+        no_desktop_entry: bool,
+    },
+    /// Launch a web app
+    Launch {
+        id: String,
+        #[arg(long)]
+        private: bool,
+        /// Show the app fullscreen as a kiosk
+        #[arg(long)]
+        kiosk: bool,
+    },
+    /// Manage profiles shared between web apps
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ProfileCommand {
+    /// List profiles with their size and the apps using them
+    List,
+    /// Create an empty profile and print its ID
+    Create { name: String },
+    /// Rename a profile
+    Rename { id: String, name: String },
+    /// Delete a profile that is not used by any web app
+    Delete { id: String },
+    /// Show the size of each kind of website data of a profile
+    Usage { id: String },
+    /// List the cookies and storage of a profile by domain
+    Inspect { id: String },
+    /// Clear cookies, cache and storage of a profile
+    Clear {
+        id: String,
+        /// Clear only this kind of data, may be repeated
+        #[arg(long, value_enum)]
+        data: Vec<SiteData>,
+        /// Clear only the cookies and storage of this domain and its subdomains
+        #[arg(long, conflicts_with = "data")]
+        domain: Option<String>,
+    },
+    /// Take a snapshot of a profile and print its timestamp
+    Backup { id: String },
+    /// List the snapshots of a profile, newest first
+    Backups { id: String },
+    /// Replace the data of a profile with a snapshot
+    Restore {
+        id: String,
+        /// Timestamp of the snapshot, as printed by backups
+        snapshot: u64,
+    },
+    /// Delete all but the newest snapshots of a profile
+    Prune {
+        id: String,
+        /// Number of snapshots to keep
+        #[arg(long, default_value_t = backup::DEFAULT_KEEP)]
+        keep: usize,
+    },
+}
+
+const SETTINGS_HELP: &str = "\
+Settings:
+  name, url, icon, category, title, window-size (WIDTHxHEIGHT), decorations,
+  private, mobile, user-agent (default, mobile or a custom string), css, js,
+  url-schemes (comma separated), content-blocking,
+  cookie-policy (always, no-third-party or never),
+  cookie-exceptions (comma separated domains allowed third-party cookies),
+  webrtc (disabled or enabled, WebKit always hides local addresses),
+  proxy (none, system, pac:URL or a proxy URI like socks5://host:1080),
+  proxy-http, proxy-https (proxy URIs for one scheme),
+  proxy-ignore (comma separated hosts reached directly),
+  header (adds '[URL PATTERN ]Name: Value', an empty value removes the header,
+  an empty setting removes all; without a pattern scripts only send it to the
+  page's own origin, form POSTs and redirects never carry it),
+  accept-language (e.g. de-CH, en),
+  ca-bundle (path of a PEM file of extra CA certificates),
+  untrust-certificate (a host, or empty for all trusted certificates),
+  client-certificate (path of a PKCS#12 file sent to sites that ask for one),
+  zoom, restore-session, offline-copy,
+  reload-interval (minutes between reloads, 0 to never reload),
+  reload-on-resume (reload after waking from suspend),
+  reload-on-focus (reload when focused after this many minutes in the
+  background, 0 to never reload),
+  kiosk (fullscreen, locked to the site of the app, closed only by its exit
+  shortcut), kiosk-exit (exit shortcut, default Ctrl+Alt+Q),
+  kiosk-idle (minutes without input before going back to the start page, 0
+  to stay), kiosk-domains (comma separated domains the kiosk may also show);
+  kiosk-exit, kiosk-idle and kiosk-domains turn kiosk mode on,
+  minimize-to-background, auto-dark-mode, camera, microphone, geolocation,
+  notifications,
+  profile (a profile ID, own for a profile of the app itself, or none),
+  icon-overlay (none, dot, private or initial:LETTER; dot and initial accept a
+  :#RRGGBB colour suffix)
+
+Boolean settings accept true/false, yes/no, on/off or 1/0. An empty value
+clears optional text settings.";
+
+type CliResult = Result<(), Box<dyn std::error::Error>>;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    init_logging();
+
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::List => list(),
+        Command::Add {
+            name,
+            url,
+            category,
+            icon,
+            persistent,
+            settings,
+            no_desktop_entry,
+        } => {
+            add(
+                name,
+                url,
+                &category,
+                icon,
+                persistent,
+                &settings,
+                no_desktop_entry,
+            )
+            .await
+        }
+        Command::Edit {
+            id,
+            settings,
+            no_desktop_entry,
+        } => edit(&id, &settings, no_desktop_entry).await,
+        Command::Remove { id } => remove(&id).await,
+        Command::Export {
+            path,
+            bundle,
+            profiles,
+        } => export(path, bundle, &profiles),
+        Command::Import {
+            path,
+            from,
+            on_conflict,
+            no_desktop_entry,
+        } => import(path.as_deref(), &from, on_conflict, no_desktop_entry).await,
+        Command::Sync {
+            manifest,
+            prune,
+            dry_run,
+            no_desktop_entry,
+        } => {
+            let options = SyncOptions {
+                prune,
+                dry_run,
+                skip_desktop_entries: no_desktop_entry,
+            };
+            sync(&manifest, options).await
+        }
+        Command::Launch { id, private, kiosk } => launch(&id, private, kiosk),
+        Command::Profile { command } => profiles(command).await,
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("webapps: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn init_logging() {
+    use tracing::Level;
+    use tracing_subscriber::FmtSubscriber;
+
+    // Keep stdout free for command output.
+    let subscriber = FmtSubscriber::builder()
+        .with_max_level(Level::WARN)
+        .with_writer(std::io::stderr)
+        .finish();
+
+    if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
+        eprintln!("Failed to set default subscriber: {e}");
+    }
+}
+
+fn find_app(id: &str) -> Result<WebAppLauncher, Box<dyn std::error::Error>> {
+    WebAppLauncher::from_appid(id).ok_or_else(|| format!("no web app with id '{id}'").into())
+}
+
+fn list() -> CliResult {
+    let mut apps = webapps::launcher::installed_webapps();
+    apps.sort_by(|a, b| {
+        let cat_cmp = a.category.name().cmp(&b.category.name());
+        cat_cmp.then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+
+    for app in apps {
+        println!(
+            "{}\t{}\t{}\t{}",
+            app.browser.app_id.as_ref(),
+            app.name,
+            app.category.as_ref(),
+            app.browser.url.unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
+
+async fn add(
+    name: String,
+    url: String,
+    category: &str,
+    icon: Option<PathBuf>,
+    persistent: bool,
+    settings: &[String],
+    no_desktop_entry: bool,
+) -> CliResult {
+    if !webapps::url_valid(&url) {
+        return Err(format!("'{url}' is not an http(s) URL").into());
+    }
+
+    let mut browser = Browser::new(&webapps::browser::generate_app_id(&name), persistent);
+    browser.window_title = Some(name.clone());
+    browser.url = Some(url.clone());
+
+    let mut launcher = WebAppLauncher {
+        browser,
+        name,
+        icon: String::new(),
+        category: parse_category(category)?,
+        icon_overlay: None,
+    };
+
+    launcher.icon = match icon {
+        Some(path) => import_icon(&path)?,
+        None => webapps::site_icon(&url, &launcher.name)
+            .await
+            .ok_or("failed to create an icon for the site, pass --icon instead")?,
+    };
+
+    for setting in settings {
+        apply_setting(&mut launcher, setting)?;
+    }
+
+    install(&launcher, no_desktop_entry).await?;
+    println!("{}", launcher.browser.app_id.as_ref());
+
+    Ok(())
+}
+
+async fn edit(id: &str, settings: &[String], no_desktop_entry: bool) -> CliResult {
+    let mut launcher = find_app(id)?;
+
+    for setting in settings {
+        apply_setting(&mut launcher, setting)?;
+    }
+
+    install(&launcher, no_desktop_entry).await
+}
+
+async fn remove(id: &str) -> CliResult {
+    find_app(id)?.delete().await
+}
+
+fn export(path: Option<PathBuf>, bundle: bool, profiles: &[String]) -> CliResult {
+    match path {
+        Some(path) if bundle => {
+            let apps = webapps::launcher::installed_webapps();
+            for name in webapps::bundle::export_bundle(&path, &apps, profiles)? {
+                eprintln!("webapps: icon of '{name}' not found, exported a monogram instead");
+            }
+            Ok(())
+        }
+        Some(path) => webapps::launcher::export_all(&path),
+        None => {
+            let apps = webapps::launcher::installed_webapps();
+            let content = ron::ser::to_string_pretty(&apps, ron::ser::PrettyConfig::default())?;
+            println!("{content}");
+            Ok(())
+        }
+    }
+}
+
+async fn import(
+    path: Option<&std::path::Path>,
+    sources: &[Source],
+    strategy: ConflictStrategy,
+    no_desktop_entry: bool,
+) -> CliResult {
+    let (apps, bundle, profiles) = match path {
+        Some(path) if webapps::bundle::is_bundle(path) => {
+            let bundle = webapps::bundle::Bundle::open(path)?;
+            (bundle.apps.clone(), Some(bundle), Vec::new())
+        }
+        Some(path) => (webapps::launcher::import_all(path)?, None, Vec::new()),
+        None => {
+            let discovery = webapps::migrate::discover(sources).await;
+            (discovery.apps, None, discovery.profiles)
+        }
+    };
+
+    let plan = webapps::launcher::plan_import(apps);
+    let results = webapps::launcher::install_imported(plan, strategy, !no_desktop_entry).await;
+
+    if let Some(bundle) = bundle {
+        bundle.install_profiles(&results);
+    }
+
+    let mut failed = false;
+    for result in &results {
+        let (id, name) = (&result.app_id, &result.name);
+        match &result.outcome {
+            ImportOutcome::Installed => println!("installed\t{id}\t{name}"),
+            ImportOutcome::Overwritten => println!("overwritten\t{id}\t{name}"),
+            ImportOutcome::Renamed(new_id) => println!("renamed\t{id} -> {new_id}\t{name}"),
+            ImportOutcome::Skipped => println!("skipped\t{id}\t{name}"),
+            ImportOutcome::Failed(e) => {
+                failed = true;
+                println!("failed\t{id}\t{name}\t{e}");
+            }
+        }
+    }
+
+    for profile in webapps::migrate::register_profiles(&profiles) {
+        println!("profile\t{}\t{}", profile.id, profile.name);
+    }
+
+    if failed {
+        return Err("some web apps could not be imported".into());
+    }
+
+    Ok(())
+}
+
+async fn sync(path: &std::path::Path, options: SyncOptions) -> CliResult {
+    let manifest = Manifest::load(path)?;
+    let base_dir = path.parent().unwrap_or(std::path::Path::new("."));
+    let report = webapps::manifest::sync(&manifest, base_dir, options).await;
+
+    for (id, status) in &report.entries {
+        match status {
+            SyncStatus::Created => println!("created\t{id}"),
+            SyncStatus::Updated => println!("updated\t{id}"),
+            SyncStatus::Unchanged => println!("ok\t{id}"),
+            SyncStatus::Removed => println!("removed\t{id}"),
+            SyncStatus::Failed(e) => println!("failed\t{id}\t{e}"),
+        }
+    }
+
+    if report.has_failures() {
+        return Err("some web apps could not be synced".into());
+    }
+
+    Ok(())
+}
+
+fn launch(id: &str, private: bool, kiosk: bool) -> CliResult {
+    let mut launcher = find_app(id)?;
+
+    launcher.record_launch();
+    if let Err(e) = launcher.save() {
+        tracing::warn!("Failed to update usage statistics: {e}");
+    }
+
+    let mut args = launcher.browser.app_id.clone();
+    args.private = private;
+    args.kiosk = kiosk;
+
+    webapps::launcher::webview_command(args).spawn()?;
+
+    Ok(())
+}
+
+async fn profiles(command: ProfileCommand) -> CliResult {
+    match command {
+        ProfileCommand::List => {
+            for profile in profile::all() {
+                let users: Vec<String> = profile::users(&profile.id)
+                    .into_iter()
+                    .map(|app| app.browser.app_id.id)
+                    .collect();
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    profile.id,
+                    profile.name,
+                    webapps::format_bytes(profile::size(&profile.id)),
+                    users.join(",")
+                );
+            }
+        }
+        ProfileCommand::Create { name } => println!("{}", profile::create(&name)?.id),
+        ProfileCommand::Rename { id, name } => profile::rename(&find_profile(&id)?, &name)?,
+        ProfileCommand::Delete { id } => profile::delete(&find_profile(&id)?)?,
+        ProfileCommand::Usage { id } => {
+            for (data, size) in site_data::sizes(&find_profile(&id)?) {
+                println!("{}\t{}", data.as_str(), webapps::format_bytes(size));
+            }
+        }
+        ProfileCommand::Inspect { id } => {
+            let inspection = inspector::inspect(&find_profile(&id)?);
+            for domain in inspection.domains() {
+                for cookie in inspection.cookies_of(&domain) {
+                    let expires = cookie
+                        .expires
+                        .map_or_else(|| "session".to_string(), |time| time.to_string());
+                    println!("{domain}\tcookie\t{}\t{expires}", cookie.name);
+                }
+                for data in inspection.local_storage_of(&domain) {
+                    let size = webapps::format_bytes(data.size);
+                    println!("{domain}\tlocal-storage\t{}\t{size}", data.origin);
+                }
+                for data in inspection.indexed_db_of(&domain) {
+                    let size = webapps::format_bytes(data.size);
+                    println!("{domain}\tindexeddb\t{}\t{size}", data.origin);
+                }
+            }
+            println!(
+                "-\tcache\t-\t{}",
+                webapps::format_bytes(inspection.cache_size)
+            );
+        }
+        ProfileCommand::Clear {
+            id,
+            domain: Some(domain),
+            ..
+        } => inspector::clear_domain(&find_profile(&id)?, &domain)
+            .await
+            .map_err(|e| e.to_string())?,
+        ProfileCommand::Clear { id, data, .. } if data.is_empty() => {
+            profile::clear(&find_profile(&id)?)?
+        }
+        ProfileCommand::Clear { id, data, .. } => site_data::clear(&find_profile(&id)?, &data)
+            .await
+            .map_err(|e| e.to_string())?,
+        ProfileCommand::Backup { id } => {
+            println!("{}", backup::create(&find_profile(&id)?)?.created)
+        }
+        ProfileCommand::Backups { id } => {
+            for snapshot in backup::list(&find_profile(&id)?) {
+                println!(
+                    "{}\t{}\t{}",
+                    snapshot.created,
+                    webapps::format_timestamp(snapshot.created),
+                    webapps::format_bytes(snapshot.size)
+                );
+            }
+        }
+        ProfileCommand::Restore { id, snapshot } => {
+            let id = find_profile(&id)?;
+            let snapshot = backup::list(&id)
+                .into_iter()
+                .find(|s| s.created == snapshot)
+                .ok_or_else(|| format!("no snapshot {snapshot} of profile '{id}'"))?;
+            backup::restore(&snapshot)?
+        }
+        ProfileCommand::Prune { id, keep } => {
+            println!("{}", backup::prune(&find_profile(&id)?, keep)?)
+        }
+    }
+
+    Ok(())
+}
+
+fn find_profile(id: &str) -> Result<String, Box<dyn std::error::Error>> {
+    profile::all()
+        .into_iter()
+        .find(|p| p.id == id)
+        .map(|p| p.id)
+        .ok_or_else(|| format!("no profile with id '{id}'").into())
+}
+
+/// Validate a web app, install its desktop entry and write it to the database.
+async fn install(launcher: &WebAppLauncher, no_desktop_entry: bool) -> CliResult {
+    if !webapplauncher_is_valid(
+        &launcher.icon,
+        &launcher.name,
+        &launcher.browser.url,
+        &launcher.category,
+    ) {
+        return Err("web app needs a name, an http(s) URL, an icon and a category".into());
+    }
+
+    // Apps sharing a profile run in one webview with one network session
+    let browser = &launcher.browser;
+    if let Some(profile) = browser.profile.as_deref() {
+        let mates = webapps::profile::process_mates(profile, browser.app_id.as_ref());
+        let settings = browser.network_settings();
+        if let (false, Some(app)) = (
+            browser.private_mode.unwrap_or(false),
+            webapps::profile::network_conflict(&mates, &settings),
+        ) {
+            return Err(format!(
+                "'{}' shares profile '{profile}' with another proxy or other languages, \
+                 both apps need the same",
+                app.name
+            )
+            .into());
+        }
+    }
+
+    if !no_desktop_entry {
+        launcher.create().await?;
+    }
+
+    launcher.save()
+}
+
+/// Normalize an icon file into the app icons directory and return its new path.
+fn import_icon(path: &std::path::Path) -> Result<String, Box<dyn std::error::Error>> {
+    if !path.is_file() {
+        return Err(format!("icon '{}' does not exist", path.display()).into());
+    }
+
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("icon");
+
+    webapps::move_icon(&path.to_string_lossy(), stem)
+        .map(|p| p.display().to_string())
+        .ok_or_else(|| format!("failed to import icon '{}'", path.display()).into())
+}
+
+fn parse_category(value: &str) -> Result<Category, Box<dyn std::error::Error>> {
+    Category::iter()
+        .filter(|c| c != &Category::None)
+        .find(|c| c.as_ref().eq_ignore_ascii_case(value) || c.name().eq_ignore_ascii_case(value))
+        .ok_or_else(|| format!("unknown category '{value}'").into())
+}
+
+fn parse_bool(value: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(format!("expected a boolean, got '{value}'").into()),
+    }
+}
+
+/// Parse a number of minutes, where 0 and an empty value mean never.
+fn parse_minutes(value: &str) -> Result<Option<u32>, Box<dyn std::error::Error>> {
+    match value.trim() {
+        "" => Ok(None),
+        minutes => Ok(Some(minutes.parse::<u32>()?).filter(|minutes| *minutes > 0)),
+    }
+}
+
+fn optional(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Parse an `icon-overlay` value. Dots and initials without a colour get the
+/// default colour of the app.
+fn parse_overlay(
+    value: &str,
+    app_id: &str,
+) -> Result<Option<IconOverlay>, Box<dyn std::error::Error>> {
+    let (kind, rest) = value.split_once(':').unwrap_or((value, ""));
+    let (text, color) = match rest.split_once(':') {
+        Some((text, color)) => (text, color.to_string()),
+        None if rest.starts_with('#') => ("", rest.to_string()),
+        None => (rest, IconOverlay::color_for(app_id)),
+    };
+
+    Ok(match kind.trim() {
+        "" | "none" => None,
+        "dot" => Some(IconOverlay::Dot(color)),
+        "private" => Some(IconOverlay::Private),
+        "initial" if !text.is_empty() => Some(IconOverlay::Initial {
+            text: text.to_string(),
+            color,
+        }),
+        "initial" => return Err("expected icon-overlay as initial:LETTER".into()),
+        other => return Err(format!("unknown icon overlay '{other}'").into()),
+    })
+}
+
+/// Apply a single `key=value` setting to a web app.
+fn apply_setting(launcher: &mut WebAppLauncher, setting: &str) -> CliResult {
+    let (key, value) = setting
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{setting}'"))?;
+    let browser = &mut launcher.browser;
+
+    match key.trim() {
+        "name" => launcher.name = value.to_string(),
+        "url" => {
+            if !webapps::url_valid(value) {
+                return Err(format!("'{value}' is not an http(s) URL").into());
+            }
+            browser.url = Some(value.to_string());
+        }
+        "icon" => launcher.icon = import_icon(std::path::Path::new(value))?,
+        "category" => launcher.category = parse_category(value)?,
+        "title" => browser.window_title = optional(value),
+        "window-size" => {
+            let (width, height) = value
+                .split_once('x')
+                .ok_or("expected window-size as WIDTHxHEIGHT")?;
+            browser.window_size = Some(WindowSize(width.trim().parse()?, height.trim().parse()?));
+        }
+        "decorations" => browser.window_decorations = Some(parse_bool(value)?),
+        "private" => browser.private_mode = Some(parse_bool(value)?),
+        "mobile" => browser.try_simulate_mobile = Some(parse_bool(value)?),
+        "user-agent" => {
+            browser.user_agent = Some(match value {
+                "" | "default" => UserAgent::Default,
+                "mobile" => UserAgent::Mobile,
+                custom => UserAgent::Custom(custom.to_string()),
+            })
+        }
+        "css" => browser.custom_css = optional(value),
+        "js" => browser.custom_js = optional(value),
+        "url-schemes" => {
+            let schemes: Vec<String> = value
+                .split(',')
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| {
+                    !s.is_empty()
+                        && s.chars()
+                            .all(|c| c.is_alphanumeric() || c == '-' || c == '+' || c == '.')
+                })
+                .collect();
+            browser.url_schemes = (!schemes.is_empty()).then_some(schemes);
+        }
+        "content-blocking" => browser.content_blocking = Some(parse_bool(value)?),
+        "cookie-policy" => browser.set_cookie_policy(
+            CookiePolicy::from_name(&value.to_ascii_lowercase())
+                .ok_or_else(|| format!("unknown cookie policy '{value}'"))?,
+        ),
+        "cookie-exceptions" => {
+            let domains = webapps::browser::parse_domains(value);
+            browser.cookie_exceptions = (!domains.is_empty()).then_some(domains);
+        }
+        "webrtc" => browser.set_webrtc_policy(
+            WebRtcPolicy::from_name(&value.to_ascii_lowercase())
+                .ok_or_else(|| format!("unknown WebRTC policy '{value}'"))?,
+        ),
+        "proxy" | "proxy-http" | "proxy-https" | "proxy-ignore" => {
+            set_proxy(browser, key.trim(), value.trim())?
+        }
+        "header" => set_header(browser, value)?,
+        "accept-language" => {
+            let languages = webapps::browser::parse_languages(value);
+            browser.accept_language = (!languages.is_empty()).then_some(languages);
+        }
+        "ca-bundle" => {
+            if !value.is_empty() {
+                webapps::tls::validate_ca_bundle(value)?;
+            }
+            browser.ca_bundle = optional(value);
+        }
+        "client-certificate" => {
+            if !value.is_empty() {
+                webapps::tls::read_client_certificate(value)?;
+            }
+            browser.client_certificate = optional(value);
+        }
+        "untrust-certificate" => {
+            browser.untrust_certificates(Some(value.trim()).filter(|host| !host.is_empty()))
+        }
+        "zoom" => browser.zoom_level = Some(value.parse::<f64>()?.clamp(0.25, 5.0)),
+        "restore-session" => browser.restore_session = Some(parse_bool(value)?),
+        "offline-copy" => browser.offline_copy = Some(parse_bool(value)?),
+        "reload-interval" => browser.reload_interval = parse_minutes(value)?,
+        "reload-on-resume" => browser.reload_on_resume = Some(parse_bool(value)?),
+        "reload-on-focus" => browser.reload_on_focus = parse_minutes(value)?,
+        "kiosk" => {
+            browser.kiosk = parse_bool(value)?.then(|| browser.kiosk.take().unwrap_or_default())
+        }
+        "kiosk-exit" | "kiosk-idle" | "kiosk-domains" => set_kiosk(browser, key.trim(), value)?,
+        "minimize-to-background" => browser.minimize_to_background = Some(parse_bool(value)?),
+        "auto-dark-mode" => browser.auto_dark_mode = Some(parse_bool(value)?),
+        "icon-overlay" => launcher.icon_overlay = parse_overlay(value, browser.app_id.as_ref())?,
+        "profile" => {
+            browser.profile = match value {
+                "" | "none" => None,
+                "own" => Some(browser.app_id.id.clone()),
+                id => Some(find_profile(id)?),
+            }
+        }
+        "camera" | "microphone" | "geolocation" | "notifications" => {
+            let allow = parse_bool(value)?;
+            let permissions = browser
+                .permissions
+                .get_or_insert_with(PermissionPolicy::default);
+            match key.trim() {
+                "camera" => permissions.allow_camera = allow,
+                "microphone" => permissions.allow_microphone = allow,
+                "geolocation" => permissions.allow_geolocation = allow,
+                _ => permissions.allow_notifications = allow,
+            }
+        }
+        other => return Err(format!("unknown setting '{other}'").into()),
+    }
+
+    Ok(())
+}
+
+/// Apply one of the kiosk settings, turning kiosk mode on.
+fn set_kiosk(browser: &mut Browser, key: &str, value: &str) -> CliResult {
+    let mut kiosk = browser.kiosk.clone().unwrap_or_default();
+    match key {
+        "kiosk-exit" => {
+            Shortcut::parse(value)?;
+            kiosk.exit_shortcut = value.trim().to_string();
+        }
+        "kiosk-idle" => kiosk.idle_reset = parse_minutes(value)?,
+        _ => kiosk.allowed_domains = webapps::browser::parse_domains(value),
+    }
+
+    browser.kiosk = Some(kiosk);
+    Ok(())
+}
+
+/// Apply one of the proxy settings, switching to a manual proxy for the
+/// per-scheme ones.
+fn set_proxy(browser: &mut Browser, key: &str, value: &str) -> CliResult {
+    let config = match (key, value, value.strip_prefix("pac:")) {
+        ("proxy", "" | "system", _) => ProxyConfig::System,
+        ("proxy", "none", _) => ProxyConfig::Direct,
+        ("proxy", _, Some(url)) => ProxyConfig::Pac(url.to_string()),
+        _ => {
+            let (mut default, mut http, mut https, mut ignore_hosts) = match browser.proxy() {
+                ProxyConfig::Manual {
+                    default,
+                    http,
+                    https,
+                    ignore_hosts,
+                } => (default, http, https, ignore_hosts),
+                _ => (None, None, None, Vec::new()),
+            };
+            match key {
+                "proxy" => default = optional(value),
+                "proxy-http" => http = optional(value),
+                "proxy-https" => https = optional(value),
+                _ => ignore_hosts = proxy::parse_ignore_hosts(value),
+            }
+            ProxyConfig::Manual {
+                default,
+                http,
+                https,
+                ignore_hosts,
+            }
+        }
+    };
+
+    config
+        .validate()
+        .map_err(|e| format!("invalid proxy: {e}"))?;
+    browser.set_proxy(config);
+
+    Ok(())
+}
+
+/// Add, replace or remove a header rule, or remove all of them if `value` is
+/// empty.
+fn set_header(browser: &mut Browser, value: &str) -> CliResult {
+    if value.trim().is_empty() {
+        browser.request_headers = None;
+        return Ok(());
+    }
+
+    let rule = HeaderRule::parse(value)
+        .ok_or_else(|| format!("expected '[URL PATTERN ]Name: Value', got '{value}'"))?;
+    rule.validate()?;
+
+    let mut rules = browser.request_headers.take().unwrap_or_default();
+    rules.retain(|r| r.pattern != rule.pattern || !r.name.eq_ignore_ascii_case(&rule.name));
+    if !rule.value.is_empty() {
+        rules.push(rule);
+    }
+    browser.request_headers = (!rules.is_empty()).then_some(rules);
+
+    Ok(())
+}
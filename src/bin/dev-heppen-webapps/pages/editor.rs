@@ -5,12 +5,49 @@ use cosmic::{
     style, task,
     widget::{self},
 };
-use rand::{Rng, rng};
+use std::collections::HashMap;
 use strum::IntoEnumIterator as _;
-use webapps::fl;
+use webapps::{
+    backup::Snapshot,
+    browser::{CookiePolicy, HeaderRule, NetworkSettings, WebRtcPolicy},
+    fl,
+    icon_pipeline::IconOverlay,
+    kiosk::{DEFAULT_EXIT_SHORTCUT, KioskConfig},
+    launcher::WebAppLauncher,
+    profile::Profile,
+    proxy::ProxyConfig,
+    site_data::SiteData,
+    tls::CertificatePin,
+};
 
 use crate::pages;
 
+/// Index of `policy` in the cookie policy dropdown.
+fn cookie_policy_idx(policy: CookiePolicy) -> usize {
+    CookiePolicy::ALL
+        .iter()
+        .position(|p| *p == policy)
+        .unwrap_or_default()
+}
+
+/// Index of `policy` in the WebRTC policy dropdown.
+fn webrtc_policy_idx(policy: WebRtcPolicy) -> usize {
+    WebRtcPolicy::ALL
+        .iter()
+        .position(|p| *p == policy)
+        .unwrap_or_default()
+}
+
+/// Index of `proxy` in the proxy dropdown.
+fn proxy_mode_idx(proxy: &ProxyConfig) -> usize {
+    match proxy {
+        ProxyConfig::System => 0,
+        ProxyConfig::Direct => 1,
+        ProxyConfig::Manual { .. } => 2,
+        ProxyConfig::Pac(_) => 3,
+    }
+}
+
 /// Filter a string to only contain digits and dots (for numeric input fields).
 fn filter_numeric(input: String) -> String {
     input
@@ -19,6 +56,28 @@ fn filter_numeric(input: String) -> String {
         .collect()
 }
 
+/// Minutes between automatic reloads, `None` for an empty or zero value.
+fn parse_minutes(value: &str) -> Option<u32> {
+    value.parse().ok().filter(|minutes| *minutes > 0)
+}
+
+fn minutes_text(minutes: Option<u32>) -> String {
+    minutes
+        .map(|minutes| minutes.to_string())
+        .unwrap_or_default()
+}
+
+/// Where a web app keeps its browser data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileChoice {
+    /// Nothing is kept between launches.
+    Temporary,
+    /// A profile used by this app only.
+    Own,
+    /// A profile that can be shared with other apps.
+    Shared(String),
+}
+
 #[derive(Debug, Clone)]
 pub struct AppEditor {
     pub app_browser: Option<webapps::browser::Browser>,
@@ -26,7 +85,12 @@ pub struct AppEditor {
     pub app_url: String,
     pub app_icon: String,
     pub app_category: webapps::Category,
-    pub app_persistent: bool,
+    pub app_profile: ProfileChoice,
+    /// Profiles other than the own profile of this app.
+    pub profiles: Vec<Profile>,
+    pub profile_options: Vec<String>,
+    /// Other apps sharing a webview with this app through the picked profile.
+    pub profile_mates: Vec<WebAppLauncher>,
     pub app_window_width: String,
     pub app_window_height: String,
     pub app_window_size: webapps::WindowSize,
@@ -42,6 +106,10 @@ pub struct AppEditor {
     pub app_user_agent: usize,
     pub app_custom_ua: String,
     pub user_agent_options: Vec<String>,
+    pub app_icon_overlay: usize,
+    pub app_overlay_text: String,
+    pub app_overlay_color: Option<String>,
+    pub icon_overlay_options: Vec<String>,
     pub app_allow_camera: bool,
     pub app_allow_microphone: bool,
     pub app_allow_geolocation: bool,
@@ -52,17 +120,45 @@ pub struct AppEditor {
     pub thumbnail_loading: bool,
     // #53, #60, #61: Privacy features
     pub app_content_blocking: bool,
-    pub app_block_cookies: bool,
-    pub app_block_webrtc: bool,
+    pub app_cookie_policy: usize,
+    pub cookie_policy_options: Vec<String>,
+    pub app_cookie_exceptions: String,
+    pub app_webrtc_policy: usize,
+    pub webrtc_policy_options: Vec<String>,
     // #54: Proxy
+    pub app_proxy_mode: usize,
+    pub proxy_mode_options: Vec<String>,
     pub app_proxy_url: String,
+    pub app_proxy_http: String,
+    pub app_proxy_https: String,
+    pub app_proxy_ignore: String,
+    pub app_proxy_pac: String,
+    /// Header rules being edited, including incomplete rows.
+    pub app_headers: Vec<HeaderRule>,
+    pub app_accept_language: String,
+    pub app_ca_bundle: String,
+    pub app_trusted_certificates: Vec<CertificatePin>,
+    pub app_client_certificate: String,
     // #55: Zoom
     pub app_zoom_level: String,
     // #56: Session restore
     pub app_restore_session: bool,
+    pub app_offline_copy: bool,
+    /// Minutes, empty or 0 to never reload.
+    pub app_reload_interval: String,
+    pub app_reload_on_resume: bool,
+    pub app_reload_on_focus: String,
+    pub app_kiosk: bool,
+    pub app_kiosk_exit: String,
+    pub app_kiosk_idle: String,
+    pub app_kiosk_domains: String,
     // #57: Usage statistics (read-only display)
     pub app_launch_count: u64,
     pub app_last_launched: Option<u64>,
+    /// Size of each kind of website data in the saved profile.
+    pub site_data: Vec<(SiteData, u64)>,
+    /// Backups of the saved profile, newest first.
+    pub snapshots: Vec<Snapshot>,
     // #59: Minimize to background
     pub app_minimize_to_background: bool,
     // #62: Auto dark mode
@@ -81,7 +177,10 @@ impl Default for AppEditor {
             app_url: String::new(),
             app_icon: String::new(),
             app_category: webapps::Category::default(),
-            app_persistent: false,
+            app_profile: ProfileChoice::Temporary,
+            profiles: Vec::new(),
+            profile_options: vec![fl!("profile-temporary"), fl!("profile-own")],
+            profile_mates: Vec::new(),
             app_window_width: webapps::DEFAULT_WINDOW_WIDTH.to_string(),
             app_window_height: webapps::DEFAULT_WINDOW_HEIGHT.to_string(),
             app_window_size: webapps::WindowSize::default(),
@@ -101,6 +200,15 @@ impl Default for AppEditor {
                 fl!("user-agent-mobile"),
                 fl!("user-agent-custom"),
             ],
+            app_icon_overlay: 0,
+            app_overlay_text: String::new(),
+            app_overlay_color: None,
+            icon_overlay_options: vec![
+                fl!("icon-overlay-none"),
+                fl!("icon-overlay-dot"),
+                fl!("icon-overlay-initial"),
+                fl!("icon-overlay-private"),
+            ],
             app_allow_camera: false,
             app_allow_microphone: false,
             app_allow_geolocation: false,
@@ -110,13 +218,46 @@ impl Default for AppEditor {
             thumbnail_handle: None,
             thumbnail_loading: false,
             app_content_blocking: false,
-            app_block_cookies: false,
-            app_block_webrtc: false,
+            app_cookie_policy: cookie_policy_idx(CookiePolicy::default()),
+            cookie_policy_options: vec![
+                fl!("cookie-policy-always"),
+                fl!("cookie-policy-no-third-party"),
+                fl!("cookie-policy-never"),
+            ],
+            app_cookie_exceptions: String::new(),
+            app_webrtc_policy: webrtc_policy_idx(WebRtcPolicy::default()),
+            webrtc_policy_options: vec![fl!("webrtc-disabled"), fl!("webrtc-enabled")],
+            app_proxy_mode: proxy_mode_idx(&ProxyConfig::default()),
+            proxy_mode_options: vec![
+                fl!("proxy-system"),
+                fl!("proxy-direct"),
+                fl!("proxy-manual"),
+                fl!("proxy-pac"),
+            ],
             app_proxy_url: String::new(),
+            app_proxy_http: String::new(),
+            app_proxy_https: String::new(),
+            app_proxy_ignore: String::new(),
+            app_proxy_pac: String::new(),
+            app_headers: Vec::new(),
+            app_accept_language: String::new(),
+            app_ca_bundle: String::new(),
+            app_trusted_certificates: Vec::new(),
+            app_client_certificate: String::new(),
             app_zoom_level: String::from("1.0"),
             app_restore_session: false,
+            app_offline_copy: false,
+            app_reload_interval: String::new(),
+            app_reload_on_resume: false,
+            app_reload_on_focus: String::new(),
+            app_kiosk: false,
+            app_kiosk_exit: DEFAULT_EXIT_SHORTCUT.to_string(),
+            app_kiosk_idle: String::new(),
+            app_kiosk_domains: String::new(),
             app_launch_count: 0,
             app_last_launched: None,
+            site_data: Vec::new(),
+            snapshots: Vec::new(),
             app_minimize_to_background: false,
             app_auto_dark_mode: false,
         }
@@ -130,7 +271,7 @@ pub enum Message {
     DownloadFavicon,
     Duplicate,
     FaviconResult(Option<String>),
-    PersistentProfile(bool),
+    ProfileSelect(usize),
     LaunchApp,
     OpenIconPicker,
     Title(String),
@@ -144,11 +285,22 @@ pub enum Message {
     CustomJs(String),
     UserAgentSelect(usize),
     CustomUserAgent(String),
+    IconOverlaySelect(usize),
+    IconOverlayText(String),
     AllowCamera(bool),
     AllowMicrophone(bool),
     AllowGeolocation(bool),
     AllowNotifications(bool),
     ClearAppData,
+    LoadSiteData,
+    SiteDataSizes(Vec<(SiteData, u64)>),
+    ClearSiteData(SiteData),
+    InspectSiteData,
+    Snapshots(Vec<Snapshot>),
+    CreateSnapshot,
+    RestoreSnapshot(Snapshot),
+    DeleteSnapshot(Snapshot),
+    PruneSnapshots,
     UrlSchemes(String),
     SiteTitleResult(Option<String>),
     ToggleAdvanced(bool),
@@ -156,11 +308,35 @@ pub enum Message {
     ThumbnailResult(Option<String>),
     ThumbnailLoaded(Option<widget::image::Handle>),
     ContentBlocking(bool),
-    BlockThirdPartyCookies(bool),
-    BlockWebRTC(bool),
+    CookiePolicySelect(usize),
+    CookieExceptions(String),
+    WebRtcPolicySelect(usize),
+    ProxyMode(usize),
     ProxyUrl(String),
+    ProxyHttp(String),
+    ProxyHttps(String),
+    ProxyIgnore(String),
+    ProxyPac(String),
+    AddHeader,
+    RemoveHeader(usize),
+    HeaderPattern(usize, String),
+    HeaderName(usize, String),
+    HeaderValue(usize, String),
+    AcceptLanguage(String),
+    CaBundle(String),
+    UntrustCertificate(usize),
+    ClientCertificate(String),
+    ForgetPasswords,
     ZoomLevel(String),
     RestoreSession(bool),
+    OfflineCopy(bool),
+    ReloadInterval(String),
+    ReloadOnResume(bool),
+    ReloadOnFocus(String),
+    Kiosk(bool),
+    KioskExit(String),
+    KioskIdle(String),
+    KioskDomains(String),
     MinimizeToBackground(bool),
     AutoDarkMode(bool),
 }
@@ -179,7 +355,11 @@ impl AppEditor {
         editor.app_url = launcher.browser.url.clone().unwrap_or_default();
         editor.app_icon = launcher.icon.clone();
         editor.app_category = launcher.category.clone();
-        editor.app_persistent = launcher.browser.profile.is_some();
+        editor.app_profile = match launcher.browser.profile.as_deref() {
+            None => ProfileChoice::Temporary,
+            Some(id) if id == launcher.browser.app_id.as_ref() => ProfileChoice::Own,
+            Some(id) => ProfileChoice::Shared(id.to_string()),
+        };
         editor.app_window_width = window_size.0.to_string();
         editor.app_window_height = window_size.1.to_string();
         editor.app_window_size = window_size;
@@ -204,6 +384,18 @@ impl AppEditor {
             _ => String::new(),
         };
 
+        editor.app_icon_overlay = match &launcher.icon_overlay {
+            None => 0,
+            Some(IconOverlay::Dot(_)) => 1,
+            Some(IconOverlay::Initial { .. }) => 2,
+            Some(IconOverlay::Private) => 3,
+        };
+        (editor.app_overlay_text, editor.app_overlay_color) = match &launcher.icon_overlay {
+            Some(IconOverlay::Dot(color)) => (String::new(), Some(color.clone())),
+            Some(IconOverlay::Initial { text, color }) => (text.clone(), Some(color.clone())),
+            _ => (String::new(), None),
+        };
+
         let perms = launcher.browser.permissions.clone().unwrap_or_default();
         editor.app_allow_camera = perms.allow_camera;
         editor.app_allow_microphone = perms.allow_microphone;
@@ -216,11 +408,40 @@ impl AppEditor {
             .unwrap_or_default();
 
         editor.app_content_blocking = launcher.browser.content_blocking.unwrap_or(false);
-        editor.app_block_cookies = launcher.browser.block_third_party_cookies.unwrap_or(false);
-        editor.app_block_webrtc = launcher.browser.block_webrtc.unwrap_or(false);
-        editor.app_proxy_url = launcher.browser.proxy_url.clone().unwrap_or_default();
+        editor.app_cookie_policy = cookie_policy_idx(launcher.browser.cookie_policy());
+        editor.app_cookie_exceptions = launcher
+            .browser
+            .cookie_exceptions
+            .as_ref()
+            .map(|domains| domains.join(", "))
+            .unwrap_or_default();
+        editor.app_webrtc_policy = webrtc_policy_idx(launcher.browser.webrtc_policy());
+        editor.set_proxy(launcher.browser.proxy());
+        editor.app_headers = launcher.browser.request_headers.clone().unwrap_or_default();
+        editor.app_accept_language = launcher
+            .browser
+            .accept_language
+            .as_ref()
+            .map(|languages| languages.join(", "))
+            .unwrap_or_default();
+        editor.app_ca_bundle = launcher.browser.ca_bundle.clone().unwrap_or_default();
+        editor.app_trusted_certificates = launcher
+            .browser
+            .trusted_certificates
+            .clone()
+            .unwrap_or_default();
+        editor.app_client_certificate = launcher
+            .browser
+            .client_certificate
+            .clone()
+            .unwrap_or_default();
         editor.app_zoom_level = launcher.browser.zoom_level.unwrap_or(1.0).to_string();
         editor.app_restore_session = launcher.browser.restore_session.unwrap_or(false);
+        editor.app_offline_copy = launcher.browser.offline_copy.unwrap_or(false);
+        editor.app_reload_interval = minutes_text(launcher.browser.reload_interval);
+        editor.app_reload_on_resume = launcher.browser.reload_on_resume.unwrap_or(false);
+        editor.app_reload_on_focus = minutes_text(launcher.browser.reload_on_focus);
+        editor.set_kiosk(launcher.browser.kiosk.as_ref());
         editor.app_launch_count = launcher.browser.launch_count.unwrap_or(0);
         editor.app_last_launched = launcher.browser.last_launched;
         editor.app_minimize_to_background = launcher.browser.minimize_to_background.unwrap_or(false);
@@ -251,8 +472,9 @@ impl AppEditor {
                 let url = self.app_url.clone();
                 if webapps::url_valid(&url) {
                     let url2 = url.clone();
+                    let name = self.app_title.clone();
                     let favicon_task = Task::perform(
-                        async move { webapps::download_favicon(&url).await },
+                        async move { webapps::site_icon(&url, &name).await },
                         |result| {
                             cosmic::Action::App(crate::pages::Message::Editor(
                                 Message::FaviconResult(result),
@@ -288,6 +510,11 @@ impl AppEditor {
                 // Keep browser config but clear app_id so a new one is generated on save
                 duplicate.app_browser = None;
                 duplicate.is_installed = false;
+                // Badge the copy so it can be told apart from the original in the dock
+                if duplicate.app_icon_overlay == 0 {
+                    duplicate.app_icon_overlay = 1;
+                }
+                duplicate.app_overlay_color = None;
                 // Preserve window settings from the original app
                 if let Some(browser) = &self.app_browser {
                     duplicate.app_window_decorations = browser.window_decorations.unwrap_or(true);
@@ -300,7 +527,6 @@ impl AppEditor {
                         duplicate.app_window_height = size.1.to_string();
                         duplicate.app_window_size = size.clone();
                     }
-                    duplicate.app_persistent = browser.profile.is_some();
                     duplicate.app_user_agent = match &browser.user_agent {
                         Some(webapps::browser::UserAgent::Default) | None => 0,
                         Some(webapps::browser::UserAgent::Mobile) => 1,
@@ -320,11 +546,32 @@ impl AppEditor {
                         .map(|schemes| schemes.join(", "))
                         .unwrap_or_default();
                     duplicate.app_content_blocking = browser.content_blocking.unwrap_or(false);
-                    duplicate.app_block_cookies = browser.block_third_party_cookies.unwrap_or(false);
-                    duplicate.app_block_webrtc = browser.block_webrtc.unwrap_or(false);
-                    duplicate.app_proxy_url = browser.proxy_url.clone().unwrap_or_default();
+                    duplicate.app_cookie_policy = cookie_policy_idx(browser.cookie_policy());
+                    duplicate.app_cookie_exceptions = browser
+                        .cookie_exceptions
+                        .as_ref()
+                        .map(|domains| domains.join(", "))
+                        .unwrap_or_default();
+                    duplicate.app_webrtc_policy = webrtc_policy_idx(browser.webrtc_policy());
+                    duplicate.set_proxy(browser.proxy());
+                    duplicate.app_headers = browser.request_headers.clone().unwrap_or_default();
+                    duplicate.app_accept_language = browser
+                        .accept_language
+                        .as_ref()
+                        .map(|languages| languages.join(", "))
+                        .unwrap_or_default();
+                    duplicate.app_ca_bundle = browser.ca_bundle.clone().unwrap_or_default();
+                    duplicate.app_trusted_certificates =
+                        browser.trusted_certificates.clone().unwrap_or_default();
+                    duplicate.app_client_certificate =
+                        browser.client_certificate.clone().unwrap_or_default();
                     duplicate.app_zoom_level = browser.zoom_level.unwrap_or(1.0).to_string();
                     duplicate.app_restore_session = browser.restore_session.unwrap_or(false);
+                    duplicate.app_offline_copy = browser.offline_copy.unwrap_or(false);
+                    duplicate.app_reload_interval = minutes_text(browser.reload_interval);
+                    duplicate.app_reload_on_resume = browser.reload_on_resume.unwrap_or(false);
+                    duplicate.app_reload_on_focus = minutes_text(browser.reload_on_focus);
+                    duplicate.set_kiosk(browser.kiosk.as_ref());
                     duplicate.app_minimize_to_background = browser.minimize_to_background.unwrap_or(false);
                     duplicate.app_auto_dark_mode = browser.auto_dark_mode.unwrap_or(false);
                 }
@@ -333,16 +580,12 @@ impl AppEditor {
                 });
             }
             Message::Done => {
-                let browser = if let Some(browser) = &self.app_browser {
+                let mut browser = if let Some(browser) = &self.app_browser {
                     browser.clone()
                 } else {
-                    let app_id = format!(
-                        "{}{}",
-                        self.app_title.replace(' ', ""),
-                        rng().random_range(1000..10000)
-                    );
+                    let app_id = webapps::browser::generate_app_id(&self.app_title);
 
-                    let mut browser = webapps::browser::Browser::new(&app_id, self.app_persistent);
+                    let mut browser = webapps::browser::Browser::new(&app_id, false);
                     browser.window_title = Some(self.app_title.clone());
                     browser.url = Some(self.app_url.clone());
                     browser.window_size = Some(self.app_window_size.clone());
@@ -376,11 +619,6 @@ impl AppEditor {
                         browser.url_schemes = Some(schemes);
                     }
                     browser.content_blocking = Some(self.app_content_blocking);
-                    browser.block_third_party_cookies = Some(self.app_block_cookies);
-                    browser.block_webrtc = Some(self.app_block_webrtc);
-                    if !self.app_proxy_url.is_empty() {
-                        browser.proxy_url = Some(self.app_proxy_url.clone());
-                    }
                     let zoom: f64 = self.app_zoom_level.parse().unwrap_or(1.0);
                     browser.zoom_level = Some(zoom.clamp(0.25, 5.0));
                     browser.restore_session = Some(self.app_restore_session);
@@ -388,6 +626,46 @@ impl AppEditor {
                     browser.auto_dark_mode = Some(self.app_auto_dark_mode);
                     browser
                 };
+                browser.profile = self.profile(browser.app_id.as_ref());
+                if let Some(policy) = CookiePolicy::ALL.get(self.app_cookie_policy) {
+                    browser.set_cookie_policy(*policy);
+                }
+                let exceptions = webapps::browser::parse_domains(&self.app_cookie_exceptions);
+                browser.cookie_exceptions = (!exceptions.is_empty()).then_some(exceptions);
+                if let Some(policy) = WebRtcPolicy::ALL.get(self.app_webrtc_policy) {
+                    browser.set_webrtc_policy(*policy);
+                }
+                let proxy = self.proxy();
+                if proxy.validate().is_err()
+                    || self.network_error().is_some()
+                    || self.headers_error().is_some()
+                    || self.ca_bundle_error().is_some()
+                    || self.client_certificate_error().is_some()
+                    || self.kiosk_exit_error().is_some()
+                {
+                    return Task::none();
+                }
+                browser.set_proxy(proxy);
+                let headers = self.headers();
+                browser.request_headers = (!headers.is_empty()).then_some(headers);
+                let languages = webapps::browser::parse_languages(&self.app_accept_language);
+                browser.accept_language = (!languages.is_empty()).then_some(languages);
+                let ca_bundle = self.app_ca_bundle.trim();
+                browser.ca_bundle = (!ca_bundle.is_empty()).then(|| ca_bundle.to_string());
+                browser.trusted_certificates = (!self.app_trusted_certificates.is_empty())
+                    .then(|| self.app_trusted_certificates.clone());
+                let client_certificate = self.app_client_certificate.trim();
+                browser.client_certificate =
+                    (!client_certificate.is_empty()).then(|| client_certificate.to_string());
+                browser.offline_copy = Some(self.app_offline_copy);
+                browser.reload_interval = parse_minutes(&self.app_reload_interval);
+                browser.reload_on_resume = Some(self.app_reload_on_resume);
+                browser.reload_on_focus = parse_minutes(&self.app_reload_on_focus);
+                browser.kiosk = self.app_kiosk.then(|| KioskConfig {
+                    exit_shortcut: self.app_kiosk_exit.trim().to_string(),
+                    idle_reset: parse_minutes(&self.app_kiosk_idle),
+                    allowed_domains: webapps::browser::parse_domains(&self.app_kiosk_domains),
+                });
 
                 if webapps::launcher::webapplauncher_is_valid(
                     &self.app_icon,
@@ -400,6 +678,7 @@ impl AppEditor {
                         name: self.app_title.clone(),
                         icon: self.app_icon.clone(),
                         category: self.app_category.clone(),
+                        icon_overlay: self.icon_overlay(browser.app_id.as_ref()),
                     };
 
                     return task::future(async move {
@@ -413,8 +692,16 @@ impl AppEditor {
                     return Task::none();
                 }
             }
-            Message::PersistentProfile(flag) => {
-                self.app_persistent = flag;
+            Message::ProfileSelect(idx) => {
+                self.app_profile = match idx {
+                    0 => ProfileChoice::Temporary,
+                    1 => ProfileChoice::Own,
+                    idx => match self.profiles.get(idx - 2) {
+                        Some(profile) => ProfileChoice::Shared(profile.id.clone()),
+                        None => return Task::none(),
+                    },
+                };
+                self.load_profile_mates();
             }
             Message::LaunchApp => {
                 if let Some(browser) = &self.app_browser {
@@ -457,6 +744,12 @@ impl AppEditor {
             Message::CustomUserAgent(ua) => {
                 self.app_custom_ua = ua;
             }
+            Message::IconOverlaySelect(idx) => {
+                self.app_icon_overlay = idx;
+            }
+            Message::IconOverlayText(text) => {
+                self.app_overlay_text = text.chars().take(1).collect();
+            }
             Message::AllowCamera(v) => {
                 self.app_allow_camera = v;
             }
@@ -470,34 +763,196 @@ impl AppEditor {
                 self.app_allow_notifications = v;
             }
             Message::ClearAppData => {
-                if let Some(browser) = &self.app_browser {
-                    let app_id = browser.app_id.as_ref().to_string();
+                if let Some(profile) = self.app_browser.as_ref().and_then(|b| b.profile.clone()) {
+                    return task::future(
+                        async move { crate::pages::Message::ClearAppData(profile) },
+                    );
+                }
+            }
+            Message::LoadSiteData => {
+                if let Some(profile) = self.app_browser.as_ref().and_then(|b| b.profile.clone()) {
+                    let id = profile.clone();
+                    return Task::batch([
+                        Task::perform(
+                            async move {
+                                tokio::task::spawn_blocking(move || {
+                                    webapps::site_data::sizes(&profile)
+                                })
+                                .await
+                                .unwrap_or_default()
+                            },
+                            |sizes| {
+                                cosmic::Action::App(crate::pages::Message::Editor(
+                                    Message::SiteDataSizes(sizes),
+                                ))
+                            },
+                        ),
+                        Task::perform(
+                            async move {
+                                tokio::task::spawn_blocking(move || webapps::backup::list(&id))
+                                    .await
+                                    .unwrap_or_default()
+                            },
+                            |snapshots| {
+                                cosmic::Action::App(crate::pages::Message::Editor(
+                                    Message::Snapshots(snapshots),
+                                ))
+                            },
+                        ),
+                    ]);
+                }
+            }
+            Message::SiteDataSizes(sizes) => {
+                self.site_data = sizes;
+            }
+            Message::Snapshots(snapshots) => {
+                self.snapshots = snapshots;
+            }
+            Message::CreateSnapshot => {
+                if let Some(profile) = self.app_browser.as_ref().and_then(|b| b.profile.clone()) {
+                    return task::future(
+                        async move { crate::pages::Message::CreateSnapshot(profile) },
+                    );
+                }
+            }
+            Message::RestoreSnapshot(snapshot) => {
+                return task::future(async move {
+                    crate::pages::Message::ConfirmRestoreSnapshot(snapshot)
+                });
+            }
+            Message::DeleteSnapshot(snapshot) => {
+                return task::future(
+                    async move { crate::pages::Message::DeleteSnapshot(snapshot) },
+                );
+            }
+            Message::PruneSnapshots => {
+                if let Some(profile) = self.app_browser.as_ref().and_then(|b| b.profile.clone()) {
+                    return task::future(
+                        async move { crate::pages::Message::PruneSnapshots(profile) },
+                    );
+                }
+            }
+            Message::InspectSiteData => {
+                if let Some(profile) = self.app_browser.as_ref().and_then(|b| b.profile.clone()) {
                     return task::future(
-                        async move { crate::pages::Message::ClearAppData(app_id) },
+                        async move { crate::pages::Message::InspectSiteData(profile) },
                     );
                 }
             }
+            Message::ClearSiteData(data) => {
+                if let Some(profile) = self.app_browser.as_ref().and_then(|b| b.profile.clone()) {
+                    return task::future(async move {
+                        crate::pages::Message::ClearSiteData(profile, data)
+                    });
+                }
+            }
             Message::UrlSchemes(schemes) => {
                 self.app_url_schemes = schemes;
             }
             Message::ContentBlocking(flag) => {
                 self.app_content_blocking = flag;
             }
-            Message::BlockThirdPartyCookies(flag) => {
-                self.app_block_cookies = flag;
+            Message::CookiePolicySelect(idx) => {
+                self.app_cookie_policy = idx;
+            }
+            Message::CookieExceptions(domains) => {
+                self.app_cookie_exceptions = domains;
             }
-            Message::BlockWebRTC(flag) => {
-                self.app_block_webrtc = flag;
+            Message::WebRtcPolicySelect(idx) => {
+                self.app_webrtc_policy = idx;
+            }
+            Message::ProxyMode(idx) => {
+                self.app_proxy_mode = idx;
             }
             Message::ProxyUrl(url) => {
                 self.app_proxy_url = url;
             }
+            Message::ProxyHttp(url) => {
+                self.app_proxy_http = url;
+            }
+            Message::ProxyHttps(url) => {
+                self.app_proxy_https = url;
+            }
+            Message::ProxyIgnore(hosts) => {
+                self.app_proxy_ignore = hosts;
+            }
+            Message::ProxyPac(url) => {
+                self.app_proxy_pac = url;
+            }
+            Message::AddHeader => {
+                self.app_headers.push(HeaderRule::default());
+            }
+            Message::RemoveHeader(idx) => {
+                if idx < self.app_headers.len() {
+                    self.app_headers.remove(idx);
+                }
+            }
+            Message::HeaderPattern(idx, pattern) => {
+                if let Some(rule) = self.app_headers.get_mut(idx) {
+                    rule.pattern = pattern;
+                }
+            }
+            Message::HeaderName(idx, name) => {
+                if let Some(rule) = self.app_headers.get_mut(idx) {
+                    rule.name = name;
+                }
+            }
+            Message::HeaderValue(idx, value) => {
+                if let Some(rule) = self.app_headers.get_mut(idx) {
+                    rule.value = value;
+                }
+            }
+            Message::AcceptLanguage(languages) => {
+                self.app_accept_language = languages;
+            }
+            Message::CaBundle(path) => {
+                self.app_ca_bundle = path;
+            }
+            Message::UntrustCertificate(idx) => {
+                if idx < self.app_trusted_certificates.len() {
+                    self.app_trusted_certificates.remove(idx);
+                }
+            }
+            Message::ClientCertificate(path) => {
+                self.app_client_certificate = path;
+            }
+            Message::ForgetPasswords => {
+                if let Some(app_id) = self.app_browser.as_ref().map(|b| b.app_id.id.clone()) {
+                    return task::future(
+                        async move { crate::pages::Message::ForgetPasswords(app_id) },
+                    );
+                }
+            }
             Message::ZoomLevel(level) => {
                 self.app_zoom_level = filter_numeric(level);
             }
             Message::RestoreSession(flag) => {
                 self.app_restore_session = flag;
             }
+            Message::OfflineCopy(flag) => {
+                self.app_offline_copy = flag;
+            }
+            Message::ReloadInterval(minutes) => {
+                self.app_reload_interval = minutes.chars().filter(char::is_ascii_digit).collect();
+            }
+            Message::ReloadOnResume(flag) => {
+                self.app_reload_on_resume = flag;
+            }
+            Message::ReloadOnFocus(minutes) => {
+                self.app_reload_on_focus = minutes.chars().filter(char::is_ascii_digit).collect();
+            }
+            Message::Kiosk(flag) => {
+                self.app_kiosk = flag;
+            }
+            Message::KioskExit(shortcut) => {
+                self.app_kiosk_exit = shortcut;
+            }
+            Message::KioskIdle(minutes) => {
+                self.app_kiosk_idle = minutes.chars().filter(char::is_ascii_digit).collect();
+            }
+            Message::KioskDomains(domains) => {
+                self.app_kiosk_domains = domains;
+            }
             Message::MinimizeToBackground(flag) => {
                 self.app_minimize_to_background = flag;
             }
@@ -556,9 +1011,212 @@ impl AppEditor {
         Task::none()
     }
 
+    /// The icon badge picked in the editor. Apps without a stored colour get the
+    /// default colour of `app_id`.
+    fn icon_overlay(&self, app_id: &str) -> Option<IconOverlay> {
+        let color = self
+            .app_overlay_color
+            .clone()
+            .unwrap_or_else(|| IconOverlay::color_for(app_id));
+
+        match self.app_icon_overlay {
+            1 => Some(IconOverlay::Dot(color)),
+            2 => Some(IconOverlay::Initial {
+                text: if self.app_overlay_text.is_empty() {
+                    self.app_title.clone()
+                } else {
+                    self.app_overlay_text.clone()
+                },
+                color,
+            }),
+            3 => Some(IconOverlay::Private),
+            _ => None,
+        }
+    }
+
+    /// Offer `profiles` in the profile dropdown, leaving out the own profile
+    /// of this app.
+    pub fn load_profiles(&mut self, profiles: &[Profile]) {
+        let app_id = self.app_browser.as_ref().map(|b| b.app_id.as_ref());
+
+        self.profiles = profiles
+            .iter()
+            .filter(|p| Some(p.id.as_str()) != app_id)
+            .cloned()
+            .collect();
+        self.profile_options = [fl!("profile-temporary"), fl!("profile-own")]
+            .into_iter()
+            .chain(self.profiles.iter().map(|p| p.name.clone()))
+            .collect();
+        self.load_profile_mates();
+    }
+
+    /// Look up the apps sharing a webview with this app through the picked
+    /// profile.
+    fn load_profile_mates(&mut self) {
+        let app_id = self
+            .app_browser
+            .as_ref()
+            .map(|b| b.app_id.as_ref().to_string())
+            .unwrap_or_default();
+
+        self.profile_mates = self
+            .profile(&app_id)
+            .map(|id| webapps::profile::process_mates(&id, &app_id))
+            .unwrap_or_default();
+    }
+
+    fn profile_idx(&self) -> Option<usize> {
+        match &self.app_profile {
+            ProfileChoice::Temporary => Some(0),
+            ProfileChoice::Own => Some(1),
+            ProfileChoice::Shared(id) => self
+                .profiles
+                .iter()
+                .position(|p| &p.id == id)
+                .map(|idx| idx + 2),
+        }
+    }
+
+    /// The profile ID picked in the editor for the app `app_id`.
+    fn profile(&self, app_id: &str) -> Option<String> {
+        match &self.app_profile {
+            ProfileChoice::Temporary => None,
+            ProfileChoice::Own => Some(app_id.to_string()),
+            ProfileChoice::Shared(id) => Some(id.clone()),
+        }
+    }
+
+    /// The proxy entered in the editor, empty fields are left out.
+    fn proxy(&self) -> ProxyConfig {
+        let optional = |value: &str| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+
+        match self.app_proxy_mode {
+            1 => ProxyConfig::Direct,
+            2 => ProxyConfig::Manual {
+                default: optional(&self.app_proxy_url),
+                http: optional(&self.app_proxy_http),
+                https: optional(&self.app_proxy_https),
+                ignore_hosts: webapps::proxy::parse_ignore_hosts(&self.app_proxy_ignore),
+            },
+            3 => ProxyConfig::Pac(self.app_proxy_pac.trim().to_string()),
+            _ => ProxyConfig::System,
+        }
+    }
+
+    /// Why the proxy and languages cannot be used with the other apps of the
+    /// profile, if they cannot. They run in one webview, which has a single
+    /// network session.
+    fn network_error(&self) -> Option<String> {
+        if self.app_private_mode {
+            return None;
+        }
+
+        let languages = webapps::browser::parse_languages(&self.app_accept_language);
+        let settings = NetworkSettings {
+            proxy: self.proxy(),
+            accept_language: (!languages.is_empty()).then_some(languages),
+        };
+        webapps::profile::network_conflict(&self.profile_mates, &settings).map(|app| {
+            fl!(
+                "warning-shared-network",
+                HashMap::from([("app", app.name.as_str())])
+            )
+        })
+    }
+
+    /// The header rules entered in the editor, rows without a name are left
+    /// out.
+    fn headers(&self) -> Vec<HeaderRule> {
+        self.app_headers
+            .iter()
+            .filter(|rule| !rule.name.trim().is_empty())
+            .map(|rule| HeaderRule {
+                pattern: rule.pattern.trim().to_string(),
+                name: rule.name.trim().to_string(),
+                value: rule.value.trim().to_string(),
+            })
+            .collect()
+    }
+
+    /// Why the header rules cannot be saved, if they cannot.
+    fn headers_error(&self) -> Option<String> {
+        self.headers()
+            .iter()
+            .find_map(|rule| rule.validate().err())
+            .map(|e| e.to_string())
+    }
+
+    /// Why the CA bundle cannot be used, if it cannot.
+    fn ca_bundle_error(&self) -> Option<String> {
+        let path = self.app_ca_bundle.trim();
+        if path.is_empty() {
+            return None;
+        }
+
+        webapps::tls::validate_ca_bundle(path)
+            .err()
+            .map(|e| e.to_string())
+    }
+
+    /// Why the client certificate cannot be used, if it cannot.
+    fn client_certificate_error(&self) -> Option<String> {
+        let path = self.app_client_certificate.trim();
+        if path.is_empty() {
+            return None;
+        }
+
+        webapps::tls::read_client_certificate(path)
+            .err()
+            .map(|e| e.to_string())
+    }
+
+    /// Why the kiosk exit shortcut cannot be used, if it cannot.
+    fn kiosk_exit_error(&self) -> Option<String> {
+        if !self.app_kiosk {
+            return None;
+        }
+
+        webapps::kiosk::Shortcut::parse(&self.app_kiosk_exit)
+            .err()
+            .map(|e| e.to_string())
+    }
+
+    /// Fill the kiosk fields from `kiosk`.
+    fn set_kiosk(&mut self, kiosk: Option<&KioskConfig>) {
+        let config = kiosk.cloned().unwrap_or_default();
+        self.app_kiosk = kiosk.is_some();
+        self.app_kiosk_exit = config.exit_shortcut;
+        self.app_kiosk_idle = minutes_text(config.idle_reset);
+        self.app_kiosk_domains = config.allowed_domains.join(", ");
+    }
+
+    /// Fill the proxy fields from `proxy`.
+    fn set_proxy(&mut self, proxy: ProxyConfig) {
+        self.app_proxy_mode = proxy_mode_idx(&proxy);
+        match proxy {
+            ProxyConfig::Manual {
+                default,
+                http,
+                https,
+                ignore_hosts,
+            } => {
+                self.app_proxy_url = default.unwrap_or_default();
+                self.app_proxy_http = http.unwrap_or_default();
+                self.app_proxy_https = https.unwrap_or_default();
+                self.app_proxy_ignore = ignore_hosts.join(", ");
+            }
+            ProxyConfig::Pac(url) => self.app_proxy_pac = url,
+            ProxyConfig::Direct | ProxyConfig::System => {}
+        }
+    }
+
     pub fn update_icon(&mut self, icon: Option<webapps::Icon>) {
         if let Some(icon) = icon {
-            self.app_icon = icon.path.clone();
+            self.app_icon = icon.name.clone().unwrap_or_else(|| icon.path.clone());
             self.selected_icon = Some(icon);
         }
     }
@@ -710,9 +1368,12 @@ impl AppEditor {
                             ),
                         ))
                         .add(widget::settings::item(
-                            fl!("persistent-profile"),
-                            widget::toggler(self.app_persistent)
-                                .on_toggle(Message::PersistentProfile),
+                            fl!("profile"),
+                            widget::dropdown(
+                                &self.profile_options,
+                                self.profile_idx(),
+                                Message::ProfileSelect,
+                            ),
                         ))
                         .add(widget::settings::item(
                             fl!("window-size"),
@@ -737,6 +1398,24 @@ impl AppEditor {
                             fl!("decorations"),
                             widget::toggler(self.app_window_decorations)
                                 .on_toggle(Message::WindowDecorations),
+                        ))
+                        .add(widget::settings::item(
+                            fl!("icon-overlay"),
+                            widget::row()
+                                .spacing(8)
+                                .push_maybe((self.app_icon_overlay == 2).then(|| {
+                                    widget::text_input(
+                                        fl!("icon-overlay-letter"),
+                                        &self.app_overlay_text,
+                                    )
+                                    .width(Length::Fixed(64.0))
+                                    .on_input(Message::IconOverlayText)
+                                }))
+                                .push(widget::dropdown(
+                                    &self.icon_overlay_options,
+                                    Some(self.app_icon_overlay),
+                                    Message::IconOverlaySelect,
+                                )),
                         )),
                 )
                 // Advanced settings toggle
@@ -835,23 +1514,110 @@ impl AppEditor {
                                 .on_toggle(Message::ContentBlocking),
                         ))
                         .add(widget::settings::item(
-                            fl!("block-third-party-cookies"),
-                            widget::toggler(self.app_block_cookies)
-                                .on_toggle(Message::BlockThirdPartyCookies),
-                        ))
-                        .add(widget::settings::item(
-                            fl!("block-webrtc"),
-                            widget::toggler(self.app_block_webrtc)
-                                .on_toggle(Message::BlockWebRTC),
+                            fl!("cookie-policy"),
+                            widget::dropdown(
+                                &self.cookie_policy_options,
+                                Some(self.app_cookie_policy),
+                                Message::CookiePolicySelect,
+                            ),
                         ))
                         .add(widget::settings::item(
-                            fl!("proxy-url"),
+                            fl!("cookie-exceptions"),
                             widget::text_input(
-                                fl!("proxy-url-placeholder"),
-                                &self.app_proxy_url,
+                                fl!("cookie-exceptions-placeholder"),
+                                &self.app_cookie_exceptions,
                             )
-                            .on_input(Message::ProxyUrl),
+                            .on_input(Message::CookieExceptions),
+                        ))
+                        .add(widget::settings::item(
+                            fl!("webrtc"),
+                            widget::column()
+                                .spacing(4)
+                                .push(widget::dropdown(
+                                    &self.webrtc_policy_options,
+                                    Some(self.app_webrtc_policy),
+                                    Message::WebRtcPolicySelect,
+                                ))
+                                .push(widget::text::caption(
+                                    match WebRtcPolicy::ALL.get(self.app_webrtc_policy) {
+                                        Some(WebRtcPolicy::Disabled) => {
+                                            fl!("webrtc-disabled-description")
+                                        }
+                                        _ => fl!("webrtc-enabled-description"),
+                                    },
+                                )),
                         ))
+                        .add(widget::settings::item(
+                            fl!("proxy"),
+                            widget::dropdown(
+                                &self.proxy_mode_options,
+                                Some(self.app_proxy_mode),
+                                Message::ProxyMode,
+                            ),
+                        ));
+
+                    if self.app_proxy_mode == 2 {
+                        advanced = advanced
+                            .add(widget::settings::item(
+                                fl!("proxy-url"),
+                                widget::text_input(
+                                    fl!("proxy-url-placeholder"),
+                                    &self.app_proxy_url,
+                                )
+                                .on_input(Message::ProxyUrl),
+                            ))
+                            .add(widget::settings::item(
+                                fl!("proxy-http"),
+                                widget::text_input(
+                                    fl!("proxy-url-placeholder"),
+                                    &self.app_proxy_http,
+                                )
+                                .on_input(Message::ProxyHttp),
+                            ))
+                            .add(widget::settings::item(
+                                fl!("proxy-https"),
+                                widget::text_input(
+                                    fl!("proxy-url-placeholder"),
+                                    &self.app_proxy_https,
+                                )
+                                .on_input(Message::ProxyHttps),
+                            ))
+                            .add(widget::settings::item(
+                                fl!("proxy-ignore"),
+                                widget::text_input(
+                                    fl!("proxy-ignore-placeholder"),
+                                    &self.app_proxy_ignore,
+                                )
+                                .on_input(Message::ProxyIgnore),
+                            ));
+                    } else if self.app_proxy_mode == 3 {
+                        advanced = advanced
+                            .add(widget::settings::item(
+                                fl!("proxy-pac-url"),
+                                widget::text_input(
+                                    fl!("proxy-pac-url-placeholder"),
+                                    &self.app_proxy_pac,
+                                )
+                                .on_input(Message::ProxyPac),
+                            ))
+                            .add(widget::text::caption(fl!("proxy-pac-description")));
+                    }
+
+                    if let Err(e) = self.proxy().validate() {
+                        advanced = advanced.add(
+                            widget::text::caption(fl!(
+                                "warning-proxy",
+                                HashMap::from([("error", e.to_string().as_str())])
+                            ))
+                            .class(style::Text::Accent),
+                        );
+                    }
+                    if let Some(error) = self.network_error() {
+                        advanced =
+                            advanced.add(widget::text::caption(error).class(style::Text::Accent));
+                    }
+
+                    advanced = advanced
                         .add(widget::settings::item(
                             fl!("zoom-level"),
                             widget::text_input(
@@ -865,6 +1631,25 @@ impl AppEditor {
                             widget::toggler(self.app_restore_session)
                                 .on_toggle(Message::RestoreSession),
                         ))
+                        .add(widget::settings::item(
+                            fl!("offline-copy"),
+                            widget::toggler(self.app_offline_copy).on_toggle(Message::OfflineCopy),
+                        ))
+                        .add(widget::settings::item(
+                            fl!("reload-interval"),
+                            widget::text_input(fl!("reload-never"), &self.app_reload_interval)
+                                .on_input(Message::ReloadInterval),
+                        ))
+                        .add(widget::settings::item(
+                            fl!("reload-on-resume"),
+                            widget::toggler(self.app_reload_on_resume)
+                                .on_toggle(Message::ReloadOnResume),
+                        ))
+                        .add(widget::settings::item(
+                            fl!("reload-on-focus"),
+                            widget::text_input(fl!("reload-never"), &self.app_reload_on_focus)
+                                .on_input(Message::ReloadOnFocus),
+                        ))
                         .add(widget::settings::item(
                             fl!("minimize-to-background"),
                             widget::toggler(self.app_minimize_to_background)
@@ -897,18 +1682,266 @@ impl AppEditor {
                 } else {
                     None
                 })
+                .push_maybe(if self.show_advanced {
+                    let mut section = widget::settings::section()
+                        .title(fl!("request-headers"))
+                        .add(widget::settings::item(
+                            fl!("accept-language"),
+                            widget::text_input(
+                                fl!("accept-language-placeholder"),
+                                &self.app_accept_language,
+                            )
+                            .on_input(Message::AcceptLanguage),
+                        ));
+
+                    if let Some(error) = self.network_error() {
+                        section =
+                            section.add(widget::text::caption(error).class(style::Text::Accent));
+                    }
+
+                    for (idx, rule) in self.app_headers.iter().enumerate() {
+                        section = section.add(widget::settings::item_row(vec![
+                            widget::text_input(fl!("header-pattern"), &rule.pattern)
+                                .on_input(move |pattern| Message::HeaderPattern(idx, pattern))
+                                .width(Length::FillPortion(3))
+                                .into(),
+                            widget::text_input(fl!("header-name"), &rule.name)
+                                .on_input(move |name| Message::HeaderName(idx, name))
+                                .width(Length::FillPortion(2))
+                                .into(),
+                            widget::text_input(fl!("header-value"), &rule.value)
+                                .on_input(move |value| Message::HeaderValue(idx, value))
+                                .width(Length::FillPortion(2))
+                                .into(),
+                            widget::button::destructive(fl!("delete"))
+                                .on_press(Message::RemoveHeader(idx))
+                                .into(),
+                        ]));
+                    }
+
+                    if let Some(error) = self.headers_error() {
+                        section = section.add(
+                            widget::text::caption(fl!(
+                                "warning-header",
+                                HashMap::from([("error", error.as_str())])
+                            ))
+                            .class(style::Text::Accent),
+                        );
+                    }
+
+                    section = section
+                        .add(widget::settings::item(
+                            fl!("request-headers-description"),
+                            widget::button::standard(fl!("add-header"))
+                                .on_press(Message::AddHeader),
+                        ))
+                        .add(widget::text::caption(fl!("request-headers-limits")));
+
+                    Some(section)
+                } else {
+                    None
+                })
+                .push_maybe(if self.show_advanced {
+                    let ca_bundle =
+                        widget::text_input(fl!("ca-bundle-placeholder"), &self.app_ca_bundle)
+                            .on_input(Message::CaBundle);
+                    let mut section = widget::settings::section()
+                        .title(fl!("certificates"))
+                        .add(widget::settings::item(fl!("ca-bundle"), ca_bundle));
+
+                    if let Some(error) = self.ca_bundle_error() {
+                        section = section.add(
+                            widget::text::caption(fl!(
+                                "warning-ca-bundle",
+                                HashMap::from([("error", error.as_str())])
+                            ))
+                            .class(style::Text::Accent),
+                        );
+                    }
+
+                    if self.app_trusted_certificates.is_empty() {
+                        section = section.add(widget::settings::item_row(vec![
+                            widget::text::body(fl!("trusted-certificates-empty")).into(),
+                        ]));
+                    }
+                    for (idx, pin) in self.app_trusted_certificates.iter().enumerate() {
+                        section = section.add(widget::settings::item(
+                            &pin.host,
+                            widget::row()
+                                .spacing(8)
+                                .align_y(Vertical::Center)
+                                .push(widget::text::caption(&pin.sha256))
+                                .push(
+                                    widget::button::destructive(fl!("delete"))
+                                        .on_press(Message::UntrustCertificate(idx)),
+                                ),
+                        ));
+                    }
+
+                    let client_certificate = widget::text_input(
+                        fl!("client-certificate-placeholder"),
+                        &self.app_client_certificate,
+                    )
+                    .on_input(Message::ClientCertificate);
+                    section = section.add(widget::settings::item(
+                        fl!("client-certificate"),
+                        client_certificate,
+                    ));
+                    if let Some(error) = self.client_certificate_error() {
+                        section = section.add(
+                            widget::text::caption(fl!(
+                                "warning-client-certificate",
+                                HashMap::from([("error", error.as_str())])
+                            ))
+                            .class(style::Text::Accent),
+                        );
+                    }
+
+                    if self.is_installed {
+                        section = section.add(widget::settings::item(
+                            fl!("saved-passwords"),
+                            widget::button::destructive(fl!("forget-passwords"))
+                                .on_press(Message::ForgetPasswords),
+                        ));
+                    }
+
+                    Some(section)
+                } else {
+                    None
+                })
+                .push_maybe(if self.show_advanced {
+                    let mut section = widget::settings::section().title(fl!("kiosk")).add(
+                        widget::settings::item(
+                            fl!("kiosk-mode"),
+                            widget::toggler(self.app_kiosk).on_toggle(Message::Kiosk),
+                        ),
+                    );
+
+                    if self.app_kiosk {
+                        section = section
+                            .add(widget::settings::item(
+                                fl!("kiosk-exit"),
+                                widget::text_input(DEFAULT_EXIT_SHORTCUT, &self.app_kiosk_exit)
+                                    .on_input(Message::KioskExit),
+                            ))
+                            .add(widget::settings::item(
+                                fl!("kiosk-idle"),
+                                widget::text_input(fl!("kiosk-idle-never"), &self.app_kiosk_idle)
+                                    .on_input(Message::KioskIdle),
+                            ))
+                            .add(widget::settings::item(
+                                fl!("kiosk-domains"),
+                                widget::text_input(
+                                    fl!("kiosk-domains-placeholder"),
+                                    &self.app_kiosk_domains,
+                                )
+                                .on_input(Message::KioskDomains),
+                            ));
+                    }
+                    if let Some(error) = self.kiosk_exit_error() {
+                        section = section.add(
+                            widget::text::caption(fl!(
+                                "warning-kiosk-exit",
+                                HashMap::from([("error", error.as_str())])
+                            ))
+                            .class(style::Text::Accent),
+                        );
+                    }
+
+                    Some(section)
+                } else {
+                    None
+                })
+                .push_maybe(if self.is_installed && !self.site_data.is_empty() {
+                    let mut section = widget::settings::section().title(fl!("site-data"));
+                    for (data, size) in &self.site_data {
+                        section = section.add(widget::settings::item(
+                            data.name(),
+                            widget::row()
+                                .spacing(8)
+                                .align_y(Vertical::Center)
+                                .push(widget::text::body(webapps::format_bytes(*size)))
+                                .push(
+                                    widget::button::standard(fl!("site-data-clear"))
+                                        .on_press(Message::ClearSiteData(*data)),
+                                ),
+                        ));
+                    }
+
+                    section = section.add(widget::settings::item(
+                        fl!("site-data-inspector"),
+                        widget::button::standard(fl!("inspect-site-data"))
+                            .on_press(Message::InspectSiteData),
+                    ));
+
+                    Some(section)
+                } else {
+                    None
+                })
+                .push_maybe(if self.is_installed && !self.site_data.is_empty() {
+                    let mut section = widget::settings::section().title(fl!("backups"));
+                    section = section.add(widget::settings::item(
+                        fl!("back-up-profile"),
+                        widget::button::standard(fl!("back-up")).on_press(Message::CreateSnapshot),
+                    ));
+
+                    if self.snapshots.is_empty() {
+                        section = section.add(widget::settings::item_row(vec![
+                            widget::text::body(fl!("backups-empty")).into(),
+                        ]));
+                    }
+                    for snapshot in &self.snapshots {
+                        section = section.add(widget::settings::item(
+                            webapps::format_timestamp(snapshot.created),
+                            widget::row()
+                                .spacing(8)
+                                .align_y(Vertical::Center)
+                                .push(widget::text::body(webapps::format_bytes(snapshot.size)))
+                                .push(
+                                    widget::button::standard(fl!("restore-backup"))
+                                        .on_press(Message::RestoreSnapshot(snapshot.clone())),
+                                )
+                                .push(
+                                    widget::button::destructive(fl!("delete"))
+                                        .on_press(Message::DeleteSnapshot(snapshot.clone())),
+                                ),
+                        ));
+                    }
+
+                    if self.snapshots.len() > webapps::backup::DEFAULT_KEEP {
+                        let keep = webapps::backup::DEFAULT_KEEP.to_string();
+                        section = section.add(widget::settings::item(
+                            fl!("prune-backups"),
+                            widget::button::standard(fl!(
+                                "prune-backups-keep",
+                                HashMap::from([("count", keep.as_str())])
+                            ))
+                            .on_press(Message::PruneSnapshots),
+                        ));
+                    }
+
+                    Some(section)
+                } else {
+                    None
+                })
                 .push(
                     widget::row()
                         .spacing(8)
                         .push(widget::horizontal_space())
-                        .push_maybe(if self.is_installed && self.app_persistent {
-                            Some(
-                                widget::button::destructive(fl!("clear-data"))
-                                    .on_press(Message::ClearAppData),
-                            )
-                        } else {
-                            None
-                        })
+                        .push_maybe(
+                            if self
+                                .app_browser
+                                .as_ref()
+                                .is_some_and(|b| b.profile.is_some())
+                            {
+                                Some(
+                                    widget::button::destructive(fl!("clear-data"))
+                                        .on_press(Message::ClearAppData),
+                                )
+                            } else {
+                                None
+                            },
+                        )
                         .push_maybe(if !self.is_installed {
                             None
                         } else {
@@ -931,7 +1964,13 @@ impl AppEditor {
                                 &self.app_title,
                                 &Some(self.app_url.clone()),
                                 &self.app_category,
-                            ) {
+                            ) && self.proxy().validate().is_ok()
+                                && self.network_error().is_none()
+                                && self.headers_error().is_none()
+                                && self.ca_bundle_error().is_none()
+                                && self.client_certificate_error().is_none()
+                                && self.kiosk_exit_error().is_none()
+                            {
                                 Some(Message::Done)
                             } else {
                                 None
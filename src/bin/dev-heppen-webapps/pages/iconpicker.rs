@@ -103,7 +103,15 @@ impl IconPicker {
                     .on_press(Message::SetIcon(Some(ico.clone())))
                     .class(theme::Button::Icon),
             };
-            icons.push(btn.into());
+            let name = ico.name.clone().unwrap_or_else(|| {
+                std::path::Path::new(&ico.path)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            });
+            icons.push(
+                widget::tooltip(btn, widget::text(name), widget::tooltip::Position::Bottom).into(),
+            );
         }
 
         let icons_input = widget::text_input(fl!("icon-name-to-find"), &self.icon_searching)
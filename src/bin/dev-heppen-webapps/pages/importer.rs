@@ -0,0 +1,200 @@
+use std::{collections::HashMap, sync::Arc};
+
+use cosmic::{
+    Element, Task,
+    action::Action,
+    iced::Length,
+    task, theme,
+    widget::{self},
+};
+use webapps::{
+    bundle::Bundle,
+    fl,
+    launcher::{ConflictStrategy, ImportOutcome, ImportResult, PlannedImport},
+    profile::Profile,
+};
+
+use crate::pages;
+
+const STRATEGIES: [ConflictStrategy; 3] = [
+    ConflictStrategy::Skip,
+    ConflictStrategy::Overwrite,
+    ConflictStrategy::Rename,
+];
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    ConflictStrategy(usize),
+    Confirm,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportPreview {
+    pub plan: Vec<PlannedImport>,
+    pub results: Option<Vec<ImportResult>>,
+    pub importing: bool,
+    bundle: Option<Arc<Bundle>>,
+    profiles: Vec<Profile>,
+    strategies: Vec<String>,
+    strategy_idx: usize,
+}
+
+impl ImportPreview {
+    pub fn new(plan: Vec<PlannedImport>, bundle: Option<Arc<Bundle>>) -> Self {
+        Self {
+            plan,
+            results: None,
+            importing: false,
+            bundle,
+            profiles: Vec::new(),
+            strategies: vec![
+                fl!("import-conflict-skip"),
+                fl!("import-conflict-overwrite"),
+                fl!("import-conflict-rename"),
+            ],
+            strategy_idx: 0,
+        }
+    }
+
+    /// Shared profiles the planned apps use, see [`webapps::migrate::Discovery`].
+    pub fn with_profiles(mut self, profiles: Vec<Profile>) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    fn strategy(&self) -> ConflictStrategy {
+        STRATEGIES[self.strategy_idx]
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Action<pages::Message>> {
+        match message {
+            Message::ConflictStrategy(idx) => {
+                if idx < STRATEGIES.len() {
+                    self.strategy_idx = idx;
+                }
+            }
+            Message::Confirm => {
+                self.importing = true;
+
+                let plan = self.plan.clone();
+                let strategy = self.strategy();
+                let bundle = self.bundle.clone();
+                let profiles = self.profiles.clone();
+
+                return task::future(async move {
+                    let results = webapps::launcher::install_imported(plan, strategy, true).await;
+                    if let Some(bundle) = bundle {
+                        bundle.install_profiles(&results);
+                    }
+                    webapps::migrate::register_profiles(&profiles);
+                    pages::Message::ImportDone(results)
+                });
+            }
+        }
+
+        Task::none()
+    }
+
+    fn planned_status(&self, planned: &PlannedImport) -> String {
+        if !planned.conflict {
+            return fl!("import-status-new");
+        }
+
+        match self.strategy() {
+            ConflictStrategy::Skip => fl!("import-status-skip"),
+            ConflictStrategy::Overwrite => fl!("import-status-overwrite"),
+            ConflictStrategy::Rename => fl!("import-status-rename"),
+        }
+    }
+
+    fn result_status(result: &ImportResult) -> String {
+        match &result.outcome {
+            ImportOutcome::Installed => fl!("import-result-installed"),
+            ImportOutcome::Overwritten => fl!("import-result-overwritten"),
+            ImportOutcome::Renamed(id) => {
+                fl!(
+                    "import-result-renamed",
+                    HashMap::from([("id", id.as_str())])
+                )
+            }
+            ImportOutcome::Skipped => fl!("import-result-skipped"),
+            ImportOutcome::Failed(error) => {
+                fl!(
+                    "import-result-failed",
+                    HashMap::from([("error", error.as_str())])
+                )
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let mut apps = widget::settings::section();
+
+        match &self.results {
+            Some(results) => {
+                for result in results {
+                    apps = apps.add(widget::settings::item(
+                        result.name.clone(),
+                        widget::text::caption(Self::result_status(result)),
+                    ));
+                }
+            }
+            None => {
+                for planned in &self.plan {
+                    apps = apps.add(widget::settings::item(
+                        planned.app.name.clone(),
+                        widget::text::caption(self.planned_status(planned)),
+                    ));
+                }
+            }
+        }
+
+        let has_conflicts = self.plan.iter().any(|planned| planned.conflict);
+
+        let profiles = (!self.profiles.is_empty()).then(|| {
+            let mut section = widget::settings::section()
+                .title(fl!("import-shared-profiles"))
+                .add(widget::text::caption(fl!(
+                    "import-shared-profiles-description"
+                )));
+
+            for profile in &self.profiles {
+                let apps = self
+                    .plan
+                    .iter()
+                    .filter(|planned| planned.app.browser.profile.as_ref() == Some(&profile.id))
+                    .map(|planned| planned.app.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                section = section.add(widget::settings::item(
+                    profile.name.clone(),
+                    widget::text::caption(apps),
+                ));
+            }
+
+            section
+        });
+
+        widget::column()
+            .spacing(theme::active().cosmic().spacing.space_s)
+            .push_maybe(
+                if has_conflicts && !self.importing && self.results.is_none() {
+                    Some(widget::settings::section().add(widget::settings::item(
+                        fl!("import-conflict-strategy"),
+                        widget::dropdown(
+                            &self.strategies,
+                            Some(self.strategy_idx),
+                            Message::ConflictStrategy,
+                        ),
+                    )))
+                } else {
+                    None
+                },
+            )
+            .push(
+                widget::scrollable(widget::column().push(apps).push_maybe(profiles))
+                    .height(Length::Fixed(320.)),
+            )
+            .into()
+    }
+}
@@ -1,7 +1,12 @@
 pub mod editor;
 mod iconpicker;
+mod importer;
 
-use crate::{config::AppConfig, pages::iconpicker::IconPicker, themes::Theme};
+use crate::{
+    config::AppConfig,
+    pages::{iconpicker::IconPicker, importer::ImportPreview},
+    themes::Theme,
+};
 use ashpd::desktop::file_chooser::{FileFilter, SelectedFiles};
 use cosmic::{
     Application, Element,
@@ -21,22 +26,18 @@ use cosmic::{
     },
 };
 use editor::AppEditor;
-use ron::ser::to_string_pretty;
 use std::{
     collections::HashMap,
     fs::read_dir,
-    io::{Read, Write},
+    io::Read,
     path::{Path, PathBuf},
-    process::ExitStatus,
     sync::{Arc, LazyLock},
     time::Duration,
 };
-use tokio::{
-    io::{AsyncBufReadExt, BufReader},
-    process::Command,
-    sync::oneshot,
+use tokio::process::Command;
+use webapps::{
+    APP_ICON, APP_ID, REPOSITORY, backup::Snapshot, fl, inspector::Inspection, site_data::SiteData,
 };
-use webapps::{APP_ICON, APP_ID, REPOSITORY, fl};
 
 static MENU_ID: LazyLock<cosmic::widget::Id> =
     LazyLock::new(|| cosmic::widget::Id::new("responsive-menu"));
@@ -55,10 +56,10 @@ pub enum Message {
     DuplicateApp(Box<editor::AppEditor>),
     DownloaderDone,
     DownloaderStarted,
-    DownloaderStream(String),
-    DownloaderStreamFinished,
+    DownloaderProgress(webapps::icon_pack::Progress),
+    DownloaderFinished(Result<(), String>),
     IconPicker(iconpicker::Message),
-    IconsResult(Vec<String>),
+    IconsResult(Vec<webapps::icon_theme::IconMatch>),
     ImportThemeFilePicker,
     Launch(webapps::WebviewArgs),
     LaunchUrl(String),
@@ -76,15 +77,45 @@ pub enum Message {
     Surface(surface::Action),
     DownloaderStop,
     ExportApps,
-    ExportAppsResult(Result<(), String>),
+    ExportAppsResult(Result<Vec<String>, String>),
+    ExportBundle,
     ImportApps,
     ImportAppsFilePicked(Vec<String>),
+    ImportFromBrowsers,
+    ImportFromBrowsersFound(webapps::migrate::Discovery),
+    ImportPreview(importer::Message),
+    ImportDone(Vec<webapps::launcher::ImportResult>),
     SearchApps(String),
     ToggleContextPage(ContextPage),
     UpdateConfig(AppConfig),
     UpdateTheme(Box<Theme>),
     ClearAppData(String),
     ClearAppDataDone(Result<(), String>),
+    ClearSiteData(String, SiteData),
+    ClearSiteDataDone(SiteData, Result<(), String>),
+    InspectSiteData(String),
+    SiteDataInspected(String, Box<Inspection>),
+    ClearDomain(String),
+    ClearDomainDone(String, Result<(), String>),
+    CreateSnapshot(String),
+    ConfirmRestoreSnapshot(Snapshot),
+    RestoreSnapshot(Snapshot),
+    DeleteSnapshot(Snapshot),
+    PruneSnapshots(String),
+    /// The toast to show, or the error of a backup operation.
+    BackupDone(Result<String, String>),
+    ForgetPasswords(String),
+    /// The number of removed passwords, or the error of the keyring.
+    PasswordsForgotten(Result<usize, String>),
+    ProfileName(String),
+    CreateProfile,
+    EditProfileName(String),
+    ProfileRename(String),
+    SaveProfileName,
+    ConfirmDeleteProfile(webapps::profile::Profile),
+    DeleteProfile(String),
+    ProfileDone(Result<(), String>),
+    ProfileSizes(HashMap<String, u64>),
     FocusSearch,
     LaunchCurrentApp,
     DuplicateCurrentApp,
@@ -112,6 +143,9 @@ pub enum Dialogs {
     IconPicker(IconPicker),
     Confirmation((widget::segmented_button::Entity, String)),
     IconsDownloader,
+    ImportPreview(ImportPreview),
+    DeleteProfile(webapps::profile::Profile),
+    RestoreSnapshot(Snapshot),
 }
 
 pub struct QuickWebApps {
@@ -133,6 +167,13 @@ pub struct QuickWebApps {
     running_app_ids: std::collections::HashSet<String>,
     bulk_mode: bool,
     selected_app_ids: std::collections::HashSet<String>,
+    profiles: Vec<webapps::profile::Profile>,
+    profile_sizes: HashMap<String, u64>,
+    new_profile_name: String,
+    profile_rename: Option<(String, String)>,
+    /// Profile shown in the site data inspector, and what it stores once read.
+    inspected_profile: Option<String>,
+    inspection: Option<Inspection>,
 }
 
 impl Application for QuickWebApps {
@@ -227,6 +268,12 @@ impl Application for QuickWebApps {
             running_app_ids: std::collections::HashSet::new(),
             bulk_mode: false,
             selected_app_ids: std::collections::HashSet::new(),
+            profiles: Vec::new(),
+            profile_sizes: HashMap::new(),
+            new_profile_name: String::new(),
+            profile_rename: None,
+            inspected_profile: None,
+            inspection: None,
         };
 
         let tasks = vec![
@@ -265,55 +312,24 @@ impl Application for QuickWebApps {
             subscriptions.push(Subscription::run_with_id(
                 self.downloader_id,
                 cosmic::iced::stream::channel(4, move |mut channel| async move {
-                    let script = match webapps::add_icon_packs_install_script().await {
-                        Ok(s) => s,
-                        Err(e) => {
-                            tracing::error!("Failed to create install script: {e}");
-                            let _ = channel.send(Message::DownloaderStreamFinished).await;
-                            return future::pending().await;
-                        }
-                    };
-                    let mut child = match webapps::execute_script(script).await {
-                        Ok(c) => c,
-                        Err(e) => {
-                            tracing::error!("Failed to execute install script: {e}");
-                            let _ = channel.send(Message::DownloaderStreamFinished).await;
-                            return future::pending().await;
-                        }
-                    };
-                    let stdout = match child.stdout.take() {
-                        Some(s) => s,
-                        None => {
-                            tracing::error!("Child process has no stdout handle");
-                            let _ = channel.send(Message::DownloaderStreamFinished).await;
-                            return future::pending().await;
-                        }
-                    };
-
-                    let mut reader = BufReader::new(stdout).lines();
-                    let (tx, rx) = oneshot::channel::<ExitStatus>();
-
-                    tokio::spawn(async move {
-                        match child.wait().await {
-                            Ok(status) => {
-                                let _ = tx.send(status);
+                    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+                    let install = webapps::icon_pack::install(tx);
+                    tokio::pin!(install);
+
+                    let result = loop {
+                        tokio::select! {
+                            result = &mut install => break result,
+                            Some(progress) = rx.recv() => {
+                                let _ = channel.send(Message::DownloaderProgress(progress)).await;
                             }
-                            Err(e) => tracing::error!("Child process error: {e}"),
                         }
-                    });
-
-                    while let Ok(Some(line)) = reader.next_line().await {
-                        _ = channel.send(Message::DownloaderStream(line)).await;
-                    }
+                    };
 
-                    match rx.await {
-                        Ok(es) => {
-                            if es.success() {
-                                let _ = channel.send(Message::DownloaderStreamFinished).await;
-                            }
-                        }
-                        Err(_) => tracing::error!("the sender dropped"),
+                    let result = result.map_err(|e| e.to_string());
+                    if let Err(e) = &result {
+                        tracing::error!("Failed to install icon pack: {e}");
                     }
+                    let _ = channel.send(Message::DownloaderFinished(result)).await;
 
                     future::pending().await
                 }),
@@ -362,6 +378,7 @@ impl Application for QuickWebApps {
                             name: app_editor.app_title.clone(),
                             icon: app_editor.app_icon.clone(),
                             category: app_editor.app_category.clone(),
+                            icon_overlay: None,
                         };
 
                         self.dialogs = None;
@@ -378,7 +395,8 @@ impl Application for QuickWebApps {
             Message::DeletionDone(id) => {
                 self.nav.remove(id);
                 self.dialogs = None;
-                self.page = Page::Editor(AppEditor::default());
+                self.reload_profiles();
+                self.show_editor(AppEditor::default());
                 tasks.push(
                     self.toasts
                         .push(widget::toaster::Toast::new(fl!("toast-app-deleted")))
@@ -386,7 +404,7 @@ impl Application for QuickWebApps {
                 );
             }
             Message::DuplicateApp(editor) => {
-                self.page = Page::Editor(*editor);
+                self.show_editor(*editor);
                 // Select the "Create new" entry so the user can save the duplicate
                 let first_entity = { self.nav.iter().next() };
                 if let Some(first) = first_entity {
@@ -400,18 +418,22 @@ impl Application for QuickWebApps {
             Message::DownloaderStarted => {
                 self.dialogs = None;
                 self.downloader_started = true;
+                self.downloader_output = fl!("icons-installer-starting");
                 self.dialogs = Some(Dialogs::IconsDownloader)
             }
-            Message::DownloaderStream(buffer) => {
-                const MAX_OUTPUT_LEN: usize = 32_768;
-                self.downloader_output.push_str(&format!("{buffer}\n"));
-                if self.downloader_output.len() > MAX_OUTPUT_LEN {
-                    let trim_at = self.downloader_output.len() - MAX_OUTPUT_LEN;
-                    if let Some(newline) = self.downloader_output[trim_at..].find('\n') {
-                        self.downloader_output =
-                            self.downloader_output[trim_at + newline + 1..].to_string();
-                    }
-                }
+            Message::DownloaderProgress(progress) => {
+                self.downloader_output = match progress {
+                    webapps::icon_pack::Progress::Downloading(received) => fl!(
+                        "icons-installer-downloading",
+                        HashMap::from([("size", webapps::format_bytes(received).as_str())])
+                    ),
+                    webapps::icon_pack::Progress::Verifying => fl!("icons-installer-verifying"),
+                    webapps::icon_pack::Progress::Extracting(files) => fl!(
+                        "icons-installer-extracting",
+                        HashMap::from([("files", files.to_string().as_str())])
+                    ),
+                    webapps::icon_pack::Progress::Installing => fl!("icons-installer-installing"),
+                };
             }
             Message::SearchApps(query) => {
                 self.search_query = query;
@@ -444,7 +466,53 @@ impl Application for QuickWebApps {
                             let path = std::path::PathBuf::from(uri.path());
                             match webapps::launcher::export_all(&path) {
                                 Ok(()) => {
-                                    return cosmic::action::app(Message::ExportAppsResult(Ok(())));
+                                    return cosmic::action::app(Message::ExportAppsResult(Ok(
+                                        Vec::new(),
+                                    )));
+                                }
+                                Err(e) => {
+                                    tracing::error!("Export failed: {e}");
+                                    return cosmic::action::app(Message::ExportAppsResult(Err(
+                                        fl!("toast-export-error"),
+                                    )));
+                                }
+                            }
+                        }
+                    }
+                    cosmic::action::none()
+                });
+            }
+            Message::ExportBundle => {
+                return task::future(async {
+                    let title = fl!("file-dialog-export-title");
+                    let label = fl!("file-dialog-save");
+                    let response = match SelectedFiles::save_file()
+                        .title(title.as_str())
+                        .accept_label(label.as_str())
+                        .modal(true)
+                        .current_name("webapps-export.tar.gz")
+                        .send()
+                        .await
+                    {
+                        Ok(r) => r.response(),
+                        Err(e) => {
+                            tracing::error!("Failed to open save dialog: {e}");
+                            return cosmic::action::app(Message::ExportAppsResult(Err(fl!(
+                                "toast-export-error"
+                            ))));
+                        }
+                    };
+
+                    if let Ok(result) = response {
+                        let uris = result.uris();
+                        if let Some(uri) = uris.first() {
+                            let path = std::path::PathBuf::from(uri.path());
+                            let apps = webapps::launcher::installed_webapps();
+                            match webapps::bundle::export_bundle(&path, &apps, &[]) {
+                                Ok(generated_icons) => {
+                                    return cosmic::action::app(Message::ExportAppsResult(Ok(
+                                        generated_icons,
+                                    )));
                                 }
                                 Err(e) => {
                                     tracing::error!("Export failed: {e}");
@@ -459,13 +527,24 @@ impl Application for QuickWebApps {
                 });
             }
             Message::ExportAppsResult(result) => match result {
-                Ok(()) => {
+                Ok(generated_icons) if generated_icons.is_empty() => {
                     tasks.push(
                         self.toasts
                             .push(widget::toaster::Toast::new(fl!("toast-export-success")))
                             .map(cosmic::Action::App),
                     );
                 }
+                Ok(generated_icons) => {
+                    let apps = generated_icons.join(", ");
+                    tasks.push(
+                        self.toasts
+                            .push(widget::toaster::Toast::new(fl!(
+                                "toast-export-generated-icons",
+                                HashMap::from([("apps", apps.as_str())])
+                            )))
+                            .map(cosmic::Action::App),
+                    );
+                }
                 Err(msg) => {
                     tasks.push(
                         self.toasts
@@ -479,12 +558,14 @@ impl Application for QuickWebApps {
                     let title = fl!("file-dialog-import-title");
                     let label = fl!("file-dialog-import");
                     let filter_name = fl!("file-filter-ron");
+                    let bundle_filter_name = fl!("file-filter-bundle");
                     let response = match SelectedFiles::open_file()
                         .title(title.as_str())
                         .accept_label(label.as_str())
                         .modal(true)
                         .multiple(false)
                         .filter(FileFilter::new(&filter_name).glob("*.ron"))
+                        .filter(FileFilter::new(&bundle_filter_name).glob("*.tar.gz"))
                         .send()
                         .await
                     {
@@ -524,22 +605,25 @@ impl Application for QuickWebApps {
                     };
                     let path = std::path::PathBuf::from(&decoded);
 
-                    match webapps::launcher::import_all(&path) {
-                        Ok(apps) => {
-                            let (saved, total) = webapps::launcher::save_imported(&apps);
-                            let msg = if saved == total {
-                                fl!("toast-import-success")
-                            } else {
-                                format!("{} ({}/{})", fl!("toast-import-success"), saved, total)
-                            };
+                    let imported = if webapps::bundle::is_bundle(&path) {
+                        webapps::bundle::Bundle::open(&path)
+                            .map(|bundle| (bundle.apps.clone(), Some(Arc::new(bundle))))
+                    } else {
+                        webapps::launcher::import_all(&path).map(|apps| (apps, None))
+                    };
+
+                    match imported {
+                        Ok((apps, _)) if apps.is_empty() => {
                             tasks.push(
                                 self.toasts
-                                    .push(widget::toaster::Toast::new(msg))
+                                    .push(widget::toaster::Toast::new(fl!("import-empty")))
                                     .map(cosmic::Action::App),
                             );
-                            return Task::batch(tasks.into_iter().chain(std::iter::once(
-                                task::message(cosmic::action::app(Message::ReloadNavbarItems)),
-                            )));
+                        }
+                        Ok((apps, bundle)) => {
+                            let plan = webapps::launcher::plan_import(apps);
+                            self.dialogs =
+                                Some(Dialogs::ImportPreview(ImportPreview::new(plan, bundle)));
                         }
                         Err(e) => {
                             tracing::error!("Import failed: {e}");
@@ -552,15 +636,78 @@ impl Application for QuickWebApps {
                     }
                 }
             }
+            Message::ImportFromBrowsers => {
+                return task::future(async {
+                    let discovery =
+                        webapps::migrate::discover(&webapps::migrate::Source::ALL).await;
+                    Message::ImportFromBrowsersFound(discovery)
+                });
+            }
+            Message::ImportFromBrowsersFound(discovery) => {
+                if discovery.apps.is_empty() {
+                    tasks.push(
+                        self.toasts
+                            .push(widget::toaster::Toast::new(fl!(
+                                "import-from-browsers-empty"
+                            )))
+                            .map(cosmic::Action::App),
+                    );
+                } else {
+                    let plan = webapps::launcher::plan_import(discovery.apps);
+                    self.dialogs = Some(Dialogs::ImportPreview(
+                        ImportPreview::new(plan, None).with_profiles(discovery.profiles),
+                    ));
+                }
+            }
+            Message::ImportPreview(msg) => {
+                if let Some(Dialogs::ImportPreview(preview)) = &mut self.dialogs {
+                    tasks.push(preview.update(msg));
+                };
+            }
+            Message::ImportDone(results) => {
+                let total = results.len();
+                let imported = results
+                    .iter()
+                    .filter(|result| {
+                        !matches!(
+                            result.outcome,
+                            webapps::launcher::ImportOutcome::Skipped
+                                | webapps::launcher::ImportOutcome::Failed(_)
+                        )
+                    })
+                    .count();
+                let failed = results.iter().any(|result| {
+                    matches!(result.outcome, webapps::launcher::ImportOutcome::Failed(_))
+                });
+
+                if let Some(Dialogs::ImportPreview(preview)) = &mut self.dialogs {
+                    preview.importing = false;
+                    preview.results = Some(results);
+                };
+
+                let msg = if failed {
+                    format!("{} ({}/{})", fl!("toast-import-error"), imported, total)
+                } else if imported == total {
+                    fl!("toast-import-success")
+                } else {
+                    format!("{} ({}/{})", fl!("toast-import-success"), imported, total)
+                };
+                tasks.push(
+                    self.toasts
+                        .push(widget::toaster::Toast::new(msg))
+                        .map(cosmic::Action::App),
+                );
+                tasks.push(task::message(cosmic::action::app(
+                    Message::ReloadNavbarItems,
+                )));
+            }
             Message::DownloaderStop => {
                 self.downloader_started = false;
                 self.downloader_id += 1;
-                self.downloader_output
-                    .push_str(&fl!("downloader-canceled").to_string());
+                self.downloader_output = fl!("downloader-canceled");
             }
-            Message::DownloaderStreamFinished => {
-                self.downloader_output
-                    .push_str(&fl!("icons-installer-finished-waiting").to_string());
+            Message::DownloaderFinished(Ok(())) => {
+                self.downloader_output = fl!("icons-installer-finished-waiting");
 
                 return task::future(async {
                     tokio::time::sleep(Duration::from_secs_f32(3.0)).await;
@@ -568,6 +715,14 @@ impl Application for QuickWebApps {
                     cosmic::action::app(Message::DownloaderDone)
                 });
             }
+            Message::DownloaderFinished(Err(error)) => {
+                self.downloader_started = false;
+                self.downloader_id += 1;
+                self.downloader_output = fl!(
+                    "icons-installer-failed",
+                    HashMap::from([("error", error.as_str())])
+                );
+            }
             Message::IconPicker(msg) => {
                 if let Some(Dialogs::IconPicker(icon_picker)) = &mut self.dialogs {
                     tasks.push(icon_picker.update(msg));
@@ -575,10 +730,13 @@ impl Application for QuickWebApps {
             }
             Message::IconsResult(result) => {
                 if let Some(Dialogs::IconPicker(_icon_picker)) = &mut self.dialogs {
-                    for path in result {
+                    for found in result {
+                        // Themed icons are stored by name, so they follow the icon theme
+                        let name = found.theme.is_some().then_some(found.name);
+                        let path = found.path.to_string_lossy().to_string();
                         tasks.push(Task::perform(webapps::image_handle(path), |icon| {
                             if let Some(icon) = icon {
-                                cosmic::Action::App(Message::PushIcon(icon))
+                                cosmic::Action::App(Message::PushIcon(icon.with_name(name)))
                             } else {
                                 cosmic::Action::None
                             }
@@ -632,29 +790,18 @@ impl Application for QuickWebApps {
             }
             Message::Launch(args) => {
                 // #57: Update usage statistics
-                let app_id_str = args.as_ref().to_string();
-                let safe_id = webapps::browser::sanitize_app_id(&app_id_str);
-                if let Some(db_path) = webapps::database_path(&format!("{safe_id}.ron")) {
-                    if let Ok(content) = std::fs::read_to_string(&db_path) {
-                        if let Ok(mut launcher) = ron::from_str::<webapps::launcher::WebAppLauncher>(&content) {
-                            let count = launcher.browser.launch_count.unwrap_or(0);
-                            launcher.browser.launch_count = Some(count + 1);
-                            let now = std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .map(|d| d.as_secs())
-                                .unwrap_or(0);
-                            launcher.browser.last_launched = Some(now);
-                            if let Ok(serialized) = to_string_pretty(&launcher, ron::ser::PrettyConfig::default()) {
-                                let _ = std::fs::write(&db_path, serialized);
-                            }
-                        }
+                if let Some(mut launcher) =
+                    webapps::launcher::WebAppLauncher::from_appid(args.as_ref())
+                {
+                    launcher.record_launch();
+                    if let Err(e) = launcher.save() {
+                        tracing::warn!("Failed to update usage statistics: {e}");
                     }
                 }
                 return Task::perform(
                     async move {
-                        if let Err(e) = Command::new("dev.heppen.webapps.webview")
-                            .args(args)
-                            .spawn()
+                        if let Err(e) =
+                            Command::from(webapps::launcher::webview_command(args)).spawn()
                         {
                             tracing::error!("Failed to spawn webview: {e}");
                         }
@@ -721,7 +868,7 @@ impl Application for QuickWebApps {
                 })
             }
             Message::OpenFileResult(file_paths) => {
-                let mut moved: Vec<String> = Vec::new();
+                let mut moved: Vec<webapps::icon_theme::IconMatch> = Vec::new();
 
                 for path in file_paths {
                     let buf = PathBuf::from(&path);
@@ -729,9 +876,14 @@ impl Application for QuickWebApps {
 
                     if let Some(file_stem) = icon_name {
                         let stem_str = file_stem.to_str().unwrap_or("icon");
-                        let ext_str = buf.extension().and_then(|e| e.to_str()).unwrap_or("png");
-                        if let Some(final_path) = webapps::move_icon(&path, stem_str, ext_str) {
-                            moved.push(final_path.display().to_string());
+                        if let Some(final_path) = webapps::move_icon(&path, stem_str) {
+                            moved.push(webapps::icon_theme::IconMatch {
+                                name: stem_str.to_string(),
+                                scalable: webapps::is_svg(&path),
+                                path: final_path,
+                                theme: None,
+                                size: 0,
+                            });
                         }
                     };
                 }
@@ -782,46 +934,34 @@ impl Application for QuickWebApps {
                 return cosmic::command::set_theme(cosmic::Theme::light());
             }
             Message::SaveLauncher(launcher) => {
-                if let Some(location) =
-                    webapps::database_path(&format!("{}.ron", launcher.browser.app_id.as_ref()))
-                {
-                    let save_result = (|| -> Result<(), Box<dyn std::error::Error>> {
-                        let content =
-                            to_string_pretty(&launcher, ron::ser::PrettyConfig::default())?;
-                        let mut f = std::fs::File::create(&location)?;
-                        f.write_all(content.as_bytes())?;
-                        Ok(())
-                    })();
-
-                    match save_result {
-                        Ok(()) => {
-                            tasks.push(
-                                self.toasts
-                                    .push(widget::toaster::Toast::new(fl!("toast-app-saved")))
-                                    .map(cosmic::Action::App),
-                            );
-                        }
-                        Err(e) => {
-                            tracing::error!("Failed to save web app: {e}");
-                            tasks.push(
-                                self.toasts
-                                    .push(widget::toaster::Toast::new(fl!("toast-save-error")))
-                                    .map(cosmic::Action::App),
-                            );
-                            return Task::batch(tasks);
-                        }
+                match launcher.save() {
+                    Ok(()) => {
+                        tasks.push(
+                            self.toasts
+                                .push(widget::toaster::Toast::new(fl!("toast-app-saved")))
+                                .map(cosmic::Action::App),
+                        );
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to save web app: {e}");
+                        tasks.push(
+                            self.toasts
+                                .push(widget::toaster::Toast::new(fl!("toast-save-error")))
+                                .map(cosmic::Action::App),
+                        );
+                        return Task::batch(tasks);
                     }
-
-                    // Reload and re-select saved app
-                    self.cached_apps = webapps::launcher::installed_webapps();
-                    self.cached_apps.sort_by(|a, b| {
-                        let cat_cmp = a.category.name().cmp(&b.category.name());
-                        cat_cmp.then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
-                    });
-                    let app_id = launcher.browser.app_id.as_ref().to_string();
-                    self.rebuild_nav_from_cache(Some(&app_id));
-                    return Task::batch(tasks);
                 }
+
+                // Reload and re-select saved app
+                self.cached_apps = webapps::launcher::installed_webapps();
+                self.cached_apps.sort_by(|a, b| {
+                    let cat_cmp = a.category.name().cmp(&b.category.name());
+                    cat_cmp.then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                });
+                let app_id = launcher.browser.app_id.as_ref().to_string();
+                self.rebuild_nav_from_cache(Some(&app_id));
+                return Task::batch(tasks);
             }
             Message::SetIcon(icon) => {
                 let Page::Editor(app_editor) = &mut self.page;
@@ -840,6 +980,11 @@ impl Application for QuickWebApps {
                     self.context_page = context_page;
                     self.core.window.show_context = true;
                 }
+
+                if self.context_page == ContextPage::Profiles && self.core.window.show_context {
+                    self.reload_profiles();
+                    return self.load_profile_sizes();
+                }
             }
 
             Message::UpdateConfig(config) => {
@@ -873,9 +1018,9 @@ impl Application for QuickWebApps {
                     tasks.push(theme_selector);
                 }
             }
-            Message::ClearAppData(app_id) => {
+            Message::ClearAppData(profile) => {
                 return task::future(async move {
-                    match tokio::task::spawn_blocking(move || webapps::clear_profile_data(&app_id))
+                    match tokio::task::spawn_blocking(move || webapps::profile::clear(&profile))
                         .await
                     {
                         Ok(Ok(())) => cosmic::action::app(Message::ClearAppDataDone(Ok(()))),
@@ -895,6 +1040,10 @@ impl Application for QuickWebApps {
                             .push(widget::toaster::Toast::new(fl!("toast-data-cleared")))
                             .map(cosmic::Action::App),
                     );
+                    tasks.push(self.load_profile_sizes());
+                    tasks.push(task::message(cosmic::action::app(Message::Editor(
+                        editor::Message::LoadSiteData,
+                    ))));
                 }
                 Err(msg) => {
                     tracing::error!("Failed to clear app data: {msg}");
@@ -905,6 +1054,259 @@ impl Application for QuickWebApps {
                     );
                 }
             },
+            Message::ClearSiteData(profile, data) => {
+                return task::future(async move {
+                    let result = webapps::site_data::clear(&profile, &[data])
+                        .await
+                        .map_err(|e| e.to_string());
+                    cosmic::action::app(Message::ClearSiteDataDone(data, result))
+                });
+            }
+            Message::ClearSiteDataDone(data, result) => match result {
+                Ok(()) => {
+                    let name = data.name();
+                    tasks.push(
+                        self.toasts
+                            .push(widget::toaster::Toast::new(fl!(
+                                "toast-site-data-cleared",
+                                HashMap::from([("data", name.as_str())])
+                            )))
+                            .map(cosmic::Action::App),
+                    );
+                    tasks.push(self.load_profile_sizes());
+                    tasks.push(task::message(cosmic::action::app(Message::Editor(
+                        editor::Message::LoadSiteData,
+                    ))));
+                }
+                Err(msg) => {
+                    tracing::error!("Failed to clear {} data: {msg}", data.as_str());
+                    tasks.push(
+                        self.toasts
+                            .push(widget::toaster::Toast::new(fl!("toast-data-clear-error")))
+                            .map(cosmic::Action::App),
+                    );
+                }
+            },
+            Message::InspectSiteData(profile) => {
+                self.inspected_profile = Some(profile);
+                self.inspection = None;
+                self.context_page = ContextPage::SiteData;
+                self.core.window.show_context = true;
+                return self.load_inspection();
+            }
+            Message::SiteDataInspected(profile, inspection) => {
+                if self.inspected_profile.as_ref() == Some(&profile) {
+                    self.inspection = Some(*inspection);
+                }
+            }
+            Message::ClearDomain(domain) => {
+                if let Some(profile) = self.inspected_profile.clone() {
+                    return task::future(async move {
+                        let result = webapps::inspector::clear_domain(&profile, &domain)
+                            .await
+                            .map_err(|e| e.to_string());
+                        cosmic::action::app(Message::ClearDomainDone(domain, result))
+                    });
+                }
+            }
+            Message::ClearDomainDone(domain, result) => match result {
+                Ok(()) => {
+                    tasks.push(
+                        self.toasts
+                            .push(widget::toaster::Toast::new(fl!(
+                                "toast-domain-data-cleared",
+                                HashMap::from([("domain", domain.as_str())])
+                            )))
+                            .map(cosmic::Action::App),
+                    );
+                    tasks.push(self.load_inspection());
+                    tasks.push(task::message(cosmic::action::app(Message::Editor(
+                        editor::Message::LoadSiteData,
+                    ))));
+                }
+                Err(msg) => {
+                    tracing::error!("Failed to delete data of {domain}: {msg}");
+                    tasks.push(
+                        self.toasts
+                            .push(widget::toaster::Toast::new(fl!("toast-data-clear-error")))
+                            .map(cosmic::Action::App),
+                    );
+                }
+            },
+            Message::CreateSnapshot(profile) => {
+                return task::future(async move {
+                    let result = tokio::task::spawn_blocking(move || {
+                        webapps::backup::create(&profile)
+                            .map(|_| fl!("toast-backup-created"))
+                            .map_err(|e| e.to_string())
+                    })
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()));
+
+                    Message::BackupDone(result)
+                });
+            }
+            Message::ConfirmRestoreSnapshot(snapshot) => {
+                self.dialogs = Some(Dialogs::RestoreSnapshot(snapshot));
+            }
+            Message::RestoreSnapshot(snapshot) => {
+                self.dialogs = None;
+                return task::future(async move {
+                    let result = tokio::task::spawn_blocking(move || {
+                        webapps::backup::restore(&snapshot)
+                            .map(|()| fl!("toast-backup-restored"))
+                            .map_err(|e| e.to_string())
+                    })
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()));
+
+                    Message::BackupDone(result)
+                });
+            }
+            Message::DeleteSnapshot(snapshot) => {
+                return task::future(async move {
+                    let result = tokio::task::spawn_blocking(move || {
+                        webapps::backup::delete(&snapshot)
+                            .map(|()| fl!("toast-backup-deleted"))
+                            .map_err(|e| e.to_string())
+                    })
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()));
+
+                    Message::BackupDone(result)
+                });
+            }
+            Message::PruneSnapshots(profile) => {
+                return task::future(async move {
+                    let result = tokio::task::spawn_blocking(move || {
+                        webapps::backup::prune(&profile, webapps::backup::DEFAULT_KEEP)
+                            .map(|count| {
+                                let count = count.to_string();
+                                fl!(
+                                    "toast-backups-pruned",
+                                    HashMap::from([("count", count.as_str())])
+                                )
+                            })
+                            .map_err(|e| e.to_string())
+                    })
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()));
+
+                    Message::BackupDone(result)
+                });
+            }
+            Message::BackupDone(result) => {
+                let toast = match result {
+                    Ok(toast) => toast,
+                    Err(msg) => {
+                        tracing::error!("Failed to update backups: {msg}");
+                        fl!(
+                            "toast-backup-error",
+                            HashMap::from([("error", msg.as_str())])
+                        )
+                    }
+                };
+
+                tasks.push(
+                    self.toasts
+                        .push(widget::toaster::Toast::new(toast))
+                        .map(cosmic::Action::App),
+                );
+                tasks.push(self.load_profile_sizes());
+                tasks.push(task::message(cosmic::action::app(Message::Editor(
+                    editor::Message::LoadSiteData,
+                ))));
+            }
+            Message::ForgetPasswords(app_id) => {
+                return task::future(async move {
+                    let result = tokio::task::spawn_blocking(move || {
+                        webapps::credentials::forget(&app_id).map_err(|e| e.to_string())
+                    })
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()));
+
+                    Message::PasswordsForgotten(result)
+                });
+            }
+            Message::PasswordsForgotten(result) => {
+                let toast = match result {
+                    Ok(count) => {
+                        let count = count.to_string();
+                        fl!(
+                            "toast-passwords-forgotten",
+                            HashMap::from([("count", count.as_str())])
+                        )
+                    }
+                    Err(msg) => {
+                        tracing::error!("Failed to remove saved passwords: {msg}");
+                        fl!(
+                            "toast-passwords-error",
+                            HashMap::from([("error", msg.as_str())])
+                        )
+                    }
+                };
+
+                tasks.push(
+                    self.toasts
+                        .push(widget::toaster::Toast::new(toast))
+                        .map(cosmic::Action::App),
+                );
+            }
+            Message::ProfileName(name) => {
+                self.new_profile_name = name;
+            }
+            Message::CreateProfile => {
+                let result = webapps::profile::create(&self.new_profile_name)
+                    .map(|_| self.new_profile_name.clear())
+                    .map_err(|e| e.to_string());
+                return task::message(cosmic::action::app(Message::ProfileDone(result)));
+            }
+            Message::EditProfileName(id) => {
+                let name = webapps::profile::name(&id);
+                self.profile_rename = Some((id, name));
+            }
+            Message::ProfileRename(name) => {
+                if let Some((_, new_name)) = &mut self.profile_rename {
+                    *new_name = name;
+                }
+            }
+            Message::SaveProfileName => {
+                if let Some((id, name)) = self.profile_rename.take() {
+                    let result = webapps::profile::rename(&id, &name).map_err(|e| e.to_string());
+                    return task::message(cosmic::action::app(Message::ProfileDone(result)));
+                }
+            }
+            Message::ConfirmDeleteProfile(profile) => {
+                self.dialogs = Some(Dialogs::DeleteProfile(profile));
+            }
+            Message::DeleteProfile(id) => {
+                self.dialogs = None;
+                return task::future(async move {
+                    let result = tokio::task::spawn_blocking(move || {
+                        webapps::profile::delete(&id).map_err(|e| e.to_string())
+                    })
+                    .await
+                    .unwrap_or_else(|e| Err(e.to_string()));
+
+                    Message::ProfileDone(result)
+                });
+            }
+            Message::ProfileDone(result) => {
+                if let Err(msg) = result {
+                    tracing::error!("Failed to update profile: {msg}");
+                    tasks.push(
+                        self.toasts
+                            .push(widget::toaster::Toast::new(fl!("toast-profile-error")))
+                            .map(cosmic::Action::App),
+                    );
+                }
+
+                self.reload_profiles();
+                tasks.push(self.load_profile_sizes());
+            }
+            Message::ProfileSizes(sizes) => {
+                self.profile_sizes = sizes;
+            }
             Message::FocusSearch => {
                 return widget::text_input::focus(SEARCH_ID.clone());
             }
@@ -943,8 +1345,8 @@ impl Application for QuickWebApps {
                 });
                 // Then activate it (mutable borrow)
                 if let Some(entity) = target {
-                    if let Some(page) = self.nav.data::<Page>(entity) {
-                        self.page = page.clone();
+                    if let Some(Page::Editor(editor)) = self.nav.data::<Page>(entity).cloned() {
+                        self.show_editor(editor);
                     }
                     self.nav.activate(entity);
                     // Switch to list view for editing
@@ -1125,8 +1527,19 @@ impl Application for QuickWebApps {
                             menu::Item::Button(fl!("new-app"), None, MenuAction::NewApp),
                             menu::Item::Divider,
                             menu::Item::Button(fl!("export-apps"), None, MenuAction::ExportApps),
+                            menu::Item::Button(
+                                fl!("export-bundle"),
+                                None,
+                                MenuAction::ExportBundle,
+                            ),
                             menu::Item::Button(fl!("import-apps"), None, MenuAction::ImportApps),
+                            menu::Item::Button(
+                                fl!("import-from-browsers"),
+                                None,
+                                MenuAction::ImportFromBrowsers,
+                            ),
                             menu::Item::Divider,
+                            menu::Item::Button(fl!("profiles"), None, MenuAction::Profiles),
                             menu::Item::Button(fl!("settings"), None, MenuAction::Settings),
                             menu::Item::Button(fl!("about"), None, MenuAction::About),
                         ],
@@ -1187,18 +1600,30 @@ impl Application for QuickWebApps {
 
     fn on_nav_select(&mut self, id: nav_bar::Id) -> Task<Message> {
         self.nav.activate(id);
-        if let Some(page) = self.nav.data::<Page>(id) {
-            self.page = page.clone();
+        if let Some(Page::Editor(editor)) = self.nav.data::<Page>(id).cloned() {
+            self.show_editor(editor);
 
-            // Auto-trigger thumbnail fetch for installed apps with URLs
+            let mut tasks = Vec::new();
             let Page::Editor(editor) = &self.page;
+
+            // Auto-trigger thumbnail fetch for installed apps with URLs
             if editor.is_installed
                 && editor.thumbnail_handle.is_none()
                 && !editor.thumbnail_loading
                 && webapps::url_valid(&editor.app_url)
             {
-                return task::message(Message::Editor(editor::Message::FetchThumbnail));
+                tasks.push(task::message(Message::Editor(
+                    editor::Message::FetchThumbnail,
+                )));
+            }
+
+            if editor.is_installed {
+                tasks.push(task::message(Message::Editor(
+                    editor::Message::LoadSiteData,
+                )));
             }
+
+            return Task::batch(tasks);
         }
         Task::none()
     }
@@ -1219,6 +1644,16 @@ impl Application for QuickWebApps {
                 Message::ToggleContextPage(ContextPage::Settings),
             )
             .title(fl!("settings")),
+            ContextPage::Profiles => context_drawer::context_drawer(
+                self.profiles_page(),
+                Message::ToggleContextPage(ContextPage::Profiles),
+            )
+            .title(fl!("profiles")),
+            ContextPage::SiteData => context_drawer::context_drawer(
+                self.site_data_page(),
+                Message::ToggleContextPage(ContextPage::SiteData),
+            )
+            .title(fl!("site-data")),
         })
     }
 
@@ -1412,16 +1847,71 @@ impl Application for QuickWebApps {
                         "confirm-delete",
                         HashMap::from([("app", title.as_str())])
                     )),
-                Dialogs::IconsDownloader => widget::dialog()
-                    .title(fl!("icons-installer-header"))
-                    .body(self.downloader_output.clone())
+                Dialogs::DeleteProfile(profile) => widget::dialog()
+                    .title(fl!("delete"))
                     .primary_action(
-                        widget::button::destructive(fl!("cancel"))
-                            .on_press(Message::DownloaderStop),
+                        widget::button::destructive(fl!("yes"))
+                            .on_press(Message::DeleteProfile(profile.id.clone())),
                     )
                     .secondary_action(
-                        widget::button::suggested(fl!("close")).on_press(Message::CloseDialog),
-                    ),
+                        widget::button::suggested(fl!("no")).on_press(Message::CloseDialog),
+                    )
+                    .body(fl!(
+                        "confirm-delete-profile",
+                        HashMap::from([("profile", profile.name.as_str())])
+                    )),
+                Dialogs::RestoreSnapshot(snapshot) => {
+                    let time = webapps::format_timestamp(snapshot.created);
+                    widget::dialog()
+                        .title(fl!("restore-backup"))
+                        .primary_action(
+                            widget::button::destructive(fl!("yes"))
+                                .on_press(Message::RestoreSnapshot(snapshot.clone())),
+                        )
+                        .secondary_action(
+                            widget::button::suggested(fl!("no")).on_press(Message::CloseDialog),
+                        )
+                        .body(fl!(
+                            "confirm-restore-backup",
+                            HashMap::from([("time", time.as_str())])
+                        ))
+                }
+                Dialogs::IconsDownloader => {
+                    widget::dialog()
+                        .title(fl!("icons-installer-header"))
+                        .body(self.downloader_output.clone())
+                        .primary_action(widget::button::destructive(fl!("cancel")).on_press_maybe(
+                            self.downloader_started.then_some(Message::DownloaderStop),
+                        ))
+                        .secondary_action(
+                            widget::button::suggested(fl!("close")).on_press(Message::CloseDialog),
+                        )
+                }
+                Dialogs::ImportPreview(preview) => {
+                    let dialog = widget::dialog()
+                        .title(fl!("import-apps"))
+                        .control(preview.view().map(Message::ImportPreview));
+
+                    if preview.results.is_some() {
+                        dialog.primary_action(
+                            widget::button::suggested(fl!("close")).on_press(Message::CloseDialog),
+                        )
+                    } else {
+                        dialog
+                            .primary_action(
+                                widget::button::suggested(fl!("import")).on_press_maybe(
+                                    (!preview.importing).then_some(Message::ImportPreview(
+                                        importer::Message::Confirm,
+                                    )),
+                                ),
+                            )
+                            .secondary_action(
+                                widget::button::standard(fl!("cancel")).on_press_maybe(
+                                    (!preview.importing).then_some(Message::CloseDialog),
+                                ),
+                            )
+                    }
+                }
             };
 
             return Some(element.into());
@@ -1436,6 +1926,7 @@ impl QuickWebApps {
     /// If select_app_id is provided, attempts to re-select that app after rebuilding.
     fn rebuild_nav_from_cache(&mut self, select_app_id: Option<&str>) {
         self.nav.clear();
+        self.profiles = webapps::profile::all();
 
         self.nav
             .insert()
@@ -1486,12 +1977,61 @@ impl QuickWebApps {
 
         if let Some((entity, app)) = selected_entity {
             self.nav.activate(entity);
-            self.page = Page::Editor(editor::AppEditor::from(app));
+            self.show_editor(editor::AppEditor::from(app));
         } else {
-            self.page = Page::Editor(AppEditor::default());
+            self.show_editor(AppEditor::default());
         }
     }
 
+    /// Show `editor` with the current list of profiles.
+    fn show_editor(&mut self, mut editor: AppEditor) {
+        editor.load_profiles(&self.profiles);
+        self.page = Page::Editor(editor);
+    }
+
+    /// Re-read the profiles and pass them to the open editor.
+    fn reload_profiles(&mut self) {
+        self.profiles = webapps::profile::all();
+        let Page::Editor(editor) = &mut self.page;
+        editor.load_profiles(&self.profiles);
+    }
+
+    fn load_profile_sizes(&self) -> Task<Message> {
+        let ids: Vec<String> = self.profiles.iter().map(|p| p.id.clone()).collect();
+
+        task::future(async move {
+            let sizes = tokio::task::spawn_blocking(move || {
+                ids.into_iter()
+                    .map(|id| {
+                        let size = webapps::profile::size(&id);
+                        (id, size)
+                    })
+                    .collect()
+            })
+            .await
+            .unwrap_or_default();
+
+            Message::ProfileSizes(sizes)
+        })
+    }
+
+    fn load_inspection(&self) -> Task<Message> {
+        let Some(profile) = self.inspected_profile.clone() else {
+            return Task::none();
+        };
+
+        task::future(async move {
+            let inspection = {
+                let profile = profile.clone();
+                tokio::task::spawn_blocking(move || webapps::inspector::inspect(&profile))
+                    .await
+                    .unwrap_or_default()
+            };
+
+            Message::SiteDataInspected(profile, Box::new(inspection))
+        })
+    }
+
     fn about(&self) -> Element<'_, Message> {
         let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
 
@@ -1524,6 +2064,93 @@ impl QuickWebApps {
             .into()
     }
 
+    fn profiles_page(&self) -> Element<'_, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+        let mut section = widget::settings::section();
+
+        for profile in &self.profiles {
+            let users: Vec<&str> = self
+                .cached_apps
+                .iter()
+                .filter(|app| app.browser.profile.as_deref() == Some(profile.id.as_str()))
+                .map(|app| app.name.as_str())
+                .collect();
+            let usage = if users.is_empty() {
+                fl!("profile-unused")
+            } else {
+                let apps = users.join(", ");
+                fl!("profile-used-by", HashMap::from([("apps", apps.as_str())]))
+            };
+            let size = self
+                .profile_sizes
+                .get(&profile.id)
+                .map(|size| webapps::format_bytes(*size))
+                .unwrap_or_else(|| fl!("loading"));
+
+            let item = match &self.profile_rename {
+                Some((id, name)) if id == &profile.id => {
+                    widget::settings::item::builder(profile.name.clone()).control(
+                        widget::row()
+                            .spacing(space_xxs)
+                            .push(
+                                widget::text_input(fl!("profile-name"), name)
+                                    .on_input(Message::ProfileRename)
+                                    .on_submit(|_| Message::SaveProfileName),
+                            )
+                            .push(
+                                widget::button::suggested(fl!("save-profile-name"))
+                                    .on_press(Message::SaveProfileName),
+                            ),
+                    )
+                }
+                _ => widget::settings::item::builder(profile.name.clone())
+                    .description(format!("{size} · {usage}"))
+                    .control(
+                        widget::row()
+                            .spacing(space_xxs)
+                            .push(
+                                widget::button::standard(fl!("rename-profile"))
+                                    .on_press(Message::EditProfileName(profile.id.clone())),
+                            )
+                            .push(
+                                widget::button::standard(fl!("clear-data"))
+                                    .on_press(Message::ClearAppData(profile.id.clone())),
+                            )
+                            .push(
+                                widget::button::destructive(fl!("delete")).on_press_maybe(
+                                    users
+                                        .is_empty()
+                                        .then(|| Message::ConfirmDeleteProfile(profile.clone())),
+                                ),
+                            ),
+                    ),
+            };
+
+            section = section.add(item);
+        }
+
+        widget::column()
+            .push(
+                widget::row()
+                    .spacing(space_xxs)
+                    .push(
+                        widget::text_input(fl!("profile-name"), &self.new_profile_name)
+                            .on_input(Message::ProfileName)
+                            .on_submit(|_| Message::CreateProfile),
+                    )
+                    .push(
+                        widget::button::suggested(fl!("create-profile")).on_press_maybe(
+                            (!self.new_profile_name.trim().is_empty())
+                                .then_some(Message::CreateProfile),
+                        ),
+                    ),
+            )
+            .push(section)
+            .spacing(space_xxs)
+            .into()
+    }
+
     fn settings(&self) -> Element<'_, Message> {
         let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
 
@@ -1552,13 +2179,85 @@ impl QuickWebApps {
             .spacing(space_xxs)
             .into()
     }
+
+    fn site_data_page(&self) -> Element<'_, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+        let Some(inspection) = &self.inspection else {
+            return widget::text::body(fl!("loading")).into();
+        };
+
+        let mut column = widget::column()
+            .spacing(space_xxs)
+            .push(widget::settings::section().add(widget::settings::item(
+                fl!("site-data-cache"),
+                widget::text::body(webapps::format_bytes(inspection.cache_size)),
+            )));
+
+        let domains = inspection.domains();
+        if domains.is_empty() {
+            column = column.push(widget::text::body(fl!("site-data-empty")));
+        }
+
+        for domain in domains {
+            let mut section = widget::settings::section().title(domain.clone());
+
+            for cookie in inspection.cookies_of(&domain) {
+                let expires = match cookie.expires {
+                    Some(time) => {
+                        let time = webapps::format_time_left(time);
+                        fl!("cookie-expires", HashMap::from([("time", time.as_str())]))
+                    }
+                    None => fl!("cookie-session"),
+                };
+                section = section.add(
+                    widget::settings::item::builder(cookie.name.clone())
+                        .description(cookie.path.clone())
+                        .control(widget::text::body(expires)),
+                );
+            }
+
+            for data in inspection.local_storage_of(&domain) {
+                section = section.add(
+                    widget::settings::item::builder(fl!("local-storage"))
+                        .description(data.origin.clone())
+                        .control(widget::text::body(webapps::format_bytes(data.size))),
+                );
+            }
+
+            for data in inspection.indexed_db_of(&domain) {
+                let count = data.databases.to_string();
+                let size = webapps::format_bytes(data.size);
+                section = section.add(
+                    widget::settings::item::builder(fl!("indexed-db"))
+                        .description(data.origin.clone())
+                        .control(widget::text::body(fl!(
+                            "indexed-db-databases",
+                            HashMap::from([("count", count.as_str()), ("size", size.as_str())])
+                        ))),
+                );
+            }
+
+            section = section.add(widget::settings::item(
+                fl!("site-data-inspector"),
+                widget::button::destructive(fl!("delete-domain-data"))
+                    .on_press(Message::ClearDomain(domain.clone())),
+            ));
+
+            column = column.push(section);
+        }
+
+        column.into()
+    }
 }
 
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub enum ContextPage {
     #[default]
     About,
+    Profiles,
     Settings,
+    SiteData,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -1566,10 +2265,13 @@ pub enum MenuAction {
     About,
     DuplicateApp,
     ExportApps,
+    ExportBundle,
     FocusSearch,
     ImportApps,
+    ImportFromBrowsers,
     LaunchApp,
     NewApp,
+    Profiles,
     Save,
     Settings,
 }
@@ -1582,10 +2284,13 @@ impl menu::action::MenuAction for MenuAction {
             MenuAction::About => Message::ToggleContextPage(ContextPage::About),
             MenuAction::DuplicateApp => Message::Editor(editor::Message::Duplicate),
             MenuAction::ExportApps => Message::ExportApps,
+            MenuAction::ExportBundle => Message::ExportBundle,
             MenuAction::FocusSearch => Message::FocusSearch,
             MenuAction::ImportApps => Message::ImportApps,
+            MenuAction::ImportFromBrowsers => Message::ImportFromBrowsers,
             MenuAction::LaunchApp => Message::Editor(editor::Message::LaunchApp),
             MenuAction::NewApp => Message::ReloadNavbarItems,
+            MenuAction::Profiles => Message::ToggleContextPage(ContextPage::Profiles),
             MenuAction::Save => Message::Editor(editor::Message::Done),
             MenuAction::Settings => Message::ToggleContextPage(ContextPage::Settings),
         }
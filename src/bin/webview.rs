@@ -1,13 +1,46 @@
 use clap::Parser;
+use gtk::{
+    gio::{self, NetworkMonitorExt as _, TlsCertificateExt as _},
+    glib::ObjectExt as _,
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 use tao::{
     event::{Event, WindowEvent},
-    event_loop::{ControlFlow, EventLoopBuilder},
+    event_loop::{ControlFlow, EventLoopBuilder, EventLoopWindowTarget},
     platform::unix::EventLoopBuilderExtUnix,
-    window::{WindowAttributes, WindowBuilder},
+    window::{Fullscreen, Window, WindowAttributes, WindowBuilder, WindowId},
 };
+use tokio::sync::oneshot;
 use url::Url;
+use webapps::{
+    browser::{Browser, CookiePolicy, WebRtcPolicy},
+    inspector::matches_domain,
+    instance::{Instance, Request},
+    kiosk::{DEFAULT_EXIT_SHORTCUT, KioskConfig, Shortcut},
+    proxy::ProxyConfig,
+    site_data::{COOKIES_FILE, SiteData},
+    tls::CertificatePin,
+};
+use webkit2gtk::{
+    AuthenticationRequest, AuthenticationRequestExt as _, AuthenticationScheme, CookieAcceptPolicy,
+    CookieManager, CookieManagerExt as _, CookiePersistentStorage, Credential,
+    CredentialPersistence, LoadEvent, NavigationPolicyDecision, NavigationPolicyDecisionExt as _,
+    NavigationType, NetworkError, NetworkProxyMode, NetworkProxySettings,
+    PermissionRequestExt as _, PolicyDecision, PolicyDecisionExt as _, PolicyDecisionType,
+    PolicyError, PrintOperation, PrintOperationExt as _, SettingsExt as _, URIRequest,
+    URIRequestExt as _, UserMediaPermissionRequest, UserMediaPermissionRequestExt as _,
+    WebContextExt as _, WebProcessTerminationReason, WebViewExt as _, WebsiteData,
+    WebsiteDataManager, WebsiteDataManagerExt as _, WebsiteDataManagerExtManual as _,
+    WebsiteDataTypes,
+};
 use wry::{
-    WebContext, WebViewBuilder,
+    WebContext, WebView, WebViewBuilder, WebViewExtUnix as _,
     dpi::{LogicalSize, Size},
 };
 
@@ -18,6 +51,287 @@ fn is_url_safe(url_str: &str) -> bool {
     }
 }
 
+#[derive(Debug)]
+enum UserEvent {
+    /// A launch forwarded by another process using the same profile.
+    Open(String),
+    /// Website data of the profile to clear, requested by the app manager.
+    ClearData(Vec<SiteData>, oneshot::Sender<Result<(), String>>),
+    /// A domain to delete all website data of, requested by the app manager.
+    ClearDomain(String, oneshot::Sender<Result<(), String>>),
+    /// The computer woke from suspend.
+    Resumed,
+    /// An app to show the print dialog for, requested over D-Bus.
+    Print(String),
+    /// An app to save the page of as PDF, requested over D-Bus.
+    SavePdf(String, oneshot::Sender<Result<String, String>>),
+}
+
+/// A web app window and the webview inside it.
+struct AppWindow {
+    app_id: String,
+    window: Window,
+    webview: WebView,
+    /// Cookie policy the shared cookie manager needs for this app.
+    cookie_policy: CookiePolicy,
+    // #59: Minimize to background on close
+    minimize_on_close: bool,
+    /// Reloads a failed page of the window when the network is back.
+    network_handler: Option<gtk::glib::SignalHandlerId>,
+    reload: ReloadSchedule,
+    kiosk: Option<Kiosk>,
+}
+
+impl AppWindow {
+    /// Reload or reset the page when one of its timers is due.
+    fn run_timers(&mut self, now: Instant) {
+        if self.reload.next.is_some_and(|next| next <= now) {
+            self.reload();
+        }
+        if let Some(kiosk) = self.kiosk.as_ref().filter(|kiosk| kiosk.is_idle(now)) {
+            kiosk.last_input.set(None);
+            if let Err(e) = self.webview.load_url(&kiosk.home) {
+                eprintln!("Failed to reset kiosk '{}': {e}", self.app_id);
+            }
+        }
+    }
+
+    /// The time the event loop has to wake up for the timers of the window.
+    fn next_timer(&self) -> Option<Instant> {
+        let idle = self.kiosk.as_ref().and_then(Kiosk::idle_deadline);
+        self.reload.next.into_iter().chain(idle).min()
+    }
+
+    /// Reload the page and start the reload interval over.
+    fn reload(&mut self) {
+        if let Err(e) = self.webview.reload() {
+            eprintln!("Failed to reload '{}': {e}", self.app_id);
+        }
+        self.reload.next = self
+            .reload
+            .interval
+            .map(|interval| Instant::now() + interval);
+    }
+}
+
+/// When the page of a window reloads on its own. The event loop keeps the
+/// time, so pages that are throttled or frozen in the background reload too.
+struct ReloadSchedule {
+    interval: Option<Duration>,
+    /// Time of the next reload by `interval`.
+    next: Option<Instant>,
+    on_resume: bool,
+    /// Time in the background after which focusing the window reloads it.
+    focus_after: Option<Duration>,
+    unfocused_since: Option<Instant>,
+}
+
+/// A window shown as a kiosk, see [`webapps::kiosk`].
+struct Kiosk {
+    /// The start page, loaded again after a crash or idle time.
+    home: String,
+    idle_reset: Option<Duration>,
+    /// Time of the last input, `None` if there was none since the start page
+    /// was loaded again.
+    last_input: Rc<Cell<Option<Instant>>>,
+    /// Set by the exit shortcut, kiosks ignore all other requests to close.
+    exit: Rc<Cell<bool>>,
+}
+
+impl Kiosk {
+    fn is_idle(&self, now: Instant) -> bool {
+        self.idle_reset
+            .zip(self.last_input.get())
+            .is_some_and(|(idle_reset, last_input)| last_input + idle_reset <= now)
+    }
+
+    fn idle_deadline(&self) -> Option<Instant> {
+        // without input there is nothing to reset, only look again later
+        let since = self.last_input.get().unwrap_or_else(Instant::now);
+        self.idle_reset.map(|idle_reset| since + idle_reset)
+    }
+}
+
+impl ReloadSchedule {
+    fn new(browser: &Browser) -> Self {
+        let minutes = |minutes: Option<u32>| {
+            minutes
+                .filter(|minutes| *minutes > 0)
+                .map(|minutes| Duration::from_secs(u64::from(minutes) * 60))
+        };
+        let interval = minutes(browser.reload_interval);
+
+        Self {
+            interval,
+            next: interval.map(|interval| Instant::now() + interval),
+            on_resume: browser.reload_on_resume.unwrap_or(false),
+            focus_after: minutes(browser.reload_on_focus),
+            unfocused_since: None,
+        }
+    }
+}
+
+impl Drop for AppWindow {
+    fn drop(&mut self) {
+        if let Some(handler) = self.network_handler.take() {
+            gio::NetworkMonitor::default().disconnect(handler);
+        }
+    }
+}
+
+type WindowResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// A page that failed with TLS errors, waiting for the user to trust its
+/// certificate on the error page.
+struct TlsFailure {
+    webview: webkit2gtk::WebView,
+    uri: String,
+    host: String,
+    certificate: gio::TlsCertificate,
+    /// Secret of the error page, so no other page can trust a certificate.
+    token: String,
+}
+
+type PendingTrust = Rc<RefCell<Option<TlsFailure>>>;
+
+/// Content shown instead of a page that failed to load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fallback {
+    ErrorPage,
+    SavedCopy,
+}
+
+/// Load failures of a window, followed across the load events of WebKit.
+#[derive(Default)]
+struct LoadState {
+    /// Page that failed to load, loaded again when the network is back.
+    failed_uri: Option<String>,
+    /// Fallback requested by the last failure, not started yet.
+    pending: Option<Fallback>,
+    /// Fallback shown by the current load, `None` for pages of the site.
+    current: Option<Fallback>,
+    /// Whether the current load failed.
+    failed: bool,
+}
+
+impl LoadState {
+    /// Note that the current load failed and `fallback` is shown instead.
+    fn fail(&mut self, fallback: Fallback) {
+        self.failed = true;
+        self.pending = Some(fallback);
+    }
+}
+
+type SharedLoadState = Rc<RefCell<LoadState>>;
+
+/// Name and icon of the app for its error page.
+struct AppInfo {
+    name: String,
+    /// The icon as a data URI.
+    icon: Option<String>,
+}
+
+/// What was entered in a [`LoginDialog`].
+struct Entered {
+    username: String,
+    password: String,
+    remember: bool,
+}
+
+/// A native dialog asking for a password, and a user name for HTTP logins.
+struct LoginDialog {
+    dialog: gtk::Dialog,
+    username: Option<gtk::Entry>,
+    password: gtk::Entry,
+    remember: gtk::CheckButton,
+}
+
+impl LoginDialog {
+    fn new(parent: &gtk::ApplicationWindow, message: &str, username: Option<&str>) -> Self {
+        use gtk::prelude::*;
+
+        let cancel = webapps::fl!("cancel");
+        let sign_in = webapps::fl!("auth-sign-in");
+        let dialog = gtk::Dialog::with_buttons(
+            Some(webapps::fl!("auth-title").as_str()),
+            Some(parent),
+            gtk::DialogFlags::MODAL | gtk::DialogFlags::DESTROY_WITH_PARENT,
+            &[
+                (cancel.as_str(), gtk::ResponseType::Cancel),
+                (sign_in.as_str(), gtk::ResponseType::Accept),
+            ],
+        );
+        dialog.set_default_response(gtk::ResponseType::Accept);
+
+        let content = dialog.content_area();
+        content.set_spacing(12);
+        content.set_border_width(18);
+
+        let label = gtk::Label::new(Some(message));
+        label.set_line_wrap(true);
+        label.set_max_width_chars(48);
+        label.set_xalign(0.0);
+        content.add(&label);
+
+        let username = username.map(|name| {
+            let entry = gtk::Entry::new();
+            entry.set_placeholder_text(Some(webapps::fl!("auth-username").as_str()));
+            entry.set_text(name);
+            entry.set_activates_default(true);
+            content.add(&entry);
+            entry
+        });
+
+        let password = gtk::Entry::new();
+        password.set_placeholder_text(Some(webapps::fl!("auth-password").as_str()));
+        password.set_visibility(false);
+        password.set_input_purpose(gtk::InputPurpose::Password);
+        password.set_activates_default(true);
+        content.add(&password);
+
+        let remember = gtk::CheckButton::with_label(&webapps::fl!("auth-remember"));
+        content.add(&remember);
+
+        Self {
+            dialog,
+            username,
+            password,
+            remember,
+        }
+    }
+
+    /// Show the dialog and call `done` with what was entered, or `None` if it
+    /// was cancelled.
+    fn run(self, done: impl FnOnce(Option<Entered>) + 'static) {
+        use gtk::prelude::*;
+
+        let Self {
+            dialog,
+            username,
+            password,
+            remember,
+        } = self;
+        let done = std::cell::Cell::new(Some(done));
+        dialog.connect_response(move |dialog, response| {
+            // closing the dialog answers it a second time
+            let Some(done) = done.take() else {
+                return;
+            };
+            let entered = (response == gtk::ResponseType::Accept).then(|| Entered {
+                username: username
+                    .as_ref()
+                    .map(|entry| entry.text().to_string())
+                    .unwrap_or_default(),
+                password: password.text().to_string(),
+                remember: remember.is_active(),
+            });
+            dialog.close();
+            done(entered);
+        });
+        dialog.show_all();
+    }
+}
+
 fn main() -> wry::Result<()> {
     let args = webapps::WebviewArgs::parse();
 
@@ -26,10 +340,9 @@ fn main() -> wry::Result<()> {
         std::process::exit(1);
     }
 
-    gtk::glib::set_program_name(args.id.clone().into());
     gtk::glib::set_application_name(&args.id);
 
-    let mut browser = match webapps::browser::Browser::from_appid(&args.id) {
+    let mut browser = match Browser::from_appid(&args.id) {
         Some(b) => b,
         None => {
             eprintln!("Failed to load web app configuration for '{}'", args.id);
@@ -41,16 +354,1295 @@ fn main() -> wry::Result<()> {
     if args.private {
         browser.private_mode = Some(true);
     }
+    if args.kiosk && browser.kiosk.is_none() {
+        browser.kiosk = Some(KioskConfig::default());
+    }
+
+    let event_loop = EventLoopBuilder::<UserEvent>::with_user_event()
+        .with_any_thread(true)
+        .build();
+
+    // Apps sharing a persistent profile run in the process that opened the
+    // profile first, WebKit cannot use a data directory from two processes.
+    let profile = browser
+        .profile
+        .clone()
+        .filter(|_| !browser.private_mode.unwrap_or(false));
+    let instance = match &profile {
+        Some(profile) => {
+            let proxy = Mutex::new(event_loop.create_proxy());
+            let handler = move |request| {
+                let event = match request {
+                    Request::Open(app_id) => UserEvent::Open(app_id),
+                    Request::ClearData(data, done) => UserEvent::ClearData(data, done),
+                    Request::ClearDomain(domain, done) => UserEvent::ClearDomain(domain, done),
+                    Request::Print(app_id) => UserEvent::Print(app_id),
+                    Request::SavePdf(app_id, done) => UserEvent::SavePdf(app_id, done),
+                };
+                if let Ok(proxy) = proxy.lock() {
+                    let _ = proxy.send_event(event);
+                }
+            };
+
+            match webapps::instance::acquire(profile, &args.id, handler) {
+                Ok(Instance::Forwarded) => return Ok(()),
+                Ok(Instance::Primary(primary)) => Some(primary),
+                Err(e) => {
+                    eprintln!("Failed to share profile '{profile}' with other web apps: {e}");
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
+    let data_dir = browser.profile.as_deref().and_then(webapps::profile::path);
+    let mut context = WebContext::new(data_dir.clone());
+    let network = browser.network_settings();
+
+    let mut windows: HashMap<WindowId, AppWindow> = HashMap::new();
+    match open_window(&event_loop, &mut context, browser, true) {
+        Ok(window) => {
+            windows.insert(window.window.id(), window);
+        }
+        Err(e) => {
+            eprintln!("Failed to open '{}': {e}", args.id);
+            std::process::exit(1);
+        }
+    }
+
+    // A temporary profile keeps its cookies in memory only
+    if let (Some(dir), Some(cookies)) = (&data_dir, cookie_manager(&windows)) {
+        cookies.set_persistent_storage(
+            &dir.join(COOKIES_FILE).to_string_lossy(),
+            CookiePersistentStorage::Text,
+        );
+    }
+    apply_cookie_policy(&windows);
+
+    let resume_proxy = event_loop.create_proxy();
+    // the subscription lasts as long as the connection, i.e. the process
+    let _resume_watch = watch_resume(move || {
+        let _ = resume_proxy.send_event(UserEvent::Resumed);
+    })
+    .inspect_err(|e| eprintln!("Failed to watch for resume from suspend: {e}"));
+
+    event_loop.run(move |event, target, control_flow| {
+        let now = Instant::now();
+        for open in windows.values_mut() {
+            open.run_timers(now);
+        }
+        *control_flow = match windows.values().filter_map(AppWindow::next_timer).min() {
+            Some(next) => ControlFlow::WaitUntil(next),
+            None => ControlFlow::Wait,
+        };
+
+        match event {
+            Event::UserEvent(UserEvent::Open(app_id)) => {
+                // Bring back a window that is already open, e.g. one minimized to background
+                if let Some(open) = windows.values().find(|w| w.app_id == app_id) {
+                    open.window.set_visible(true);
+                    open.window.set_focus();
+                    return;
+                }
+
+                let browser = match Browser::from_appid(&app_id) {
+                    Some(browser) if browser.profile == profile => browser,
+                    _ => {
+                        eprintln!("Refusing to open '{app_id}': it does not use this profile");
+                        return;
+                    }
+                };
+                if browser.network_settings() != network {
+                    eprintln!(
+                        "'{app_id}' shares its profile with an app using another proxy or \
+                         other languages, it uses those of '{}'",
+                        args.id
+                    );
+                }
+
+                match open_window(target, &mut context, browser, false) {
+                    Ok(window) => {
+                        windows.insert(window.window.id(), window);
+                    }
+                    Err(e) => eprintln!("Failed to open '{app_id}': {e}"),
+                }
+            }
+            Event::UserEvent(UserEvent::ClearData(data, done)) => {
+                match data_manager(&windows) {
+                    Some(manager) => manager.clear(
+                        website_data_types(&data),
+                        gtk::glib::TimeSpan::from_seconds(0),
+                        None::<&gtk::gio::Cancellable>,
+                        move |result| {
+                            let _ = done.send(result.map_err(|e| e.to_string()));
+                        },
+                    ),
+                    None => {
+                        let _ = done.send(Err("no website data manager".to_string()));
+                    }
+                }
+                return;
+            }
+            Event::UserEvent(UserEvent::ClearDomain(domain, done)) => {
+                match data_manager(&windows) {
+                    Some(manager) => clear_domain(manager, domain, done),
+                    None => {
+                        let _ = done.send(Err("no website data manager".to_string()));
+                    }
+                }
+                return;
+            }
+            Event::UserEvent(UserEvent::Print(app_id)) => {
+                use tao::platform::unix::WindowExtUnix;
+                if let Some(open) = windows.values().find(|w| w.app_id == app_id) {
+                    open.window.set_visible(true);
+                    let webview = open.webview.webview();
+                    let parent = open.window.gtk_window().clone();
+                    // the dialog runs a main loop of its own, so not inside this callback
+                    gtk::glib::idle_add_local_once(move || {
+                        run_print_dialog(&PrintOperation::new(&webview), &parent)
+                    });
+                }
+                return;
+            }
+            Event::UserEvent(UserEvent::SavePdf(app_id, done)) => {
+                match windows.values().find(|w| w.app_id == app_id) {
+                    Some(open) => save_pdf(&open.webview.webview(), move |result| {
+                        let result = result.map(|path| path.to_string_lossy().into_owned());
+                        let _ = done.send(result);
+                    }),
+                    None => {
+                        let _ = done.send(Err(format!("'{app_id}' is not open")));
+                    }
+                }
+                return;
+            }
+            Event::UserEvent(UserEvent::Resumed) => {
+                for open in windows.values_mut().filter(|w| w.reload.on_resume) {
+                    open.reload();
+                }
+                return;
+            }
+            Event::WindowEvent {
+                window_id,
+                event: WindowEvent::Focused(focused),
+                ..
+            } => {
+                if let Some(open) = windows.get_mut(&window_id) {
+                    let unfocused_since = open.reload.unfocused_since.take();
+                    if !focused {
+                        open.reload.unfocused_since = Some(Instant::now());
+                    } else if unfocused_since
+                        .zip(open.reload.focus_after)
+                        .is_some_and(|(since, after)| since.elapsed() >= after)
+                    {
+                        open.reload();
+                    }
+                }
+                return;
+            }
+            Event::WindowEvent {
+                window_id,
+                event: WindowEvent::CloseRequested,
+                ..
+            } => {
+                match windows.get(&window_id) {
+                    Some(open) if open.kiosk.as_ref().is_some_and(|k| !k.exit.get()) => return,
+                    Some(open) if open.minimize_on_close => open.window.set_visible(false),
+                    Some(_) => {
+                        windows.remove(&window_id);
+                    }
+                    None => {}
+                }
+
+                if windows.is_empty() {
+                    *control_flow = ControlFlow::Exit;
+                }
+            }
+            _ => return,
+        }
+
+        apply_cookie_policy(&windows);
+        if let Some(instance) = &instance {
+            instance.set_apps(windows.values().map(|w| w.app_id.as_str()));
+        }
+    });
+}
+
+/// Call `resumed` whenever logind announces that the computer woke from
+/// suspend.
+fn watch_resume(resumed: impl Fn() + 'static) -> WindowResult<gio::DBusConnection> {
+    let connection = gio::bus_get_sync(gio::BusType::System, None::<&gio::Cancellable>)?;
+    connection.signal_subscribe(
+        Some("org.freedesktop.login1"),
+        Some("org.freedesktop.login1.Manager"),
+        Some("PrepareForSleep"),
+        Some("/org/freedesktop/login1"),
+        None,
+        gio::DBusSignalFlags::NONE,
+        move |_, _, _, _, _, parameters| {
+            // sent with `true` before suspending and `false` after waking up
+            if parameters.get::<(bool,)>() == Some((false,)) {
+                resumed();
+            }
+        },
+    );
+
+    Ok(connection)
+}
+
+/// The data manager of the profile. All windows share one context, so any of
+/// them reaches it.
+fn data_manager(windows: &HashMap<WindowId, AppWindow>) -> Option<WebsiteDataManager> {
+    windows
+        .values()
+        .next()
+        .and_then(|w| w.webview.webview().website_data_manager())
+}
+
+/// The cookie manager of the profile, shared by all windows.
+fn cookie_manager(windows: &HashMap<WindowId, AppWindow>) -> Option<CookieManager> {
+    data_manager(windows).and_then(|manager| manager.cookie_manager())
+}
+
+/// The cookie manager is shared by all windows, so it gets the most permissive
+/// policy any of them needs. Stricter apps are held to their own policy by the
+/// content rules of their webview, see [`cookie_rules`].
+fn apply_cookie_policy(windows: &HashMap<WindowId, AppWindow>) {
+    let Some(policy) = windows.values().map(|w| w.cookie_policy).min() else {
+        return;
+    };
+
+    if let Some(cookies) = cookie_manager(windows) {
+        cookies.set_accept_policy(match policy {
+            CookiePolicy::Always => CookieAcceptPolicy::Always,
+            CookiePolicy::NoThirdParty => CookieAcceptPolicy::NoThirdParty,
+            CookiePolicy::Never => CookieAcceptPolicy::Never,
+        });
+    }
+}
+
+/// Allow a certificate signed by the CA bundle of the app, or show the
+/// certificate error page.
+fn handle_tls_error(
+    webview: &webkit2gtk::WebView,
+    uri: &str,
+    certificate: &gio::TlsCertificate,
+    errors: gio::TlsCertificateFlags,
+    ca_bundle: Option<&str>,
+    pending: &PendingTrust,
+    load_state: &SharedLoadState,
+) -> bool {
+    let Some(host) = Url::parse(uri)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+    else {
+        return false;
+    };
+
+    if ca_bundle.is_some_and(|path| signed_by_bundle(certificate, &host, path)) {
+        if let Some(context) = webview.context() {
+            context.allow_tls_certificate_for_host(certificate, &host);
+            load_state.borrow_mut().failed = true;
+            webview.load_uri(uri);
+            return true;
+        }
+    }
+
+    let token = format!("{:032x}", rand::random::<u128>());
+    load_state.borrow_mut().fail(Fallback::ErrorPage);
+    webview.load_alternate_html(
+        &tls_error_page(&host, certificate, errors, &token),
+        uri,
+        None,
+    );
+    *pending.borrow_mut() = Some(TlsFailure {
+        webview: webview.clone(),
+        uri: uri.to_string(),
+        host,
+        certificate: certificate.clone(),
+        token,
+    });
+    true
+}
+
+/// Whether `certificate` is valid for `host` when trusting the CA bundle at
+/// `path`.
+fn signed_by_bundle(certificate: &gio::TlsCertificate, host: &str, path: &str) -> bool {
+    let identity = gio::NetworkAddress::new(host, 443);
+    match gio::TlsCertificate::list_new_from_file(path) {
+        Ok(authorities) => authorities
+            .iter()
+            .any(|ca| certificate.verify(Some(&identity), Some(ca)).is_empty()),
+        Err(e) => {
+            eprintln!("Failed to read CA bundle '{path}': {e}");
+            false
+        }
+    }
+}
+
+/// Pin the certificate of the error page holding `token` and load the page
+/// again.
+fn trust_certificate(pending: &PendingTrust, app_id: &str, token: &str) {
+    let Some(failure) = pending.borrow_mut().take_if(|f| f.token == token) else {
+        eprintln!("Refusing to trust a certificate outside of the error page");
+        return;
+    };
+
+    let pin = CertificatePin {
+        host: failure.host.clone(),
+        sha256: certificate_fingerprint(&failure.certificate),
+        pem: failure
+            .certificate
+            .certificate_pem()
+            .map(|pem| pem.to_string())
+            .unwrap_or_default(),
+    };
+    match webapps::launcher::WebAppLauncher::from_appid(app_id) {
+        Some(mut launcher) => {
+            launcher.browser.trust_certificate(pin);
+            if let Err(e) = launcher.save() {
+                eprintln!("Failed to save the trusted certificate: {e}");
+            }
+        }
+        None => eprintln!("Failed to save the trusted certificate: '{app_id}' not found"),
+    }
+
+    if let Some(context) = failure.webview.context() {
+        context.allow_tls_certificate_for_host(&failure.certificate, &failure.host);
+    }
+    failure.webview.load_uri(&failure.uri);
+}
+
+/// Answer a site asking for a login or a client certificate. Returns whether
+/// the request is handled, WebKit continues without credentials otherwise.
+fn handle_authentication(
+    parent: &gtk::ApplicationWindow,
+    request: &AuthenticationRequest,
+    app_id: &str,
+    client_certificate: Option<&str>,
+) -> bool {
+    match (request.scheme(), client_certificate) {
+        (
+            AuthenticationScheme::Default
+            | AuthenticationScheme::HttpBasic
+            | AuthenticationScheme::HttpDigest
+            | AuthenticationScheme::Ntlm,
+            _,
+        ) => {
+            request_login(parent, request, app_id);
+            true
+        }
+        (AuthenticationScheme::ClientCertificateRequested, Some(path)) => {
+            request_client_certificate(parent, request, app_id, path);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Answer an HTTP login request with the login saved in the keyring, or ask
+/// for one.
+fn request_login(parent: &gtk::ApplicationWindow, request: &AuthenticationRequest, app_id: &str) {
+    let host = request
+        .host()
+        .map(|host| host.to_string())
+        .unwrap_or_default();
+    let realm = request
+        .realm()
+        .map(|realm| realm.to_string())
+        .unwrap_or_default();
+
+    // a retry means the saved login was refused
+    if !request.is_retry() {
+        match webapps::credentials::lookup_login(app_id, &host, &realm) {
+            Ok(Some(login)) => {
+                let credential = Credential::new(
+                    &login.username,
+                    &login.password,
+                    CredentialPersistence::ForSession,
+                );
+                authenticate(request, Some(&credential));
+                return;
+            }
+            Ok(None) => (),
+            Err(e) => eprintln!("Failed to read the saved login for {host}: {e}"),
+        }
+    }
+
+    let args = HashMap::from([("host", host.as_str()), ("realm", realm.as_str())]);
+    let message = if request.is_retry() {
+        webapps::fl!("auth-retry", args)
+    } else {
+        webapps::fl!("auth-description", args)
+    };
+    let username = request
+        .proposed_credential()
+        .and_then(|mut credential| credential.username())
+        .map(|username| username.to_string())
+        .unwrap_or_default();
+
+    let request = request.clone();
+    let app_id = app_id.to_string();
+    LoginDialog::new(parent, &message, Some(&username)).run(move |entered| {
+        let Some(entered) = entered else {
+            request.cancel();
+            return;
+        };
+
+        let credential = Credential::new(
+            &entered.username,
+            &entered.password,
+            CredentialPersistence::ForSession,
+        );
+        authenticate(&request, Some(&credential));
+        if entered.remember {
+            let login = webapps::credentials::Login {
+                username: entered.username,
+                password: entered.password,
+            };
+            if let Err(e) = webapps::credentials::store_login(&app_id, &host, &realm, &login) {
+                eprintln!("Failed to save the login for {host}: {e}");
+            }
+        }
+    });
+}
+
+/// Answer a request for a client certificate with the PKCS#12 file at `path`,
+/// asking for its password unless the keyring has it or it has none.
+fn request_client_certificate(
+    parent: &gtk::ApplicationWindow,
+    request: &AuthenticationRequest,
+    app_id: &str,
+    path: &str,
+) {
+    let data = match webapps::tls::read_client_certificate(path) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to read the client certificate: {e}");
+            authenticate(request, None);
+            return;
+        }
+    };
+
+    let saved =
+        webapps::credentials::lookup_certificate_password(app_id, path).unwrap_or_else(|e| {
+            eprintln!("Failed to read the saved client certificate password: {e}");
+            None
+        });
+    let certificate = [saved.as_deref(), None]
+        .into_iter()
+        .find_map(|password| gio::TlsCertificate::from_pkcs12(&data, password).ok());
+
+    match certificate {
+        Some(certificate) => {
+            let credential =
+                Credential::for_certificate(Some(&certificate), CredentialPersistence::ForSession);
+            authenticate(request, Some(&credential));
+        }
+        None => ask_certificate_password(
+            parent,
+            request.clone(),
+            app_id.to_string(),
+            path,
+            data,
+            false,
+        ),
+    }
+}
+
+/// Ask for the password of the client certificate `data` until it opens the
+/// file or the dialog is cancelled.
+fn ask_certificate_password(
+    parent: &gtk::ApplicationWindow,
+    request: AuthenticationRequest,
+    app_id: String,
+    path: &str,
+    data: Vec<u8>,
+    retry: bool,
+) {
+    let host = request
+        .host()
+        .map(|host| host.to_string())
+        .unwrap_or_default();
+    let file = std::path::Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let args = HashMap::from([("host", host.as_str()), ("file", file.as_str())]);
+    let message = if retry {
+        webapps::fl!("client-certificate-retry", args)
+    } else {
+        webapps::fl!("client-certificate-password", args)
+    };
+
+    let dialog_parent = parent.clone();
+    let path = path.to_string();
+    LoginDialog::new(parent, &message, None).run(move |entered| {
+        let Some(entered) = entered else {
+            request.cancel();
+            return;
+        };
+
+        match gio::TlsCertificate::from_pkcs12(&data, Some(&entered.password)) {
+            Ok(certificate) => {
+                let credential = Credential::for_certificate(
+                    Some(&certificate),
+                    CredentialPersistence::ForSession,
+                );
+                authenticate(&request, Some(&credential));
+                if entered.remember {
+                    if let Err(e) = webapps::credentials::store_certificate_password(
+                        &app_id,
+                        &path,
+                        &entered.password,
+                    ) {
+                        eprintln!("Failed to save the client certificate password: {e}");
+                    }
+                }
+            }
+            Err(e) if e.matches(gio::TlsError::BadCertificatePassword) => {
+                ask_certificate_password(&dialog_parent, request, app_id, &path, data, true);
+            }
+            Err(e) => {
+                eprintln!("Failed to open the client certificate: {e}");
+                authenticate(&request, None);
+            }
+        }
+    });
+}
+
+/// Answer `request` with `credential`, or continue without one if it is
+/// `None`. The webkit2gtk bindings leave this call out.
+fn authenticate(request: &AuthenticationRequest, credential: Option<&Credential>) {
+    use gtk::glib::translate::{ToGlibPtr as _, mut_override};
+
+    // SAFETY: both pointers are valid for the call, WebKit copies the
+    // credential
+    unsafe {
+        webkit2gtk::ffi::webkit_authentication_request_authenticate(
+            request.to_glib_none().0,
+            mut_override(credential.to_glib_none().0),
+        );
+    }
+}
+
+/// A property of `certificate` added in GLib 2.70, if the running GLib has it.
+fn certificate_property<T>(certificate: &gio::TlsCertificate, name: &str) -> Option<T>
+where
+    T: 'static,
+    Option<T>: for<'a> gtk::glib::value::FromValue<'a>,
+{
+    certificate.find_property(name)?;
+    certificate.property::<Option<T>>(name)
+}
+
+fn certificate_fingerprint(certificate: &gio::TlsCertificate) -> String {
+    certificate
+        .certificate()
+        .map(|der| webapps::tls::fingerprint(&der))
+        .unwrap_or_default()
+}
+
+/// Page explaining why the certificate of `host` is not trusted.
+fn tls_error_page(
+    host: &str,
+    certificate: &gio::TlsCertificate,
+    errors: gio::TlsCertificateFlags,
+    token: &str,
+) -> String {
+    use gio::TlsCertificateFlags as Flags;
+
+    let host_arg = HashMap::from([("host", host)]);
+    let reasons: String = [
+        (Flags::UNKNOWN_CA, webapps::fl!("tls-error-unknown-ca")),
+        (Flags::BAD_IDENTITY, webapps::fl!("tls-error-bad-identity")),
+        (
+            Flags::NOT_ACTIVATED,
+            webapps::fl!("tls-error-not-activated"),
+        ),
+        (Flags::EXPIRED, webapps::fl!("tls-error-expired")),
+        (Flags::REVOKED, webapps::fl!("tls-error-revoked")),
+        (Flags::INSECURE, webapps::fl!("tls-error-insecure")),
+        (Flags::GENERIC_ERROR, webapps::fl!("tls-error-generic")),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| errors.contains(*flag))
+    .map(|(_, reason)| format!("<li>{}</li>", html_escape(&reason)))
+    .collect();
+
+    let date = |name: &str| {
+        certificate_property::<gtk::glib::DateTime>(certificate, name)
+            .and_then(|date| date.format("%Y-%m-%d").ok())
+            .map(|date| date.to_string())
+    };
+    let details: String = [
+        (
+            webapps::fl!("tls-error-subject"),
+            certificate_property::<String>(certificate, "subject-name"),
+        ),
+        (
+            webapps::fl!("tls-error-issuer"),
+            certificate_property::<String>(certificate, "issuer-name"),
+        ),
+        (
+            webapps::fl!("tls-error-valid-from"),
+            date("not-valid-before"),
+        ),
+        (
+            webapps::fl!("tls-error-valid-until"),
+            date("not-valid-after"),
+        ),
+        (
+            webapps::fl!("tls-error-fingerprint"),
+            Some(certificate_fingerprint(certificate)),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(label, value)| {
+        value.map(|value| {
+            format!(
+                "<dt>{}</dt><dd>{}</dd>",
+                html_escape(&label),
+                html_escape(&value)
+            )
+        })
+    })
+    .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>{title}</title><style>
+body {{ font-family: sans-serif; max-width: 40em; margin: 4em auto; padding: 0 1em; color: #333; }}
+@media (prefers-color-scheme: dark) {{ body {{ background: #1b1b1b; color: #ddd; }} }}
+dt {{ font-weight: bold; margin-top: .6em; }}
+dd {{ margin: 0; font-family: monospace; word-break: break-all; }}
+button {{ margin: 1.5em .5em 0 0; padding: .5em 1em; }}
+</style></head><body>
+<h1>{title}</h1>
+<p>{description}</p>
+<ul>{reasons}</ul>
+<dl>{details}</dl>
+<p>{trust_description}</p>
+<button onclick="location.reload()">{retry}</button>
+<button onclick="window.ipc.postMessage(JSON.stringify({{ type: 'trust_certificate', token: '{token}' }}))">{trust}</button>
+</body></html>"#,
+        title = html_escape(&webapps::fl!("tls-error-title")),
+        description = html_escape(&webapps::fl!("tls-error-description", host_arg.clone())),
+        trust_description = html_escape(&webapps::fl!("tls-error-trust-description", host_arg)),
+        retry = html_escape(&webapps::fl!("tls-error-retry")),
+        trust = html_escape(&webapps::fl!("tls-error-trust")),
+    )
+}
+
+/// Show the saved copy of the app or its error page instead of a page that
+/// failed to load.
+fn handle_load_failed(
+    webview: &webkit2gtk::WebView,
+    uri: &str,
+    error: &gtk::glib::Error,
+    app: &AppInfo,
+    offline_copy: Option<&std::path::Path>,
+    state: &SharedLoadState,
+) -> bool {
+    // stopped loads and downloads are no failures
+    if error.matches(NetworkError::Cancelled)
+        || error.matches(PolicyError::FrameLoadInterruptedByPolicyChange)
+    {
+        return false;
+    }
+
+    let copy = offline_copy
+        .filter(|_| !gio::NetworkMonitor::default().is_network_available())
+        .and_then(|path| std::fs::read(path).ok());
+    {
+        // loading the fallback may emit load events right away
+        let mut state = state.borrow_mut();
+        state.failed_uri = Some(uri.to_string());
+        state.fail(if copy.is_some() {
+            Fallback::SavedCopy
+        } else {
+            Fallback::ErrorPage
+        });
+    }
+
+    match copy {
+        Some(copy) => webview.load_bytes(
+            &gtk::glib::Bytes::from_owned(copy),
+            Some("multipart/related"),
+            None,
+            Some(uri),
+        ),
+        None => webview.load_alternate_html(&load_error_page(app, uri, error), uri, None),
+    }
+    true
+}
+
+/// Follow the loads of `webview`, saving a copy of each page of the site that
+/// loaded to `offline_copy`.
+fn handle_load_changed(
+    webview: &webkit2gtk::WebView,
+    event: LoadEvent,
+    offline_copy: Option<&std::path::Path>,
+    state: &SharedLoadState,
+) {
+    let mut state = state.borrow_mut();
+    match event {
+        LoadEvent::Started => {
+            state.current = state.pending.take();
+            if state.current.is_none() {
+                state.failed = false;
+            }
+        }
+        LoadEvent::Finished => match state.current {
+            Some(Fallback::SavedCopy) => {
+                let message =
+                    serde_json::to_string(&webapps::fl!("offline-copy-banner")).unwrap_or_default();
+                let banner = SAVED_COPY_BANNER.replace("{message}", &message);
+                webview.run_javascript(&banner, None::<&gio::Cancellable>, |_| ());
+            }
+            Some(Fallback::ErrorPage) => (),
+            None if state.failed => (),
+            None => {
+                state.failed_uri = None;
+                if let Some(path) = offline_copy {
+                    save_offline_copy(webview, path.to_path_buf());
+                }
+            }
+        },
+        _ => (),
+    }
+}
+
+/// Marks a saved copy as such, `{message}` is replaced by a JSON string.
+const SAVED_COPY_BANNER: &str = r#"
+(() => {
+  if (document.getElementById('webapps-offline-banner')) return;
+  const banner = document.createElement('div');
+  banner.id = 'webapps-offline-banner';
+  banner.textContent = {message};
+  banner.style.cssText = 'position:fixed;top:0;left:0;right:0;z-index:2147483647;' +
+    'padding:.5em 1em;background:#333;color:#fff;font:14px sans-serif;text-align:center';
+  document.documentElement.appendChild(banner);
+})();
+"#;
+
+/// Largest saved copy of a page that is kept.
+const MAX_OFFLINE_COPY_SIZE: u64 = 32 * 1024 * 1024;
+
+/// Size of the app icon shown on the offline page.
+const OFFLINE_ICON_SIZE: u32 = 96;
+
+/// Save the page shown by `webview` as MHTML to `path`.
+fn save_offline_copy(webview: &webkit2gtk::WebView, path: std::path::PathBuf) {
+    use gio::prelude::InputStreamExtManual as _;
+    use std::io::Read as _;
+
+    let write = move |stream: gio::InputStream| -> WindowResult<()> {
+        let mut content = Vec::new();
+        stream
+            .into_read()
+            .take(MAX_OFFLINE_COPY_SIZE + 1)
+            .read_to_end(&mut content)?;
+        if content.len() as u64 > MAX_OFFLINE_COPY_SIZE {
+            return Err("page is too large".into());
+        }
+
+        let dir = path.parent().ok_or("invalid offline copy path")?;
+        std::fs::create_dir_all(dir)?;
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, content)?;
+        std::fs::rename(&partial, &path)?;
+        Ok(())
+    };
+
+    webview.save(
+        webkit2gtk::SaveMode::Mhtml,
+        None::<&gio::Cancellable>,
+        move |result| {
+            if let Err(e) = result.map_err(Into::into).and_then(write) {
+                eprintln!("Failed to save the page for offline use: {e}");
+            }
+        },
+    );
+}
+
+/// Error page with the name and icon of the app.
+fn load_error_page(app: &AppInfo, uri: &str, error: &gtk::glib::Error) -> String {
+    let host = Url::parse(uri)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| uri.to_string());
+
+    let (title, description) = if gio::NetworkMonitor::default().is_network_available() {
+        (
+            webapps::fl!("load-error-title", HashMap::from([("host", host.as_str())])),
+            error.message().to_string(),
+        )
+    } else {
+        (
+            webapps::fl!("offline-title"),
+            webapps::fl!(
+                "offline-description",
+                HashMap::from([("app", app.name.as_str())])
+            ),
+        )
+    };
+    let icon = app
+        .icon
+        .as_deref()
+        .map(|icon| format!(r#"<img src="{}" alt="">"#, html_escape(icon)))
+        .unwrap_or_default();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>{name}</title><style>
+body {{ font-family: sans-serif; max-width: 32em; margin: 6em auto; padding: 0 1em; color: #333; text-align: center; }}
+@media (prefers-color-scheme: dark) {{ body {{ background: #1b1b1b; color: #ddd; }} }}
+img {{ width: 96px; height: 96px; }}
+.app {{ font-weight: bold; margin: .5em 0 2em; }}
+.note {{ opacity: .7; font-size: .9em; }}
+button {{ margin-top: 1.5em; padding: .5em 1.5em; }}
+</style></head><body>
+{icon}
+<p class="app">{name}</p>
+<h1>{title}</h1>
+<p>{description}</p>
+<button onclick="location.reload()">{retry}</button>
+<p class="note">{note}</p>
+</body></html>"#,
+        name = html_escape(&app.name),
+        title = html_escape(&title),
+        description = html_escape(&description),
+        retry = html_escape(&webapps::fl!("offline-retry")),
+        note = html_escape(&webapps::fl!("offline-note")),
+    )
+}
+
+/// The icon file at `path` as a data URI.
+fn icon_data_uri(path: &str) -> Option<String> {
+    let extension = std::path::Path::new(path)
+        .extension()?
+        .to_string_lossy()
+        .to_ascii_lowercase();
+    let mime = match extension.as_str() {
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        _ => return None,
+    };
+    let data = std::fs::read(path).ok()?;
+
+    Some(format!(
+        "data:{mime};base64,{}",
+        gtk::glib::base64_encode(&data)
+    ))
+}
+
+/// Escape `text` for HTML content and attributes.
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Valid header rules of `browser` matching `url`.
+fn request_headers(browser: &Browser, url: &str) -> wry::http::HeaderMap {
+    let mut headers = wry::http::HeaderMap::new();
+    for rule in browser.request_headers.iter().flatten() {
+        if rule.validate().is_err() || !rule.matches(url) {
+            continue;
+        }
+        if let (Ok(name), Ok(value)) = (
+            wry::http::HeaderName::try_from(rule.name.as_str()),
+            wry::http::HeaderValue::try_from(rule.value.as_str()),
+        ) {
+            headers.insert(name, value);
+        }
+    }
+
+    headers
+}
+
+/// Decide on a navigation of `webview`, showing the app of `browser`. Unsafe
+/// URLs and pages outside a kiosk are refused. WebKit only sends header rules
+/// with requests the app makes itself, so pages the user opens through links
+/// and GET forms are loaded again with the headers. POST forms and redirects
+/// cannot be repeated and go out without them, as do frames, which load
+/// without a user gesture and cannot be told apart from the page otherwise.
+fn decide_navigation(
+    webview: &webkit2gtk::WebView,
+    decision: &PolicyDecision,
+    kind: PolicyDecisionType,
+    browser: &Browser,
+    leaves_kiosk: &dyn Fn(&str) -> bool,
+) -> bool {
+    use gtk::glib::Cast as _;
+
+    let Some(action) = decision
+        .downcast_ref::<NavigationPolicyDecision>()
+        .filter(|_| kind == PolicyDecisionType::NavigationAction)
+        .and_then(|decision| decision.navigation_action())
+    else {
+        return false;
+    };
+    let Some((request, uri)) = action
+        .request()
+        .and_then(|request| request.uri().map(|uri| (request, uri.to_string())))
+    else {
+        return false;
+    };
+
+    if !is_url_safe(&uri) {
+        eprintln!("Blocked navigation to unsafe URL: {uri}");
+        decision.ignore();
+        return true;
+    }
+    if leaves_kiosk(&uri) {
+        eprintln!("Blocked navigation out of the kiosk: {uri}");
+        decision.ignore();
+        return true;
+    }
+
+    let followed = action.is_user_gesture()
+        && matches!(
+            action.navigation_type(),
+            NavigationType::LinkClicked | NavigationType::FormSubmitted | NavigationType::Other
+        )
+        && request.http_method().is_none_or(|method| method == "GET");
+    let headers = request_headers(browser, &uri);
+    let sent = request.http_headers();
+    let missing = headers.iter().any(|(name, value)| {
+        let sent = sent.as_ref().and_then(|sent| sent.one(name.as_str()));
+        sent.as_deref() != value.to_str().ok()
+    });
+    if !(followed && missing) {
+        return false;
+    }
+
+    let reload = URIRequest::new(&uri);
+    if let Some(extra) = reload.http_headers() {
+        for (name, value) in &headers {
+            if let Ok(value) = value.to_str() {
+                extra.replace(name.as_str(), value);
+            }
+        }
+    }
+    decision.ignore();
+    webview.load_request(&reload);
+    true
+}
+
+/// Script adding the header rules of `browser` to `fetch` and
+/// `XMLHttpRequest`, which the webview cannot change for page scripts. Rules
+/// without a pattern only apply to the origin of the page, so requests to
+/// other sites are not turned into CORS preflights.
+fn header_script(browser: &Browser) -> Option<String> {
+    let rules: Vec<serde_json::Value> = browser
+        .request_headers
+        .iter()
+        .flatten()
+        .filter(|rule| rule.validate().is_ok())
+        .map(|rule| {
+            // an empty pattern stands for the origin of the page
+            let parts: Vec<String> = rule.pattern.split('*').map(regex_escape).collect();
+            let pattern = if rule.pattern.is_empty() {
+                None
+            } else {
+                Some(format!("^{}$", parts.join(".*")))
+            };
+            serde_json::json!({ "pattern": pattern, "name": rule.name, "value": rule.value })
+        })
+        .collect();
+    if rules.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        r#"(function () {{
+            var rules = {rules}.map(function (r) {{
+                return {{ re: r.pattern && new RegExp(r.pattern), name: r.name, value: r.value }};
+            }});
+            function matching(url) {{
+                var target = new URL(url, location.href);
+                return rules.filter(function (r) {{
+                    return r.re ? r.re.test(target.href) : target.origin === location.origin;
+                }});
+            }}
+            var fetch = window.fetch;
+            window.fetch = function (input, init) {{
+                var found = matching(input instanceof Request ? input.url : String(input));
+                if (!found.length) return fetch.apply(this, arguments);
+                var request = new Request(input, init);
+                found.forEach(function (r) {{ request.headers.set(r.name, r.value); }});
+                return fetch.call(this, request);
+            }};
+            var open = XMLHttpRequest.prototype.open;
+            var send = XMLHttpRequest.prototype.send;
+            XMLHttpRequest.prototype.open = function (method, url) {{
+                this.__webappsUrl = String(url);
+                return open.apply(this, arguments);
+            }};
+            XMLHttpRequest.prototype.send = function () {{
+                var xhr = this;
+                matching(xhr.__webappsUrl).forEach(function (r) {{
+                    xhr.setRequestHeader(r.name, r.value);
+                }});
+                return send.apply(this, arguments);
+            }};
+        }})()"#,
+        rules = serde_json::Value::Array(rules),
+    ))
+}
+
+/// Escape `text` for a JavaScript regular expression.
+fn regex_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if r"\^$.|?*+()[]{}/".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Hand `proxy` to the network settings of the profile.
+fn apply_proxy(manager: &WebsiteDataManager, proxy: &ProxyConfig) {
+    let (mode, mut settings) = match proxy {
+        // auto-config files are evaluated by libproxy, see `main`
+        ProxyConfig::System | ProxyConfig::Pac(_) => return,
+        ProxyConfig::Direct => (NetworkProxyMode::NoProxy, None),
+        ProxyConfig::Manual {
+            default,
+            http,
+            https,
+            ignore_hosts,
+        } => {
+            let ignore: Vec<&str> = ignore_hosts.iter().map(String::as_str).collect();
+            let mut settings = NetworkProxySettings::new(default.as_deref(), &ignore);
+            if let Some(uri) = http {
+                settings.add_proxy_for_scheme("http", uri);
+            }
+            if let Some(uri) = https {
+                settings.add_proxy_for_scheme("https", uri);
+            }
+            (NetworkProxyMode::Custom, Some(settings))
+        }
+    };
+
+    manager.set_network_proxy_settings(mode, settings.as_mut());
+}
+
+/// Cookie policy the cookie manager needs for `browser`. Exceptions cannot be
+/// expressed as a policy, with them third-party cookies are only blocked by the
+/// content rules of the webview.
+fn engine_cookie_policy(browser: &Browser) -> CookiePolicy {
+    match browser.cookie_policy() {
+        CookiePolicy::NoThirdParty if !cookie_exceptions(browser).is_empty() => {
+            CookiePolicy::Always
+        }
+        policy => policy,
+    }
+}
+
+/// Exception domains of `browser`, validated again as the config may have been
+/// edited by hand.
+fn cookie_exceptions(browser: &Browser) -> Vec<String> {
+    browser
+        .cookie_exceptions
+        .as_ref()
+        .map(|domains| webapps::browser::parse_domains(&domains.join(",")))
+        .unwrap_or_default()
+}
+
+/// WebKit content rules enforcing the cookie policy of `browser` in its own
+/// webview, `None` if it accepts all cookies.
+fn cookie_rules(browser: &Browser) -> Option<String> {
+    let rules: Vec<serde_json::Value> = match browser.cookie_policy() {
+        CookiePolicy::Always => return None,
+        CookiePolicy::NoThirdParty => std::iter::once(serde_json::json!({
+            "trigger": { "url-filter": ".*", "load-type": ["third-party"] },
+            "action": { "type": "block-cookies" },
+        }))
+        .chain(cookie_exceptions(browser).iter().map(|domain| {
+            serde_json::json!({
+                "trigger": {
+                    "url-filter": format!("^[a-z]+://([^/]*\\.)?{}[:/]", domain.replace('.', "\\.")),
+                    "load-type": ["third-party"],
+                },
+                "action": { "type": "ignore-previous-rules" },
+            })
+        }))
+        .collect(),
+        CookiePolicy::Never => vec![serde_json::json!({
+            "trigger": { "url-filter": ".*" },
+            "action": { "type": "block-cookies" },
+        })],
+    };
+
+    Some(serde_json::Value::Array(rules).to_string())
+}
+
+/// Compile the content `rules` and add them to `webview`. The webkit2gtk
+/// bindings do not cover content filters, so WebKit is called directly. This
+/// waits for the rules to compile, so they apply to the first page load.
+fn add_content_rules(webview: &webkit2gtk::WebView, rules: &str) -> WindowResult<()> {
+    use gtk::{
+        gio,
+        glib::{self, gobject_ffi, translate::*},
+    };
+    use sha2::{Digest, Sha256};
+    use webkit2gtk::ffi;
+
+    type Compiled = Option<Result<*mut ffi::WebKitUserContentFilter, glib::Error>>;
+
+    unsafe extern "C" fn saved(
+        store: *mut gobject_ffi::GObject,
+        result: *mut gio::ffi::GAsyncResult,
+        compiled: glib::ffi::gpointer,
+    ) {
+        let mut error = std::ptr::null_mut();
+        // SAFETY: `store` is the store passed to the save call and `compiled`
+        // the slot allocated by `add_content_rules`, which is still waiting
+        unsafe {
+            let filter =
+                ffi::webkit_user_content_filter_store_save_finish(store.cast(), result, &mut error);
+            *compiled.cast::<Compiled>() = Some(if error.is_null() {
+                Ok(filter)
+            } else {
+                Err(from_glib_full(error))
+            });
+        }
+    }
+
+    let manager = webview
+        .user_content_manager()
+        .ok_or("webview has no content manager")?;
+    let store_path = dirs::cache_dir()
+        .ok_or("failed to resolve cache directory")?
+        .join(webapps::APP_ID)
+        .join("content-rules");
+    // identical rules share one compiled filter
+    let identifier = format!("{:x}", Sha256::digest(rules.as_bytes()));
+    let source = glib::Bytes::from_owned(rules.as_bytes().to_vec());
+
+    let compiled: *mut Compiled = Box::into_raw(Box::new(None));
+    // SAFETY: the store and the slot are freed only after the callback ran
+    let compiled = unsafe {
+        let store = ffi::webkit_user_content_filter_store_new(
+            store_path.to_string_lossy().to_glib_none().0,
+        );
+        ffi::webkit_user_content_filter_store_save(
+            store,
+            identifier.to_glib_none().0,
+            source.to_glib_none().0,
+            std::ptr::null_mut(),
+            Some(saved),
+            compiled.cast(),
+        );
+        while (*compiled).is_none() {
+            glib::MainContext::default().iteration(true);
+        }
+        gobject_ffi::g_object_unref(store.cast());
 
+        Box::from_raw(compiled)
+    };
+
+    match *compiled {
+        Some(Ok(filter)) => {
+            // SAFETY: the filter was returned with a reference owned by us
+            unsafe {
+                ffi::webkit_user_content_manager_add_filter(manager.to_glib_none().0, filter);
+                ffi::webkit_user_content_filter_unref(filter);
+            }
+            Ok(())
+        }
+        Some(Err(e)) => Err(e.into()),
+        None => Err("content rules were not compiled".into()),
+    }
+}
+
+/// Remove everything WebKit stores for `domain`. WebKit groups website data by
+/// registrable domain, so a subdomain takes the data of its parent with it.
+fn clear_domain(
+    manager: WebsiteDataManager,
+    domain: String,
+    done: oneshot::Sender<Result<(), String>>,
+) {
+    let remover = manager.clone();
+    manager.fetch(
+        WebsiteDataTypes::ALL,
+        None::<&gtk::gio::Cancellable>,
+        move |result| {
+            let records = match result {
+                Ok(records) => records,
+                Err(e) => {
+                    let _ = done.send(Err(e.to_string()));
+                    return;
+                }
+            };
+
+            let matching: Vec<&WebsiteData> = records
+                .iter()
+                .filter(|data| {
+                    data.name().is_some_and(|name| {
+                        matches_domain(&name, &domain) || matches_domain(&domain, &name)
+                    })
+                })
+                .collect();
+            remover.remove(
+                WebsiteDataTypes::ALL,
+                &matching,
+                None::<&gtk::gio::Cancellable>,
+                move |result| {
+                    let _ = done.send(result.map_err(|e| e.to_string()));
+                },
+            );
+        },
+    );
+}
+
+/// WebKit data types of the `data` categories.
+fn website_data_types(data: &[SiteData]) -> WebsiteDataTypes {
+    data.iter()
+        .fold(WebsiteDataTypes::empty(), |types, data| match data {
+            SiteData::Cache => {
+                types
+                    | WebsiteDataTypes::MEMORY_CACHE
+                    | WebsiteDataTypes::DISK_CACHE
+                    | WebsiteDataTypes::OFFLINE_APPLICATION_CACHE
+            }
+            SiteData::Cookies => types | WebsiteDataTypes::COOKIES,
+            SiteData::Storage => {
+                types
+                    | WebsiteDataTypes::SESSION_STORAGE
+                    | WebsiteDataTypes::LOCAL_STORAGE
+                    | WebsiteDataTypes::INDEXEDDB_DATABASES
+                    | WebsiteDataTypes::WEBSQL_DATABASES
+            }
+            SiteData::ServiceWorkers => {
+                types | WebsiteDataTypes::SERVICE_WORKER_REGISTRATIONS | WebsiteDataTypes::DOM_CACHE
+            }
+        })
+}
+
+/// Open `browser` in a new window. Apps sharing a profile share its network
+/// settings, they are applied by the app that opened the profile, the `owner`.
+fn open_window(
+    target: &EventLoopWindowTarget<UserEvent>,
+    context: &mut WebContext,
+    mut browser: Browser,
+    owner: bool,
+) -> WindowResult<AppWindow> {
     // Validate URL scheme before loading
-    let url = browser.url.unwrap_or_default();
+    let url = browser.url.take().unwrap_or_default();
     if !url.is_empty() && !is_url_safe(&url) {
-        eprintln!("Refusing to load unsafe URL scheme: {url}");
-        std::process::exit(1);
+        return Err(format!("refusing to load unsafe URL scheme: {url}").into());
     }
 
-    let event_loop = EventLoopBuilder::new().with_any_thread(true).build();
-
     // Clone title before window builder consumes it (needed for notification forwarding)
     let app_title_for_notifications = browser
         .window_title
@@ -65,49 +1657,36 @@ fn main() -> wry::Result<()> {
     let mut window_builder = WindowBuilder::new();
     window_builder.window = attrs;
 
-    let window = match window_builder
-        .with_title(browser.window_title.unwrap_or(webapps::fl!("app")))
-        .with_decorations(browser.window_decorations.unwrap_or(true))
-        .build(&event_loop)
-    {
-        Ok(w) => w,
-        Err(e) => {
-            eprintln!("Failed to create window: {e}");
-            std::process::exit(1);
-        }
-    };
+    // Issue #46: GTK uses the program name as WM_CLASS res_name on X11 and as
+    // the app ID on Wayland. It is read when the window is created, so windows
+    // of apps sharing this process each get their own StartupWMClass.
+    gtk::glib::set_program_name(Some(browser.app_id.id.clone()));
 
-    // Issue #46: WM_CLASS is set via gtk::glib::set_program_name() above (line 29),
-    // which GTK uses as the WM_CLASS res_name on X11. This matches StartupWMClass
-    // in the generated .desktop entry.
+    let kiosk = browser.kiosk.clone();
+    let window = window_builder
+        .with_title(browser.window_title.clone().unwrap_or(webapps::fl!("app")))
+        .with_decorations(kiosk.is_none() && browser.window_decorations.unwrap_or(true))
+        .with_fullscreen(kiosk.as_ref().map(|_| Fullscreen::Borderless(None)))
+        .build(target)?;
 
-    // #54: Set proxy environment variables if configured
-    if let Some(ref proxy) = browser.proxy_url {
-        if !proxy.trim().is_empty() {
-            // SAFETY: Called before any threads are spawned
-            unsafe {
-                std::env::set_var("http_proxy", proxy);
-                std::env::set_var("https_proxy", proxy);
-            }
-        }
-    }
-
-    let mut context = WebContext::new(browser.profile);
+    let kiosk_scope = kiosk.clone().map(|kiosk| (kiosk, url.clone()));
+    let leaves_kiosk = move |nav_url: &str| {
+        kiosk_scope
+            .as_ref()
+            .is_some_and(|(kiosk, home)| !kiosk.allows(home, nav_url))
+    };
+    let kiosk_window = kiosk.is_some();
 
-    let mut builder = WebViewBuilder::new_with_web_context(&mut context)
-        .with_url(&url)
+    // loaded once the webview is set up, so no request bypasses the proxy
+    let mut start_url = url.clone();
+    let mut builder = WebViewBuilder::new_with_web_context(context)
         .with_incognito(browser.private_mode.unwrap_or(false))
         .with_devtools(false)
-        .with_navigation_handler(|nav_url| {
-            if is_url_safe(&nav_url) {
-                true
-            } else {
-                eprintln!("Blocked navigation to unsafe URL: {nav_url}");
-                false
-            }
-        })
-        .with_new_window_req_handler(|new_url, _features| {
-            if is_url_safe(&new_url) {
+        .with_new_window_req_handler(move |new_url, _features| {
+            if kiosk_window {
+                eprintln!("Blocked new window of the kiosk: {new_url}");
+                wry::NewWindowResponse::Deny
+            } else if is_url_safe(&new_url) {
                 wry::NewWindowResponse::Allow
             } else {
                 eprintln!("Blocked new window with unsafe URL: {new_url}");
@@ -233,43 +1812,6 @@ fn main() -> wry::Result<()> {
         );
     }
 
-    // #60: Block third-party cookies
-    if let Some(true) = browser.block_third_party_cookies {
-        builder = builder.with_initialization_script(
-            r#"(function(){
-                try {
-                    Object.defineProperty(document, 'cookie', {
-                        get: function() {
-                            return document._firstPartyCookies || '';
-                        },
-                        set: function(val) {
-                            // Only allow first-party cookie setting
-                            if (!val.includes('domain=') || val.includes(window.location.hostname)) {
-                                document._firstPartyCookies = val;
-                            }
-                        }
-                    });
-                } catch(e) {}
-            })()"#,
-        );
-    }
-
-    // #61: Block WebRTC IP leak
-    if let Some(true) = browser.block_webrtc {
-        builder = builder.with_initialization_script(
-            r#"(function(){
-                window.RTCPeerConnection = undefined;
-                window.webkitRTCPeerConnection = undefined;
-                window.mozRTCPeerConnection = undefined;
-                if (navigator.mediaDevices) {
-                    navigator.mediaDevices.enumerateDevices = function() {
-                        return Promise.resolve([]);
-                    };
-                }
-            })()"#,
-        );
-    }
-
     // Issue #39: Forward web notifications to COSMIC desktop notifications
     if perms.allow_notifications {
         builder = builder.with_initialization_script(
@@ -365,6 +1907,8 @@ fn main() -> wry::Result<()> {
     let app_title = app_title_for_notifications.clone();
     let restore_session_enabled = browser.restore_session.unwrap_or(false);
     let ipc_app_id = browser.app_id.as_ref().to_string();
+    let pending_trust = PendingTrust::default();
+    let ipc_pending_trust = pending_trust.clone();
     builder = builder.with_ipc_handler(move |req| {
         let msg = req.body();
         if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(msg) {
@@ -391,6 +1935,11 @@ fn main() -> wry::Result<()> {
                         tracing::debug!("Badge count: {count}");
                     }
                 }
+                Some("trust_certificate") => {
+                    if let Some(token) = parsed.get("token").and_then(|t| t.as_str()) {
+                        trust_certificate(&ipc_pending_trust, &ipc_app_id, token);
+                    }
+                }
                 Some("save_url") if restore_session_enabled => {
                     if let Some(new_url) = parsed.get("url").and_then(|u| u.as_str()) {
                         if !new_url.is_empty() {
@@ -442,11 +1991,12 @@ fn main() -> wry::Result<()> {
         }
     }
 
-    // #56: Session restore — navigate to last URL if enabled
-    if let Some(true) = browser.restore_session {
+    // #56: Session restore — navigate to last URL if enabled, kiosks always
+    // start at their start page
+    if let (Some(true), None) = (browser.restore_session, &kiosk) {
         if let Some(ref last) = browser.last_url {
             if !last.is_empty() && is_url_safe(last) && last != &url {
-                builder = builder.with_url(last);
+                start_url = last.clone();
             }
         }
     }
@@ -491,35 +2041,422 @@ fn main() -> wry::Result<()> {
         );
     }
 
-    let _webview = {
+    if let Some(script) = header_script(&browser) {
+        builder = builder.with_initialization_script_for_main_only(script, true);
+    }
+
+    let webview = {
         use tao::platform::unix::WindowExtUnix;
         use wry::WebViewBuilderExtUnix;
-        let vbox = match window.default_vbox() {
-            Some(vbox) => vbox,
-            None => {
-                eprintln!("Failed to get GTK vbox from window");
-                std::process::exit(1);
-            }
-        };
+        let vbox = window
+            .default_vbox()
+            .ok_or("failed to get GTK vbox from window")?;
         builder.build_gtk(vbox)?
     };
 
-    // #59: Minimize to background on close
-    let minimize_on_close = browser.minimize_to_background.unwrap_or(false);
+    if let Some(settings) = webview.webview().settings() {
+        settings.set_enable_webrtc(browser.webrtc_policy() != WebRtcPolicy::Disabled);
+        settings.set_enable_media_stream(perms.allow_camera || perms.allow_microphone);
+    }
 
-    event_loop.run(move |event, _, control_flow| {
-        *control_flow = ControlFlow::Wait;
+    let kiosk = kiosk.map(|config| lock_kiosk(&window, &webview, &config, &url));
 
-        if let Event::WindowEvent {
-            event: WindowEvent::CloseRequested,
-            ..
-        } = event
+    let header_browser = browser.clone();
+    webview
+        .webview()
+        .connect_decide_policy(move |webview, decision, kind| {
+            decide_navigation(webview, decision, kind, &header_browser, &leaves_kiosk)
+        });
+
+    let (allow_camera, allow_microphone) = (perms.allow_camera, perms.allow_microphone);
+    webview
+        .webview()
+        .connect_permission_request(move |_, request| {
+            use gtk::glib::Cast as _;
+            let Some(media) = request.downcast_ref::<UserMediaPermissionRequest>() else {
+                return false;
+            };
+
+            if (allow_camera || !media.is_for_video_device())
+                && (allow_microphone || !media.is_for_audio_device())
+            {
+                request.allow();
+            } else {
+                request.deny();
+            }
+            true
+        });
+
+    if let Some(rules) = cookie_rules(&browser) {
+        if let Err(e) = add_content_rules(&webview.webview(), &rules) {
+            eprintln!("Failed to apply the cookie policy: {e}");
+        }
+    }
+
+    if let Some(context) = webview.webview().context() {
+        for pin in browser.trusted_certificates.iter().flatten() {
+            match gio::TlsCertificate::from_pem(&pin.pem) {
+                Ok(certificate) => context.allow_tls_certificate_for_host(&certificate, &pin.host),
+                Err(e) => eprintln!(
+                    "Failed to read the trusted certificate of {}: {e}",
+                    pin.host
+                ),
+            }
+        }
+    }
+    let load_state = SharedLoadState::default();
+    let tls_state = load_state.clone();
+    let ca_bundle = browser.ca_bundle.clone();
+    webview.webview().connect_load_failed_with_tls_errors(
+        move |webview, uri, certificate, errors| {
+            handle_tls_error(
+                webview,
+                uri,
+                certificate,
+                errors,
+                ca_bundle.as_deref(),
+                &pending_trust,
+                &tls_state,
+            )
+        },
+    );
+
+    let parent = {
+        use tao::platform::unix::WindowExtUnix;
+        window.gtk_window().clone()
+    };
+    if kiosk.is_none() {
+        connect_print_shortcuts(
+            &parent,
+            &webview.webview(),
+            app_title_for_notifications.clone(),
+        );
+    }
+    let print_parent = parent.clone();
+    webview.webview().connect_print(move |_, operation| {
+        // kiosks do not print
+        if !kiosk_window {
+            run_print_dialog(operation, &print_parent);
+        }
+        true
+    });
+
+    let auth_app_id = browser.app_id.id.clone();
+    let client_certificate = browser.client_certificate.clone();
+    webview.webview().connect_authenticate(move |_, request| {
+        handle_authentication(
+            &parent,
+            request,
+            &auth_app_id,
+            client_certificate.as_deref(),
+        )
+    });
+
+    let app_info = match webapps::launcher::WebAppLauncher::from_appid(&browser.app_id.id) {
+        Some(launcher) => AppInfo {
+            icon: icon_data_uri(
+                &webapps::icon_pipeline::icon_file(&launcher.icon, OFFLINE_ICON_SIZE)
+                    .to_string_lossy(),
+            ),
+            name: launcher.name,
+        },
+        None => AppInfo {
+            name: browser.window_title.clone().unwrap_or_default(),
+            icon: None,
+        },
+    };
+    // private windows leave nothing behind
+    let offline_copy = browser
+        .offline_copy_path()
+        .filter(|_| browser.offline_copy.unwrap_or(false))
+        .filter(|_| !browser.private_mode.unwrap_or(false));
+    let failed_state = load_state.clone();
+    let failed_copy = offline_copy.clone();
+    webview
+        .webview()
+        .connect_load_failed(move |webview, _, uri, error| {
+            handle_load_failed(
+                webview,
+                uri,
+                error,
+                &app_info,
+                failed_copy.as_deref(),
+                &failed_state,
+            )
+        });
+    let changed_state = load_state.clone();
+    webview
+        .webview()
+        .connect_load_changed(move |webview, event| {
+            handle_load_changed(webview, event, offline_copy.as_deref(), &changed_state)
+        });
+    let weak_webview = webview.webview().downgrade();
+    let network_handler =
+        gio::NetworkMonitor::default().connect_network_changed(move |_, available| {
+            let failed_uri = load_state.borrow().failed_uri.clone();
+            if let (true, Some(webview), Some(uri)) =
+                (available, weak_webview.upgrade(), failed_uri)
+            {
+                webview.load_uri(&uri);
+            }
+        });
+
+    if owner {
+        if let Some(manager) = webview.webview().website_data_manager() {
+            apply_proxy(&manager, &browser.proxy());
+        }
+        if let (Some(languages), Some(context)) =
+            (&browser.accept_language, webview.webview().context())
         {
-            if minimize_on_close {
-                window.set_visible(false);
+            let languages: Vec<&str> = languages.iter().map(String::as_str).collect();
+            context.set_preferred_languages(&languages);
+        }
+    }
+
+    let headers = request_headers(&browser, &start_url);
+    if !start_url.is_empty() {
+        if headers.is_empty() {
+            webview.load_url(&start_url)?;
+        } else {
+            webview.load_url_with_headers(&start_url, headers)?;
+        }
+    }
+
+    Ok(AppWindow {
+        app_id: browser.app_id.id.clone(),
+        window,
+        webview,
+        cookie_policy: engine_cookie_policy(&browser),
+        minimize_on_close: browser.minimize_to_background.unwrap_or(false),
+        network_handler: Some(network_handler),
+        reload: ReloadSchedule::new(&browser),
+        kiosk,
+    })
+}
+
+/// Longest page title used as the name of a saved PDF, in characters.
+const MAX_PDF_NAME: usize = 100;
+
+/// Run the print dialog of GTK, or of the portal inside a sandbox, for
+/// `operation`.
+fn run_print_dialog(operation: &PrintOperation, parent: &gtk::ApplicationWindow) {
+    operation.connect_failed(|_, e| eprintln!("Failed to print the page: {e}"));
+    operation.run_dialog(Some(parent));
+}
+
+/// Ctrl+P prints the page and Ctrl+Shift+S saves it as PDF, telling the user
+/// where it went.
+fn connect_print_shortcuts(
+    window: &gtk::ApplicationWindow,
+    webview: &webkit2gtk::WebView,
+    app_title: String,
+) {
+    use gtk::{gdk::ModifierType, glib::Propagation, prelude::*};
+
+    let webview = webview.downgrade();
+    window.connect_key_press_event(move |window, event| {
+        let modifiers = ModifierType::CONTROL_MASK | ModifierType::SHIFT_MASK;
+        let held = event.state() & modifiers;
+        let key = event.keyval().to_lower().name();
+        let Some(webview) = webview.upgrade() else {
+            return Propagation::Proceed;
+        };
+
+        match key.as_deref() {
+            Some("p") if held == ModifierType::CONTROL_MASK => {
+                run_print_dialog(&PrintOperation::new(&webview), window);
+                Propagation::Stop
+            }
+            Some("s") if held == modifiers => {
+                let app_title = app_title.clone();
+                save_pdf(&webview, move |result| {
+                    let body = match result {
+                        Ok(path) => {
+                            let file = path.to_string_lossy();
+                            webapps::fl!("pdf-saved", HashMap::from([("file", file.as_ref())]))
+                        }
+                        Err(e) => {
+                            webapps::fl!("pdf-failed", HashMap::from([("error", e.as_str())]))
+                        }
+                    };
+                    let _ = notify_rust::Notification::new()
+                        .summary(&app_title)
+                        .body(&body)
+                        .appname("dev.heppen.webapps")
+                        .show();
+                });
+                Propagation::Stop
+            }
+            _ => Propagation::Proceed,
+        }
+    });
+}
+
+/// Print the page of `webview` to a PDF in the download directory and call
+/// `done` with its path.
+fn save_pdf(
+    webview: &webkit2gtk::WebView,
+    done: impl FnOnce(Result<std::path::PathBuf, String>) + 'static,
+) {
+    let title = webview.title().unwrap_or_default();
+    let Some(path) = pdf_path(&title) else {
+        done(Err("no download directory".to_string()));
+        return;
+    };
+    let uri = match gtk::glib::filename_to_uri(&path, None) {
+        Ok(uri) => uri,
+        Err(e) => {
+            done(Err(e.to_string()));
+            return;
+        }
+    };
+
+    let settings = gtk::PrintSettings::new();
+    // the file printer of GTK goes by a translated name
+    settings.set_printer(&gtk::glib::dgettext(Some("gtk30"), "Print to File"));
+    settings.set(gtk::PRINT_SETTINGS_OUTPUT_FILE_FORMAT.as_str(), Some("pdf"));
+    settings.set(gtk::PRINT_SETTINGS_OUTPUT_URI.as_str(), Some(uri.as_str()));
+
+    let operation = PrintOperation::new(webview);
+    operation.set_print_settings(&settings);
+    // finished follows failed, the operation is kept until either arrives
+    let pending = Rc::new(RefCell::new(Some((done, operation.clone()))));
+    let failed = pending.clone();
+    operation.connect_failed(move |_, e| {
+        if let Some((done, _)) = failed.borrow_mut().take() {
+            done(Err(e.to_string()));
+        }
+    });
+    operation.connect_finished(move |_| {
+        if let Some((done, _)) = pending.borrow_mut().take() {
+            done(Ok(path.clone()));
+        }
+    });
+    operation.print();
+}
+
+/// A file in the download directory named after the page title, numbered if
+/// the name is taken.
+fn pdf_path(title: &str) -> Option<std::path::PathBuf> {
+    let dir = dirs::download_dir()?;
+    let name: String = title
+        .chars()
+        .map(|c| if c == '/' || c.is_control() { '_' } else { c })
+        .take(MAX_PDF_NAME)
+        .collect();
+    let name = match name.trim().trim_start_matches('.') {
+        "" => "page",
+        name => name,
+    };
+
+    (1..)
+        .map(|n| match n {
+            1 => dir.join(format!("{name}.pdf")),
+            n => dir.join(format!("{name} ({n}).pdf")),
+        })
+        .find(|path| !path.exists())
+}
+
+/// Seconds before a crashed kiosk loads its start page again, so a page that
+/// crashes right away does not restart in a tight loop.
+const KIOSK_RESTART_DELAY: u64 = 3;
+
+/// Lock a window down as a kiosk starting at `home`: no context menu, no
+/// shortcuts besides the exit shortcut, and a restart after crashes.
+fn lock_kiosk(window: &Window, webview: &WebView, config: &KioskConfig, home: &str) -> Kiosk {
+    use gtk::{gdk::ModifierType, glib::Propagation, prelude::*};
+    use tao::platform::unix::WindowExtUnix;
+
+    let exit_shortcut = Shortcut::parse(&config.exit_shortcut).unwrap_or_else(|e| {
+        eprintln!(
+            "Invalid kiosk exit shortcut '{}': {e}",
+            config.exit_shortcut
+        );
+        Shortcut::parse(DEFAULT_EXIT_SHORTCUT).unwrap_or_default()
+    });
+    let exit = Rc::new(Cell::new(false));
+    let exit_requested = exit.clone();
+    // runs after the handler of tao, so key events still reach the event loop
+    window
+        .gtk_window()
+        .connect_key_press_event(move |gtk_window, event| {
+            let state = event.state();
+            let pressed = Shortcut {
+                ctrl: state.contains(ModifierType::CONTROL_MASK),
+                alt: state.contains(ModifierType::MOD1_MASK),
+                shift: state.contains(ModifierType::SHIFT_MASK),
+                super_key: state.intersects(ModifierType::SUPER_MASK | ModifierType::META_MASK),
+                key: event
+                    .keyval()
+                    .to_lower()
+                    .name()
+                    .map(|name| name.to_lowercase())
+                    .unwrap_or_default(),
+            };
+
+            if pressed == exit_shortcut {
+                exit_requested.set(true);
+                gtk_window.close();
+                return Propagation::Stop;
+            }
+            // typing reaches the page, anything that may be a shortcut does not
+            let function_key = pressed.key.len() > 1
+                && pressed.key.starts_with('f')
+                && pressed.key[1..].bytes().all(|b| b.is_ascii_digit());
+            if pressed.ctrl || pressed.alt || pressed.super_key || function_key {
+                Propagation::Stop
             } else {
-                *control_flow = ControlFlow::Exit;
+                Propagation::Proceed
             }
+        });
+
+    webview.webview().connect_context_menu(|_, _, _, _| true);
+
+    let restart_home = home.to_string();
+    webview
+        .webview()
+        .connect_web_process_terminated(move |webview, reason| {
+            if reason == WebProcessTerminationReason::TerminatedByApi {
+                return;
+            }
+            eprintln!("Kiosk page ended ({reason:?}), loading the start page again");
+            let webview = webview.downgrade();
+            let home = restart_home.clone();
+            gtk::glib::timeout_add_local_once(
+                Duration::from_secs(KIOSK_RESTART_DELAY),
+                move || {
+                    if let Some(webview) = webview.upgrade() {
+                        webview.load_uri(&home);
+                    }
+                },
+            );
+        });
+
+    let last_input = Rc::new(Cell::new(None));
+    let input = last_input.clone();
+    webview.webview().connect_event(move |_, event| {
+        use gtk::gdk::EventType;
+        if matches!(
+            event.event_type(),
+            EventType::KeyPress
+                | EventType::ButtonPress
+                | EventType::MotionNotify
+                | EventType::Scroll
+                | EventType::TouchBegin
+                | EventType::TouchUpdate
+        ) {
+            input.set(Some(Instant::now()));
         }
+        Propagation::Proceed
     });
+
+    Kiosk {
+        home: home.to_string(),
+        idle_reset: config
+            .idle_reset
+            .filter(|minutes| *minutes > 0)
+            .map(|minutes| Duration::from_secs(u64::from(minutes) * 60)),
+        last_input,
+        exit,
+    }
 }
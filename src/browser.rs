@@ -1,5 +1,7 @@
+use rand::{Rng, rng};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+
+use crate::{kiosk::KioskConfig, proxy::ProxyConfig, tls::CertificatePin};
 
 /// Maximum length for a sanitized app ID.
 const MAX_APP_ID_LEN: usize = 128;
@@ -22,6 +24,226 @@ pub struct PermissionPolicy {
     pub allow_notifications: bool,
 }
 
+/// Cookies a web app accepts, enforced by the engine.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CookiePolicy {
+    /// Accept all cookies.
+    Always,
+    /// Accept cookies only from the site shown in the window.
+    #[default]
+    NoThirdParty,
+    /// Accept no cookies at all, sites cannot keep you signed in.
+    Never,
+}
+
+impl CookiePolicy {
+    /// All policies, from the most permissive to the strictest.
+    pub const ALL: [CookiePolicy; 3] = [
+        CookiePolicy::Always,
+        CookiePolicy::NoThirdParty,
+        CookiePolicy::Never,
+    ];
+
+    /// Stable name used on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CookiePolicy::Always => "always",
+            CookiePolicy::NoThirdParty => "no-third-party",
+            CookiePolicy::Never => "never",
+        }
+    }
+
+    /// The policy called `name` by [`CookiePolicy::as_str`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|policy| policy.as_str() == name)
+    }
+}
+
+/// How much of WebRTC a web app may use. WebKit always hides local addresses
+/// behind mDNS names and offers no mode between these two.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub enum WebRtcPolicy {
+    /// No WebRTC at all, calls and screen sharing do not work.
+    Disabled,
+    /// WebRTC as the engine offers it, which hides local addresses behind
+    /// mDNS names.
+    #[default]
+    Enabled,
+}
+
+impl WebRtcPolicy {
+    pub const ALL: [WebRtcPolicy; 2] = [WebRtcPolicy::Disabled, WebRtcPolicy::Enabled];
+
+    /// Stable name used on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebRtcPolicy::Disabled => "disabled",
+            WebRtcPolicy::Enabled => "enabled",
+        }
+    }
+
+    /// The policy called `name` by [`WebRtcPolicy::as_str`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|policy| policy.as_str() == name)
+    }
+}
+
+/// Headers pages cannot set, or that other settings of the app control.
+const RESERVED_HEADERS: [&str; 12] = [
+    "accept-language",
+    "connection",
+    "content-length",
+    "cookie",
+    "host",
+    "origin",
+    "referer",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "user-agent",
+];
+
+/// An extra request header for URLs matching a pattern.
+///
+/// The header is sent with the pages the app loads and the `fetch` and
+/// `XMLHttpRequest` calls of the main frame. Form POSTs, redirects and frames
+/// go out without it.
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+pub struct HeaderRule {
+    /// URL pattern where `*` matches anything, e.g.
+    /// `https://tools.example.com/*`. Empty matches every page, but only
+    /// requests of page scripts to the origin of the page.
+    pub pattern: String,
+    pub name: String,
+    pub value: String,
+}
+
+impl HeaderRule {
+    /// Parse `[PATTERN ]Name: Value`.
+    pub fn parse(rule: &str) -> Option<Self> {
+        let rule = rule.trim();
+        // the colon after the header name, not the one of the pattern's scheme
+        let (head, value) = rule
+            .match_indices(':')
+            .map(|(idx, _)| (&rule[..idx], &rule[idx + 1..]))
+            .find(|(head, value)| {
+                !value.starts_with("//")
+                    && head
+                        .rsplit(char::is_whitespace)
+                        .next()
+                        .is_some_and(|name| !name.is_empty() && !name.contains('/'))
+            })?;
+        let (pattern, name) = head.rsplit_once(char::is_whitespace).unwrap_or(("", head));
+
+        Some(Self {
+            pattern: pattern.trim().to_string(),
+            name: name.to_string(),
+            value: value.trim().to_string(),
+        })
+    }
+
+    /// Check the header name and value.
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let is_token = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c);
+        if self.name.is_empty() || !self.name.chars().all(is_token) {
+            return Err(format!("'{}' is not a header name", self.name).into());
+        }
+        let name = self.name.to_ascii_lowercase();
+        if RESERVED_HEADERS.contains(&name.as_str())
+            || name.starts_with("proxy-")
+            || name.starts_with("sec-")
+        {
+            return Err(format!("the {} header cannot be set", self.name).into());
+        }
+        if self.value.contains(['\r', '\n', '\0']) {
+            return Err(format!("the value of {} spans multiple lines", self.name).into());
+        }
+
+        Ok(())
+    }
+
+    /// Whether the rule applies to `url`.
+    pub fn matches(&self, url: &str) -> bool {
+        let mut parts = self.pattern.split('*');
+        let first = parts.next().unwrap_or_default();
+        let Some(mut rest) = url.strip_prefix(first) else {
+            return false;
+        };
+
+        let parts: Vec<&str> = parts.collect();
+        let Some((last, middle)) = parts.split_last() else {
+            return rest.is_empty() || self.pattern.is_empty();
+        };
+        for part in middle {
+            match rest.find(part) {
+                Some(idx) => rest = &rest[idx + part.len()..],
+                None => return false,
+            }
+        }
+
+        rest.ends_with(last)
+    }
+}
+
+/// Settings of the network session of a webview process. Apps sharing a
+/// persistent profile run in one process, see [`crate::instance`], and all
+/// use the settings of the app that opened it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetworkSettings {
+    pub proxy: ProxyConfig,
+    /// Languages offered to sites, see [`Browser::accept_language`].
+    pub accept_language: Option<Vec<String>>,
+}
+
+/// Parse a comma separated list of language tags like `de-CH, de;q=0.8, en`,
+/// dropping the weights.
+pub fn parse_languages(value: &str) -> Vec<String> {
+    let mut languages: Vec<String> = Vec::new();
+
+    for item in value.split(',') {
+        let tag = item
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .replace('_', "-");
+        if !tag.is_empty()
+            && tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            && !languages.iter().any(|l| l.eq_ignore_ascii_case(&tag))
+        {
+            languages.push(tag);
+        }
+    }
+
+    languages
+}
+
+/// Parse a comma separated list of domains, accepting URLs and `*.` wildcards.
+pub fn parse_domains(value: &str) -> Vec<String> {
+    let mut domains: Vec<String> = Vec::new();
+
+    for item in value.split(',') {
+        let item = item.trim().to_lowercase();
+        let host = item
+            .split_once("://")
+            .map_or(item.as_str(), |(_, rest)| rest);
+        let host = host.split(['/', ':', '?', '#']).next().unwrap_or_default();
+        let host = host.trim_start_matches("*.").trim_matches('.');
+
+        if !host.is_empty()
+            && host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+            && !domains.iter().any(|d| d == host)
+        {
+            domains.push(host.to_string());
+        }
+    }
+
+    domains
+}
+
 /// Sanitize an app ID for safe use in filesystem paths and desktop entry filenames.
 /// Removes path separators, traversal sequences, and enforces length limits.
 /// Returns an empty string if the input is empty after sanitization.
@@ -42,12 +264,25 @@ pub fn sanitize_app_id(id: &str) -> String {
     }
 }
 
+/// Generate a new app ID from a web app title, e.g. `My App` -> `MyApp4821`.
+pub fn generate_app_id(title: &str) -> String {
+    sanitize_app_id(&format!(
+        "{}{}",
+        title.replace(' ', ""),
+        rng().random_range(1000..10000)
+    ))
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Browser {
+    #[serde(default)]
     pub app_id: crate::WebviewArgs,
     pub window_title: Option<String>,
     pub url: Option<String>,
-    pub profile: Option<PathBuf>,
+    /// ID of the [`crate::profile`] holding the browser data, `None` for a
+    /// temporary profile.
+    #[serde(default, deserialize_with = "crate::profile::deserialize_id")]
+    pub profile: Option<String>,
     pub window_size: Option<crate::WindowSize>,
     pub window_decorations: Option<bool>,
     pub private_mode: Option<bool>,
@@ -59,17 +294,52 @@ pub struct Browser {
     pub url_schemes: Option<Vec<String>>,
     // #53: Content blocking (ads/trackers)
     pub content_blocking: Option<bool>,
-    // #60: Block third-party cookies
+    /// Cookies accepted by the engine, see [`Browser::cookie_policy`].
+    pub cookie_policy: Option<CookiePolicy>,
+    /// Set by older versions, `Some(true)` means [`CookiePolicy::NoThirdParty`].
+    #[serde(default, skip_serializing)]
     pub block_third_party_cookies: Option<bool>,
-    // #61: Block WebRTC IP leak
+    /// Domains allowed to use cookies as a third party, e.g. single sign-on
+    /// providers. Only used with [`CookiePolicy::NoThirdParty`].
+    pub cookie_exceptions: Option<Vec<String>>,
+    /// WebRTC allowed to the app, see [`Browser::webrtc_policy`].
+    pub webrtc_policy: Option<WebRtcPolicy>,
+    /// Set by older versions, `Some(true)` means [`WebRtcPolicy::Disabled`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub block_webrtc: Option<bool>,
-    // #54: HTTP/HTTPS proxy
+    /// Proxy of the app, see [`Browser::proxy`].
+    pub proxy: Option<ProxyConfig>,
+    /// Set by older versions, a proxy for all schemes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub proxy_url: Option<String>,
+    /// Extra headers sent by the app, see [`HeaderRule`].
+    pub request_headers: Option<Vec<HeaderRule>>,
+    /// Languages sent as `Accept-Language` and offered to pages, most
+    /// preferred first. `None` follows the desktop language.
+    pub accept_language: Option<Vec<String>>,
+    /// PEM file of extra CA certificates trusted by the app.
+    pub ca_bundle: Option<String>,
+    /// Certificates trusted on the error page of the webview.
+    pub trusted_certificates: Option<Vec<CertificatePin>>,
+    /// PKCS#12 file with the certificate and key sent to sites asking for a
+    /// client certificate. Its password is kept in the keyring.
+    pub client_certificate: Option<String>,
     // #55: Page zoom level
     pub zoom_level: Option<f64>,
     // #56: Session restore
     pub restore_session: Option<bool>,
     pub last_url: Option<String>,
+    /// Show a copy of the last loaded page when a page cannot be loaded.
+    pub offline_copy: Option<bool>,
+    /// Minutes between reloads of the page.
+    pub reload_interval: Option<u32>,
+    /// Reload the page when the computer wakes from suspend.
+    pub reload_on_resume: Option<bool>,
+    /// Reload the page when the window gets the focus back after at least
+    /// this many minutes in the background.
+    pub reload_on_focus: Option<u32>,
+    /// Show the app fullscreen as a kiosk.
+    pub kiosk: Option<KioskConfig>,
     // #57: Usage statistics
     pub launch_count: Option<u64>,
     pub last_launched: Option<u64>,
@@ -80,16 +350,18 @@ pub struct Browser {
 }
 
 impl Browser {
+    /// A browser for `app_id`, using its own profile if `with_profile` is set.
     pub fn new(app_id: &str, with_profile: bool) -> Self {
         let safe_id = sanitize_app_id(app_id);
-        let mut browser = Self {
+        Self {
             app_id: crate::WebviewArgs {
                 id: safe_id.clone(),
                 private: false,
+                kiosk: false,
             },
             window_title: None,
             url: None,
-            profile: None,
+            profile: with_profile.then(|| safe_id.clone()),
             window_size: None,
             window_decorations: None,
             private_mode: None,
@@ -100,60 +372,175 @@ impl Browser {
             permissions: None,
             url_schemes: None,
             content_blocking: None,
+            cookie_policy: Some(CookiePolicy::default()),
             block_third_party_cookies: None,
+            cookie_exceptions: None,
+            webrtc_policy: None,
             block_webrtc: None,
+            proxy: None,
             proxy_url: None,
+            request_headers: None,
+            accept_language: None,
+            ca_bundle: None,
+            trusted_certificates: None,
+            client_certificate: None,
             zoom_level: None,
             restore_session: None,
             last_url: None,
+            offline_copy: None,
+            reload_interval: None,
+            reload_on_resume: None,
+            reload_on_focus: None,
+            kiosk: None,
             launch_count: None,
             last_launched: None,
             minimize_to_background: None,
             auto_dark_mode: None,
-        };
+        }
+    }
 
-        if with_profile {
-            if let Some(xdg_data) = dirs::data_dir() {
-                let path = xdg_data.join(crate::APP_ID).join("profiles").join(&safe_id);
-                browser.profile = Some(path);
-            }
-        };
+    /// The cookie policy, honouring the setting of older versions. Apps saved
+    /// before the policy existed keep accepting all cookies.
+    pub fn cookie_policy(&self) -> CookiePolicy {
+        match (self.cookie_policy, self.block_third_party_cookies) {
+            (Some(policy), _) => policy,
+            (None, Some(true)) => CookiePolicy::NoThirdParty,
+            (None, _) => CookiePolicy::Always,
+        }
+    }
+
+    /// Replace the cookie policy, dropping the setting of older versions.
+    pub fn set_cookie_policy(&mut self, policy: CookiePolicy) {
+        self.cookie_policy = Some(policy);
+        self.block_third_party_cookies = None;
+    }
+
+    /// The WebRTC policy, honouring the setting of older versions.
+    pub fn webrtc_policy(&self) -> WebRtcPolicy {
+        match (self.webrtc_policy, self.block_webrtc) {
+            (Some(policy), _) => policy,
+            (None, Some(true)) => WebRtcPolicy::Disabled,
+            (None, _) => WebRtcPolicy::default(),
+        }
+    }
+
+    /// Replace the WebRTC policy, dropping the setting of older versions.
+    pub fn set_webrtc_policy(&mut self, policy: WebRtcPolicy) {
+        self.webrtc_policy = Some(policy);
+        self.block_webrtc = None;
+    }
+
+    /// The proxy, honouring the setting of older versions.
+    pub fn proxy(&self) -> ProxyConfig {
+        match (&self.proxy, &self.proxy_url) {
+            (Some(proxy), _) => proxy.clone(),
+            (None, Some(url)) if !url.trim().is_empty() => ProxyConfig::Manual {
+                default: Some(url.trim().to_string()),
+                http: None,
+                https: None,
+                ignore_hosts: Vec::new(),
+            },
+            (None, _) => ProxyConfig::default(),
+        }
+    }
+
+    /// Replace the proxy, dropping the setting of older versions.
+    pub fn set_proxy(&mut self, proxy: ProxyConfig) {
+        self.proxy = Some(proxy);
+        self.proxy_url = None;
+    }
+
+    /// Settings this app needs from the network session of its process.
+    pub fn network_settings(&self) -> NetworkSettings {
+        NetworkSettings {
+            proxy: self.proxy(),
+            accept_language: self.accept_language.clone(),
+        }
+    }
+
+    /// Trust `pin`, replacing an earlier pin for its host.
+    pub fn trust_certificate(&mut self, pin: CertificatePin) {
+        let pins = self.trusted_certificates.get_or_insert_with(Vec::new);
+        pins.retain(|p| p.host != pin.host);
+        pins.push(pin);
+    }
 
-        browser
+    /// Remove the pins for `host`, or all pins if `host` is `None`.
+    pub fn untrust_certificates(&mut self, host: Option<&str>) {
+        let mut pins = self.trusted_certificates.take().unwrap_or_default();
+        pins.retain(|p| host.is_some_and(|host| p.host != host));
+        self.trusted_certificates = (!pins.is_empty()).then_some(pins);
     }
 
     pub fn from_appid(id: &str) -> Option<Self> {
-        let safe_id = sanitize_app_id(id);
-        let db_path = crate::database_path(&format!("{safe_id}.ron"))?;
+        crate::launcher::WebAppLauncher::from_appid(id).map(|launcher| launcher.browser)
+    }
 
-        let content = std::fs::read_to_string(&db_path).ok()?;
+    /// Program and arguments used to launch this web app from a desktop entry.
+    /// The [`Browser::webview_environment`] is set through `env`.
+    pub fn exec_args(&self) -> Vec<String> {
+        let environment = self.webview_environment();
+        let mut args = Vec::with_capacity(environment.len() + 3);
 
-        // Same 64KB safety limit used in launcher::installed_webapps()
-        const MAX_RON_SIZE: usize = 64 * 1024;
-        if content.len() > MAX_RON_SIZE {
-            tracing::warn!("RON file too large: {}", db_path.display());
-            return None;
+        if !environment.is_empty() {
+            args.push("env".to_string());
+            args.extend(
+                environment
+                    .into_iter()
+                    .map(|(key, value)| format!("{key}={value}")),
+            );
         }
+        args.push(format!("{}.webview", crate::APP_ID));
+        args.push(self.app_id.as_ref().to_string());
+
+        args
+    }
 
-        let launcher: crate::launcher::WebAppLauncher = ron::from_str(&content).ok()?;
-        Some(launcher.browser)
+    /// Environment the webview of this app is started with. WebKit cannot
+    /// evaluate proxy auto-config files, libproxy does for each request.
+    pub fn webview_environment(&self) -> Vec<(&'static str, String)> {
+        match self.proxy() {
+            ProxyConfig::Pac(url) => crate::proxy::pac_environment(&url).to_vec(),
+            _ => Vec::new(),
+        }
     }
 
-    pub fn get_exec(&self) -> String {
-        format!("{}.webview {}", crate::APP_ID, self.app_id.as_ref())
+    /// Where the webview keeps the copy of the last page loaded by this app.
+    pub fn offline_copy_path(&self) -> Option<std::path::PathBuf> {
+        let name = format!("{}.mhtml", sanitize_app_id(&self.app_id.id));
+        Some(
+            dirs::cache_dir()?
+                .join(crate::APP_ID)
+                .join("offline")
+                .join(name),
+        )
     }
 
+    /// Remove the offline copy and the own profile of this app, also when it
+    /// was renamed. Shared profiles and profiles other apps use are kept.
     pub fn delete(&self) {
-        if self.profile.is_some() {
-            if let Some(xdg_data) = dirs::data_dir() {
-                let path = xdg_data
-                    .join(crate::APP_ID)
-                    .join("profiles")
-                    .join(self.app_id.as_ref());
-                if let Err(e) = std::fs::remove_dir_all(&path) {
-                    tracing::error!("Failed to delete profile directory: {e}");
-                }
-            }
+        if let Some(path) = self.offline_copy_path() {
+            let _ = std::fs::remove_file(path);
+        }
+
+        let Some(profile) = self.profile.as_deref() else {
+            return;
+        };
+        // Only the own profile of an app goes with it, even when it was
+        // renamed, and only if no other app uses it.
+        if profile != self.app_id.as_ref() {
+            return;
+        }
+
+        let shared = crate::profile::users(profile)
+            .iter()
+            .any(|app| app.browser.app_id.id != self.app_id.id);
+        if shared {
+            return;
+        }
+
+        if let Err(e) = crate::profile::remove(profile) {
+            tracing::error!("Failed to delete profile directory: {e}");
         }
     }
 }
@@ -0,0 +1,393 @@
+//! Portable export bundles.
+//!
+//! A bundle is a gzip compressed tar archive with the following layout:
+//!
+//! ```text
+//! apps.ron              web app records, icon paths relative to the bundle
+//! icons/<file>          icons referenced by the records
+//! profiles.ron          names of the shared profiles used by the records
+//! profiles/<id>/        optional browser profiles
+//! ```
+//!
+//! Unlike [`crate::launcher::export_all`], a bundle can be imported on another
+//! machine without breaking icon paths.
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    icon_pipeline::{INSTALL_SIZE, icon_file},
+    launcher::{
+        ImportOutcome, ImportResult, MAX_IMPORT_APPS, MAX_IMPORT_FILE_SIZE, WebAppLauncher,
+        validate_imported_app,
+    },
+    profile::{self, Profile},
+};
+
+const APPS_FILE: &str = "apps.ron";
+const PROFILES_FILE: &str = "profiles.ron";
+const ICONS_DIR: &str = "icons";
+const PROFILES_DIR: &str = "profiles";
+/// Prefix of the temporary directories bundles are unpacked into.
+const BUNDLE_DIR_PREFIX: &str = "webapps-bundle-";
+
+/// Maximum size for a single icon inside a bundle (2 MB).
+const MAX_BUNDLE_ICON_SIZE: u64 = 2 * 1024 * 1024;
+
+/// Maximum total size of the files unpacked from an archive (4 GB).
+const MAX_UNPACKED_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Maximum number of entries unpacked from an archive.
+const MAX_UNPACKED_ENTRIES: usize = 200_000;
+
+/// Check for the gzip magic bytes to tell bundles from plain RON exports.
+pub fn is_bundle(path: &Path) -> bool {
+    let mut magic = [0u8; 2];
+
+    fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .is_ok()
+        && magic == [0x1f, 0x8b]
+}
+
+/// Write `apps` to a bundle at `path`, including the data of the profile IDs
+/// listed in `profiles`. The own profile of an app has the app ID as its ID.
+///
+/// Apps whose icon file is missing are exported with a monogram icon, their
+/// names are returned.
+pub fn export_bundle(
+    path: &Path,
+    apps: &[WebAppLauncher],
+    profiles: &[String],
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let file = fs::File::create(path)?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    builder.follow_symlinks(false);
+
+    let mut icons: HashMap<PathBuf, String> = HashMap::new();
+    let mut records = Vec::with_capacity(apps.len());
+    let mut shared: Vec<Profile> = Vec::new();
+    let mut bundled_profiles: Vec<String> = Vec::new();
+    let mut generated_icons: Vec<String> = Vec::new();
+
+    for app in apps {
+        let mut app = app.clone();
+        let app_id = app.browser.app_id.as_ref().to_string();
+        let icon_path = icon_file(&app.icon, INSTALL_SIZE);
+
+        if icon_path.is_file() {
+            let name = match icons.get(&icon_path) {
+                Some(name) => name.clone(),
+                None => {
+                    let file_name = icon_path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .unwrap_or("icon.png");
+                    let name = format!("{ICONS_DIR}/{app_id}-{file_name}");
+                    builder.append_path_with_name(&icon_path, &name)?;
+                    icons.insert(icon_path, name.clone());
+                    name
+                }
+            };
+            app.icon = name;
+        } else {
+            tracing::warn!("Icon of '{}' not found, exporting a monogram", app.name);
+            let name = format!("{ICONS_DIR}/{app_id}-monogram.svg");
+            let url = app.browser.url.as_deref().unwrap_or_default();
+            append_file(
+                &mut builder,
+                &name,
+                &crate::monogram::offline_svg(&app.name, url),
+            )?;
+            app.icon = name;
+            generated_icons.push(app.name.clone());
+        }
+
+        if let Some(id) = app.browser.profile.as_deref() {
+            if id != app_id && !shared.iter().any(|p| p.id == id) {
+                shared.push(Profile {
+                    id: id.to_string(),
+                    name: profile::name(id),
+                });
+            }
+
+            let profile_dir = profile::path(id).filter(|dir| {
+                profiles.iter().any(|p| p == id)
+                    && !bundled_profiles.iter().any(|p| p == id)
+                    && dir.is_dir()
+            });
+            if let Some(dir) = profile_dir {
+                builder.append_dir_all(format!("{PROFILES_DIR}/{id}"), dir)?;
+                bundled_profiles.push(id.to_string());
+            }
+        }
+
+        records.push(app);
+    }
+
+    let config = ron::ser::PrettyConfig::default();
+    append_file(
+        &mut builder,
+        APPS_FILE,
+        &ron::ser::to_string_pretty(&records, config.clone())?,
+    )?;
+    if !shared.is_empty() {
+        append_file(
+            &mut builder,
+            PROFILES_FILE,
+            &ron::ser::to_string_pretty(&shared, config)?,
+        )?;
+    }
+
+    builder.into_inner()?.finish()?;
+
+    Ok(generated_icons)
+}
+
+fn append_file(
+    builder: &mut tar::Builder<GzEncoder<fs::File>>,
+    name: &str,
+    content: &str,
+) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    );
+    header.set_cksum();
+    builder.append_data(&mut header, name, content.as_bytes())
+}
+
+/// An unpacked bundle. The extracted files are removed when it is dropped, so
+/// it has to outlive [`crate::launcher::install_imported`] of its apps.
+#[derive(Debug)]
+pub struct Bundle {
+    dir: tempfile::TempDir,
+    /// Validated apps with icons inside the unpacked bundle. Their icons are
+    /// only stored once the import is confirmed.
+    pub apps: Vec<WebAppLauncher>,
+    /// Names of the shared profiles used by the apps.
+    profiles: Vec<Profile>,
+}
+
+impl Bundle {
+    /// Unpack a bundle into a private temporary directory and validate its apps.
+    pub fn open(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let dir = tempfile::Builder::new()
+            .prefix(BUNDLE_DIR_PREFIX)
+            .tempdir()?;
+        unpack(path, dir.path())?;
+
+        let apps_path = dir.path().join(APPS_FILE);
+        let metadata = fs::metadata(&apps_path).map_err(|_| "Bundle contains no apps.ron")?;
+        if metadata.len() > MAX_IMPORT_FILE_SIZE {
+            return Err(format!(
+                "Bundle apps.ron too large: {} bytes (max {} bytes)",
+                metadata.len(),
+                MAX_IMPORT_FILE_SIZE
+            )
+            .into());
+        }
+
+        let records: Vec<WebAppLauncher> = ron::from_str(&fs::read_to_string(&apps_path)?)?;
+        if records.len() > MAX_IMPORT_APPS {
+            return Err(format!(
+                "Bundle contains too many apps: {} (max {})",
+                records.len(),
+                MAX_IMPORT_APPS
+            )
+            .into());
+        }
+
+        let mut apps = Vec::with_capacity(records.len());
+        for mut app in records {
+            match bundled_icon(dir.path(), &app.icon) {
+                Some(icon) => app.icon = icon,
+                None => {
+                    tracing::warn!("Rejecting bundled app '{}': missing icon", app.name);
+                    continue;
+                }
+            }
+
+            if let Some(app) = validate_imported_app(app) {
+                apps.push(app);
+            }
+        }
+
+        let profiles = fs::read_to_string(dir.path().join(PROFILES_FILE))
+            .ok()
+            .filter(|content| content.len() as u64 <= MAX_IMPORT_FILE_SIZE)
+            .and_then(|content| ron::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Ok(Self {
+            dir,
+            apps,
+            profiles,
+        })
+    }
+
+    /// Copy bundled profiles of the imported apps to their profile directories.
+    /// Profiles of skipped or failed apps are left alone. The own profile of an
+    /// app replaces the existing data, a shared profile that already exists is
+    /// kept as is.
+    pub fn install_profiles(&self, results: &[ImportResult]) {
+        for result in results {
+            let target_id = match &result.outcome {
+                ImportOutcome::Installed | ImportOutcome::Overwritten => &result.app_id,
+                ImportOutcome::Renamed(new_id) => new_id,
+                ImportOutcome::Skipped | ImportOutcome::Failed(_) => continue,
+            };
+
+            let Some(profile_id) = self
+                .apps
+                .iter()
+                .find(|app| app.browser.app_id.id == result.app_id)
+                .and_then(|app| app.browser.profile.as_deref())
+            else {
+                continue;
+            };
+
+            let source = self.dir.path().join(PROFILES_DIR).join(profile_id);
+            let own = profile_id == result.app_id;
+
+            let named = self.profiles.iter().find(|p| !own && p.id == profile_id);
+            if let Some(Err(e)) = named.map(profile::register) {
+                tracing::error!("Failed to import profile '{profile_id}': {e}");
+            }
+
+            if !source.is_dir() {
+                continue;
+            }
+
+            let target = if own {
+                profile::path(target_id)
+            } else {
+                profile::path(profile_id)
+            };
+            let Some(target) = target else {
+                continue;
+            };
+
+            let cleared = match (own, target.exists()) {
+                (true, true) => fs::remove_dir_all(&target),
+                (false, true) => continue,
+                (_, false) => Ok(()),
+            };
+
+            if let Err(e) = cleared.and_then(|()| copy_dir_all(&source, &target)) {
+                tracing::error!("Failed to import profile of '{}': {e}", result.name);
+            }
+        }
+    }
+}
+
+/// Path of the bundled icon `icon` inside the unpacked bundle, if it is a
+/// file of the icons directory small enough to import.
+fn bundled_icon(bundle_dir: &Path, icon: &str) -> Option<String> {
+    let relative = Path::new(icon);
+    if !relative.starts_with(ICONS_DIR)
+        || !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+
+    let source = bundle_dir.join(relative);
+    let metadata = fs::metadata(&source).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_BUNDLE_ICON_SIZE {
+        return None;
+    }
+
+    Some(source.to_string_lossy().to_string())
+}
+
+/// The icon of an unpacked bundle `icon` refers to, if it is one.
+pub(crate) fn staged_icon(icon: &str) -> Option<PathBuf> {
+    let path = Path::new(icon);
+    let icons_dir = path.parent()?;
+    let bundle_dir = icons_dir.parent()?;
+
+    (icons_dir.file_name()? == ICONS_DIR
+        && bundle_dir.parent() == Some(std::env::temp_dir().as_path())
+        && bundle_dir
+            .file_name()?
+            .to_str()?
+            .starts_with(BUNDLE_DIR_PREFIX)
+        && path.is_file())
+    .then(|| path.to_path_buf())
+}
+
+/// Unpack the gzip compressed tar archive at `path` into `dir`. Entries
+/// escaping `dir` and anything but files and directories are skipped.
+///
+/// Unpacking fails once the archive holds more than [`MAX_UNPACKED_ENTRIES`]
+/// entries or [`MAX_UNPACKED_SIZE`] bytes.
+pub(crate) fn unpack(path: &Path, dir: &Path) -> std::io::Result<()> {
+    let mut archive = tar::Archive::new(GzDecoder::new(fs::File::open(path)?));
+    let mut unpacked_size: u64 = 0;
+
+    for (count, entry) in archive.entries()?.enumerate() {
+        if count >= MAX_UNPACKED_ENTRIES {
+            return Err(std::io::Error::other(format!(
+                "Archive has too many entries (max {MAX_UNPACKED_ENTRIES})"
+            )));
+        }
+
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+
+        if !entry_path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            tracing::warn!("Skipping archive entry with unsafe path {entry_path:?}");
+            continue;
+        }
+
+        let entry_type = entry.header().entry_type();
+        if !entry_type.is_file() && !entry_type.is_dir() {
+            tracing::warn!("Skipping archive entry {entry_path:?} of type {entry_type:?}");
+            continue;
+        }
+
+        unpacked_size = unpacked_size.saturating_add(entry.size());
+        if unpacked_size > MAX_UNPACKED_SIZE {
+            return Err(std::io::Error::other(format!(
+                "Archive is too large when unpacked (max {MAX_UNPACKED_SIZE} bytes)"
+            )));
+        }
+
+        entry.unpack_in(dir)?;
+    }
+
+    Ok(())
+}
+
+fn copy_dir_all(source: &Path, target: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(target)?;
+
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let dest = target.join(entry.file_name());
+
+        if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &dest)?;
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), dest)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Passwords of web apps in the Secret Service keyring.
+//!
+//! Items carry the ID of the app they belong to as an attribute, so all of them
+//! can be removed together with the app. The blocking API runs its own
+//! runtime, call it from a blocking task inside async code.
+
+use secret_service::{EncryptionType, Error, blocking::SecretService};
+use std::collections::HashMap;
+
+use crate::APP_ID;
+
+const LOGIN: &str = "login";
+const CLIENT_CERTIFICATE: &str = "client-certificate";
+
+/// Attributes and secret of a keyring item.
+type Found = (HashMap<String, String>, Vec<u8>);
+
+/// A user name and password for an HTTP authentication realm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Login {
+    pub username: String,
+    pub password: String,
+}
+
+/// The login saved for `realm` of `host`.
+pub fn lookup_login(app_id: &str, host: &str, realm: &str) -> Result<Option<Login>, Error> {
+    let attributes = HashMap::from([
+        ("application", APP_ID),
+        ("app-id", app_id),
+        ("kind", LOGIN),
+        ("host", host),
+        ("realm", realm),
+    ]);
+
+    Ok(lookup(attributes)?.and_then(|(attributes, secret)| {
+        Some(Login {
+            username: attributes.get("username")?.clone(),
+            password: String::from_utf8(secret).ok()?,
+        })
+    }))
+}
+
+/// Save the login for `realm` of `host`, replacing the previous one.
+pub fn store_login(app_id: &str, host: &str, realm: &str, login: &Login) -> Result<(), Error> {
+    let attributes = HashMap::from([
+        ("application", APP_ID),
+        ("app-id", app_id),
+        ("kind", LOGIN),
+        ("host", host),
+        ("realm", realm),
+        ("username", login.username.as_str()),
+    ]);
+
+    // the user name is an attribute, drop the login of another user first
+    delete(HashMap::from([
+        ("application", APP_ID),
+        ("app-id", app_id),
+        ("kind", LOGIN),
+        ("host", host),
+        ("realm", realm),
+    ]))?;
+    store(
+        &format!("{} on {host} ({app_id})", login.username),
+        attributes,
+        login.password.as_bytes(),
+    )
+}
+
+/// The password saved for the client certificate file at `path`.
+pub fn lookup_certificate_password(app_id: &str, path: &str) -> Result<Option<String>, Error> {
+    let attributes = HashMap::from([
+        ("application", APP_ID),
+        ("app-id", app_id),
+        ("kind", CLIENT_CERTIFICATE),
+        ("path", path),
+    ]);
+
+    Ok(lookup(attributes)?.and_then(|(_, secret)| String::from_utf8(secret).ok()))
+}
+
+/// Save the password of the client certificate file at `path`.
+pub fn store_certificate_password(app_id: &str, path: &str, password: &str) -> Result<(), Error> {
+    let attributes = HashMap::from([
+        ("application", APP_ID),
+        ("app-id", app_id),
+        ("kind", CLIENT_CERTIFICATE),
+        ("path", path),
+    ]);
+
+    store(
+        &format!("Client certificate {path} ({app_id})"),
+        attributes,
+        password.as_bytes(),
+    )
+}
+
+/// Delete all passwords of the app and return how many there were.
+pub fn forget(app_id: &str) -> Result<usize, Error> {
+    delete(HashMap::from([("application", APP_ID), ("app-id", app_id)]))
+}
+
+/// Attributes and secret of the first item matching `attributes`. Unlocking a
+/// locked keyring may prompt the user.
+fn lookup(attributes: HashMap<&str, &str>) -> Result<Option<Found>, Error> {
+    let service = SecretService::connect(EncryptionType::Dh)?;
+    let items = service.search_items(attributes)?;
+    let Some(item) = items.unlocked.first().or(items.locked.first()) else {
+        return Ok(None);
+    };
+
+    if item.is_locked()? {
+        item.unlock()?;
+    }
+    Ok(Some((item.get_attributes()?, item.get_secret()?)))
+}
+
+fn store(label: &str, attributes: HashMap<&str, &str>, secret: &[u8]) -> Result<(), Error> {
+    let service = SecretService::connect(EncryptionType::Dh)?;
+    let collection = service.get_default_collection()?;
+
+    if collection.is_locked()? {
+        collection.unlock()?;
+    }
+    collection.create_item(label, attributes, secret, true, "text/plain")?;
+    Ok(())
+}
+
+fn delete(attributes: HashMap<&str, &str>) -> Result<usize, Error> {
+    let service = SecretService::connect(EncryptionType::Dh)?;
+    let items = service.search_items(attributes)?;
+
+    let mut count = 0;
+    for item in items.unlocked.iter().chain(&items.locked) {
+        if item.is_locked()? {
+            item.unlock()?;
+        }
+        item.delete()?;
+        count += 1;
+    }
+
+    Ok(count)
+}
@@ -0,0 +1,415 @@
+//! Encoding and parsing of Desktop Entry files.
+//!
+//! Values are escaped as described by the Desktop Entry Specification instead of
+//! being stripped, so names and URLs containing `;`, `%`, quotes or backslashes
+//! survive unchanged when the launcher reads them back.
+
+/// Characters that force an `Exec` argument to be quoted.
+const EXEC_RESERVED: &[char] = &[
+    ' ', '\t', '\n', '"', '\'', '\\', '>', '<', '~', '|', '&', ';', '$', '*', '?', '#', '(', ')',
+    '`',
+];
+
+/// Characters that must be backslash-escaped inside a quoted `Exec` argument.
+const EXEC_QUOTED_ESCAPES: &[char] = &['"', '`', '$', '\\'];
+
+/// Escape a value of type `string` or `localestring`.
+///
+/// Backslashes, newlines, tabs and carriage returns use the spec's escape sequences,
+/// leading and trailing spaces become `\s`, and any other control character is dropped
+/// because the spec does not allow it in a value.
+pub fn escape_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    let first_non_space = value.chars().position(|c| c != ' ');
+    let last_non_space = value
+        .chars()
+        .rev()
+        .position(|c| c != ' ')
+        .map(|pos| value.chars().count() - 1 - pos);
+
+    for (idx, c) in value.chars().enumerate() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            ' ' if first_non_space.is_none_or(|pos| idx < pos)
+                || last_non_space.is_none_or(|pos| idx > pos) =>
+            {
+                escaped.push_str("\\s")
+            }
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Reverse [`escape_string`]. Unknown escape sequences are kept verbatim.
+pub fn unescape_string(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') => unescaped.push('\\'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Quote a single `Exec` argument, escaping literal `%` as `%%`.
+///
+/// The result still has to go through [`escape_string`]; use [`escape_exec`] to build
+/// a complete value.
+pub fn quote_exec_arg(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+
+    if !arg.is_empty() && !arg.contains(EXEC_RESERVED) {
+        return arg;
+    }
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        if EXEC_QUOTED_ESCAPES.contains(&c) {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+/// Build an escaped `Exec` value from a program and its arguments.
+pub fn escape_exec<I, S>(args: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let command = args
+        .into_iter()
+        .map(|arg| quote_exec_arg(arg.as_ref()))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    escape_string(&command)
+}
+
+/// Split an unescaped `Exec` value into its arguments.
+///
+/// `%%` is turned back into `%`; other field codes are returned untouched.
+/// Returns `None` if a quoted argument is not terminated.
+pub fn split_exec(exec: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => {
+                            let next = chars.next()?;
+                            if !EXEC_QUOTED_ESCAPES.contains(&next) {
+                                current.push('\\');
+                            }
+                            current.push(next);
+                        }
+                        other => current.push(other),
+                    }
+                }
+            }
+            other => {
+                in_arg = true;
+                current.push(other);
+            }
+        }
+    }
+
+    if in_arg {
+        args.push(current);
+    }
+
+    Some(args.into_iter().map(|arg| arg.replace("%%", "%")).collect())
+}
+
+/// A parsed desktop entry file, keeping groups and keys in file order.
+#[derive(Debug, Clone, Default)]
+pub struct DesktopEntry {
+    groups: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl DesktopEntry {
+    /// Parse the contents of a `.desktop` file. Comments, blank lines and
+    /// lines outside of a group are ignored.
+    pub fn parse(content: &str) -> Self {
+        let mut groups: Vec<(String, Vec<(String, String)>)> = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim_start();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                groups.push((name.to_string(), Vec::new()));
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            if let Some((_, entries)) = groups.last_mut() {
+                entries.push((key.trim().to_string(), value.trim_start().to_string()));
+            }
+        }
+
+        Self { groups }
+    }
+
+    /// Names of all groups, in file order.
+    pub fn groups(&self) -> impl Iterator<Item = &str> {
+        self.groups.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// The raw, still escaped value of `key` in `group`.
+    pub fn get_raw(&self, group: &str, key: &str) -> Option<&str> {
+        self.groups
+            .iter()
+            .find(|(name, _)| name == group)?
+            .1
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The unescaped value of `key` in `group`.
+    pub fn get(&self, group: &str, key: &str) -> Option<String> {
+        self.get_raw(group, key).map(unescape_string)
+    }
+
+    /// The `Exec` key of `group` split into arguments.
+    pub fn exec_args(&self, group: &str) -> Option<Vec<String>> {
+        split_exec(&self.get(group, "Exec")?)
+    }
+
+    /// The values of a list key such as `Categories`, split on unescaped `;`.
+    pub fn get_list(&self, group: &str, key: &str) -> Option<Vec<String>> {
+        let raw = self.get_raw(group, key)?;
+        let mut values = Vec::new();
+        let mut current = String::new();
+        let mut chars = raw.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(';') => current.push(';'),
+                    Some(next) => {
+                        current.push('\\');
+                        current.push(next);
+                    }
+                    None => current.push('\\'),
+                },
+                ';' => values.push(unescape_string(&std::mem::take(&mut current))),
+                other => current.push(other),
+            }
+        }
+
+        if !current.is_empty() {
+            values.push(unescape_string(&current));
+        }
+
+        Some(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_round_trip() {
+        let samples = [
+            "Foo; Bar",
+            "back\\slash",
+            "line\nbreak",
+            "tab\there",
+            "  leading and trailing  ",
+            "100% = done",
+            "",
+        ];
+
+        for sample in samples {
+            let escaped = escape_string(sample);
+            assert!(!escaped.contains('\n'), "newline leaked: {escaped:?}");
+            assert_eq!(unescape_string(&escaped), sample);
+        }
+    }
+
+    #[test]
+    fn surrounding_spaces_round_trip() {
+        assert_eq!(escape_string("a  "), "a\\s\\s");
+        assert_eq!(escape_string("  a b"), "\\s\\sa b");
+
+        for value in ["a  ", "  a  ", "   "] {
+            let entry =
+                DesktopEntry::parse(&format!("[Desktop Entry]\nName={}\n", escape_string(value)));
+            assert_eq!(entry.get("Desktop Entry", "Name").as_deref(), Some(value));
+        }
+    }
+
+    #[test]
+    fn control_characters_are_dropped() {
+        assert_eq!(escape_string("a\u{7}b\u{1b}c"), "abc");
+    }
+
+    #[test]
+    fn exec_round_trip() {
+        let args = [
+            "dev.heppen.webapps.webview",
+            "plain",
+            "with space",
+            "quote\"d",
+            "$HOME",
+            "`cmd`",
+            "back\\slash",
+            "semi;colon",
+            "100%",
+            "%u",
+            "",
+        ];
+
+        let exec = escape_exec(args);
+        let parsed = split_exec(&unescape_string(&exec)).expect("valid exec");
+
+        assert_eq!(parsed, args);
+    }
+
+    #[test]
+    fn percent_is_escaped() {
+        assert_eq!(quote_exec_arg("a%20b"), "a%%20b");
+        assert_eq!(
+            escape_exec(["xdg-open", "https://x.org/?q=%u"]),
+            r#"xdg-open "https://x.org/?q=%%u""#
+        );
+    }
+
+    #[test]
+    fn unterminated_quote_is_rejected() {
+        assert_eq!(split_exec("foo \"bar"), None);
+    }
+
+    #[test]
+    fn list_values() {
+        let entry = DesktopEntry::parse("[Desktop Entry]\nCategories=Network;Office\\;Suite;\n");
+
+        assert_eq!(
+            entry.get_list("Desktop Entry", "Categories"),
+            Some(vec!["Network".to_string(), "Office;Suite".to_string()])
+        );
+    }
+
+    #[test]
+    fn launcher_entry_round_trip() {
+        let url = "https://example.com/app?a=1;b=2&c=%41 \"x\"";
+        let name = "Foo; Bar\nExec=rm -rf ~";
+
+        let mut browser = crate::browser::Browser::new("FooBar1234", false);
+        browser.url = Some(url.to_string());
+
+        let launcher = crate::launcher::WebAppLauncher {
+            browser,
+            name: name.to_string(),
+            icon: String::from("/tmp/icon.png"),
+            category: crate::Category::Network,
+            icon_overlay: None,
+        };
+
+        let entry = DesktopEntry::parse(&launcher.desktop_entry());
+
+        assert_eq!(entry.get("Desktop Entry", "Name").as_deref(), Some(name));
+        assert_eq!(
+            entry.get_raw("Desktop Entry", "Exec"),
+            entry.get_raw("Desktop Action new-window", "Exec")
+        );
+        assert_eq!(
+            entry.exec_args("Desktop Entry"),
+            Some(vec![
+                format!("{}.webview", crate::APP_ID),
+                "FooBar1234".to_string()
+            ])
+        );
+        assert_eq!(
+            entry.exec_args("Desktop Action new-private-window"),
+            Some(vec![
+                format!("{}.webview", crate::APP_ID),
+                "FooBar1234".to_string(),
+                "--private".to_string()
+            ])
+        );
+        assert_eq!(
+            entry.exec_args("Desktop Action open-in-browser"),
+            Some(vec!["xdg-open".to_string(), url.to_string()])
+        );
+        assert_eq!(
+            entry.groups().collect::<Vec<&str>>(),
+            [
+                "Desktop Entry",
+                "Desktop Action new-window",
+                "Desktop Action new-private-window",
+                "Desktop Action open-in-browser"
+            ]
+        );
+    }
+
+    #[test]
+    fn pac_environment_is_passed_through_env() {
+        let mut browser = crate::browser::Browser::new("FooBar1234", false);
+        browser.proxy = Some(crate::proxy::ProxyConfig::Pac(
+            "http://proxy.example.com/proxy.pac".to_string(),
+        ));
+
+        let exec = escape_exec(browser.exec_args());
+        let args = split_exec(&unescape_string(&exec)).expect("valid exec");
+
+        assert_eq!(args[0], "env");
+        assert!(args.contains(&"http_proxy=pac+http://proxy.example.com/proxy.pac".to_string()));
+        assert_eq!(
+            args[args.len() - 2..],
+            [
+                format!("{}.webview", crate::APP_ID),
+                "FooBar1234".to_string()
+            ]
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Native installer for the Papirus icon pack.
+//!
+//! The release archive is downloaded with wget into a private temporary
+//! directory inside [`crate::icons_location`], checked against a pinned SHA-256
+//! digest and unpacked into a staging directory. The themes are only moved into place once
+//! everything succeeded, so a failed or canceled install leaves no partial
+//! themes behind.
+//!
+//! Dropping the future returned by [`install`] cancels the installation.
+
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    io::Read,
+    path::{Component, Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Release of the Papirus icon theme that gets installed.
+pub const PAPIRUS_TAG: &str = "20250501";
+/// SHA-256 digest of the release archive of [`PAPIRUS_TAG`]. Must be updated
+/// together with the tag, `just icon-pack-sha256` prints the digest.
+const PAPIRUS_SHA256: &str = "";
+/// Themes of the archive that are installed.
+pub const PACKS: [&str; 3] = ["Papirus", "Papirus-Dark", "Papirus-Light"];
+
+/// Maximum size of the downloaded archive (256 MB).
+const MAX_ARCHIVE_SIZE: u64 = 256 * 1024 * 1024;
+/// Seconds to wait for the server before giving up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Interval between two progress reports while downloading.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+/// Number of archive entries between two progress reports while extracting.
+const EXTRACT_REPORT_INTERVAL: usize = 500;
+
+type InstallResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Progress {
+    /// Bytes of the archive received so far.
+    Downloading(u64),
+    Verifying,
+    /// Number of files extracted so far.
+    Extracting(usize),
+    Installing,
+}
+
+/// Sets the flag when dropped, which stops extraction running on a blocking
+/// thread once the [`install`] future is dropped.
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+fn archive_url() -> String {
+    format!(
+        "https://github.com/PapirusDevelopmentTeam/papirus-icon-theme/archive/refs/tags/{PAPIRUS_TAG}.tar.gz"
+    )
+}
+
+/// Download, verify and install the icon packs, reporting progress to `progress`.
+pub async fn install(progress: UnboundedSender<Progress>) -> InstallResult<()> {
+    if PAPIRUS_SHA256.is_empty() {
+        return Err(format!("no checksum pinned for icon pack {PAPIRUS_TAG}").into());
+    }
+
+    let icons_dir = crate::icons_location().ok_or("failed to resolve icons directory")?;
+    tokio::fs::create_dir_all(&icons_dir).await?;
+
+    let temp_dir = tempfile::Builder::new()
+        .prefix(".icon-pack-")
+        .tempdir_in(&icons_dir)?;
+    let archive = temp_dir.path().join("papirus.tar.gz");
+
+    download(&archive, &progress).await?;
+
+    let _ = progress.send(Progress::Verifying);
+    let path = archive.clone();
+    let digest = tokio::task::spawn_blocking(move || sha256_file(&path)).await??;
+    if !digest.eq_ignore_ascii_case(PAPIRUS_SHA256) {
+        return Err(format!("icon pack checksum mismatch: got {digest}").into());
+    }
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let _guard = CancelOnDrop(cancel.clone());
+    let staging = temp_dir.path().join("staging");
+    let (archive_path, staging_path, sender) = (archive, staging.clone(), progress.clone());
+    tokio::task::spawn_blocking(move || extract(&archive_path, &staging_path, &sender, &cancel))
+        .await??;
+
+    let _ = progress.send(Progress::Installing);
+    for pack in PACKS {
+        let source = staging.join(pack);
+        if !source.is_dir() {
+            tracing::warn!("Icon pack archive does not contain {pack}");
+            continue;
+        }
+
+        let target = icons_dir.join(pack);
+        if target.exists() {
+            tokio::fs::remove_dir_all(&target).await?;
+        }
+        tokio::fs::rename(&source, &target).await?;
+    }
+
+    Ok(())
+}
+
+/// Download the release archive to `path` with wget, reporting the bytes
+/// received. Dropping the future kills wget and stops the download.
+async fn download(path: &Path, progress: &UnboundedSender<Progress>) -> InstallResult<()> {
+    let mut child = tokio::process::Command::new("wget")
+        .arg("-q")
+        .arg("-O")
+        .arg(path)
+        .arg(format!("--timeout={}", CONNECT_TIMEOUT.as_secs()))
+        .arg("--max-redirect=3")
+        .arg(archive_url())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        let received = tokio::fs::metadata(path)
+            .await
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        if received > MAX_ARCHIVE_SIZE {
+            return Err("icon pack archive is too large".into());
+        }
+        let _ = progress.send(Progress::Downloading(received));
+
+        tokio::time::sleep(PROGRESS_INTERVAL).await;
+    };
+
+    if !status.success() {
+        return Err(format!("failed to download icon pack: wget exited with {status}").into());
+    }
+    if tokio::fs::metadata(path).await?.len() > MAX_ARCHIVE_SIZE {
+        return Err("icon pack archive is too large".into());
+    }
+
+    Ok(())
+}
+
+fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Unpack the themes listed in [`PACKS`] from the archive into `staging`,
+/// dropping the top level `papirus-icon-theme-<tag>` directory.
+fn extract(
+    archive: &Path,
+    staging: &Path,
+    progress: &UnboundedSender<Progress>,
+    cancel: &AtomicBool,
+) -> InstallResult<()> {
+    let mut archive = tar::Archive::new(GzDecoder::new(fs::File::open(archive)?));
+    let mut extracted = 0usize;
+
+    for entry in archive.entries()? {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("icon pack installation canceled".into());
+        }
+
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+
+        let Some(relative) = pack_path(&path) else {
+            continue;
+        };
+
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_symlink() {
+            let target = entry.link_name()?.ok_or("symlink without target")?;
+            if !symlink_stays_inside(&relative, &target) {
+                tracing::warn!("Skipping icon pack symlink {path:?} pointing outside the theme");
+                continue;
+            }
+        } else if !entry_type.is_file() && !entry_type.is_dir() {
+            continue;
+        }
+
+        let dest = staging.join(&relative);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        entry.unpack(&dest)?;
+
+        extracted += 1;
+        if extracted.is_multiple_of(EXTRACT_REPORT_INTERVAL) {
+            let _ = progress.send(Progress::Extracting(extracted));
+        }
+    }
+
+    Ok(())
+}
+
+/// Strip the top level directory of an archive path, keeping only safe paths
+/// inside one of the [`PACKS`].
+fn pack_path(path: &Path) -> Option<PathBuf> {
+    if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+
+    let relative: PathBuf = path.components().skip(1).collect();
+    let theme = relative.components().next()?.as_os_str().to_str()?;
+
+    PACKS.contains(&theme).then_some(relative)
+}
+
+/// Check that a relative symlink at `link` (relative to the staging directory)
+/// resolves to a path inside its own theme directory.
+fn symlink_stays_inside(link: &Path, target: &Path) -> bool {
+    // Depth of the directory containing the link, the theme directory is depth 1.
+    let mut depth = link.components().count() as isize - 1;
+
+    for component in target.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => {
+                depth -= 1;
+                if depth < 1 {
+                    return false;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+
+    depth >= 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn papirus_sha256_is_pinned() {
+        assert_eq!(PAPIRUS_SHA256.len(), 64);
+        assert!(PAPIRUS_SHA256.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
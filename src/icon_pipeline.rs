@@ -0,0 +1,387 @@
+//! Icon normalization.
+//!
+//! Icons picked by the user, downloaded favicons and imported icons all go
+//! through [`normalize`]. It decodes PNG, JPEG, GIF, ICO and SVG data, centers
+//! the image on a square transparent canvas and writes the standard hicolor
+//! sizes to `<icons dir>/hicolor/<size>x<size>/apps/<name>.png`. SVG sources
+//! are also kept as `hicolor/scalable/apps/<name>.svg`.
+//!
+//! Raster sources are never upscaled, so a 64px favicon only produces the sizes
+//! up to 64px.
+//!
+//! An [`IconOverlay`] is composited onto the icon when it is installed, not
+//! when it is stored, so changing the overlay never touches the stored sizes.
+
+use crate::monogram::{FONT_FAMILIES, Rgb, hash_color, initials, parse_color, text_color};
+use image::{DynamicImage, ImageFormat, ImageReader, Limits, RgbaImage, imageops::FilterType};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+/// Sizes written to the hicolor tree.
+pub const HICOLOR_SIZES: [u32; 8] = [16, 24, 32, 48, 64, 128, 256, 512];
+/// Size of the icon stored in web app records and handed to the launcher portal.
+pub const INSTALL_SIZE: u32 = 256;
+
+/// Maximum size of an icon source file (16 MB).
+const MAX_SOURCE_SIZE: u64 = 16 * 1024 * 1024;
+/// Maximum width or height of a decoded raster image.
+const MAX_DIMENSION: u32 = 8192;
+
+/// SVG options with the system fonts loaded, for icons containing text.
+static SVG_OPTIONS: LazyLock<resvg::usvg::Options<'static>> = LazyLock::new(|| {
+    let mut options = resvg::usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    options
+});
+
+/// Badge composited onto the bottom right corner of an installed icon, to tell
+/// apart duplicates and private variants of the same site. Colours are CSS hex
+/// colours such as `#3584e4`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub enum IconOverlay {
+    /// A coloured dot.
+    Dot(String),
+    /// A letter, e.g. the initial of a profile, on a coloured dot.
+    Initial { text: String, color: String },
+    /// A mask marking a private browsing variant.
+    Private,
+}
+
+impl IconOverlay {
+    /// Background colour of the private mode badge.
+    const PRIVATE_COLOR: Rgb = [0x24, 0x1f, 0x31];
+    /// Fallback for colours that fail to parse.
+    const FALLBACK_COLOR: Rgb = [0x77, 0x76, 0x7b];
+
+    /// A stable colour for overlays of the app `app_id`, so that duplicates of
+    /// the same site get different badges.
+    pub fn color_for(app_id: &str) -> String {
+        let [r, g, b] = hash_color(app_id);
+        format!("#{r:02x}{g:02x}{b:02x}")
+    }
+
+    /// Render the badge as an SVG on a transparent 256 x 256 canvas.
+    fn svg(&self) -> String {
+        let parse = |color: &str| parse_color(color).unwrap_or(Self::FALLBACK_COLOR);
+        let ring = r##"stroke="#ffffff" stroke-width="10""##;
+
+        let badge = match self {
+            Self::Dot(color) => {
+                let [r, g, b] = parse(color);
+                format!(
+                    r##"<circle cx="200" cy="200" r="40" fill="#{r:02x}{g:02x}{b:02x}" {ring}/>"##
+                )
+            }
+            Self::Initial { text, color } => {
+                let background = parse(color);
+                let [r, g, b] = background;
+                let foreground = text_color(background);
+                let letter = initials(text)
+                    .and_then(|text| text.chars().next())
+                    .unwrap_or('?');
+                format!(
+                    r##"<circle cx="192" cy="192" r="56" fill="#{r:02x}{g:02x}{b:02x}" {ring}/>
+  <text x="192" y="192" dominant-baseline="central" text-anchor="middle" font-family="{FONT_FAMILIES}" font-size="68" font-weight="bold" fill="{foreground}">{letter}</text>"##
+                )
+            }
+            Self::Private => {
+                let [r, g, b] = Self::PRIVATE_COLOR;
+                format!(
+                    r##"<circle cx="192" cy="192" r="56" fill="#{r:02x}{g:02x}{b:02x}" {ring}/>
+  <g fill="#ffffff">
+    <path d="M166 180 L174 152 H210 L218 180 Z"/>
+    <rect x="150" y="180" width="84" height="8" rx="4"/>
+  </g>
+  <g fill="none" stroke="#ffffff" stroke-width="6">
+    <circle cx="173" cy="208" r="11"/>
+    <circle cx="211" cy="208" r="11"/>
+    <path d="M184 206 Q192 200 200 206"/>
+  </g>"##
+                )
+            }
+        };
+
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="256" height="256" viewBox="0 0 256 256">
+  {badge}
+</svg>
+"#
+        )
+    }
+}
+
+type PipelineResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// A decoded icon source.
+enum Source {
+    Raster(DynamicImage),
+    Svg(Vec<u8>, Box<resvg::usvg::Tree>),
+}
+
+impl Source {
+    fn decode(data: &[u8]) -> PipelineResult<Self> {
+        if data.len() as u64 > MAX_SOURCE_SIZE {
+            return Err(format!("icon too large: {} bytes", data.len()).into());
+        }
+
+        if looks_like_svg(data) {
+            let tree = resvg::usvg::Tree::from_data(data, &SVG_OPTIONS)?;
+            return Ok(Self::Svg(data.to_vec(), Box::new(tree)));
+        }
+
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(MAX_DIMENSION);
+        limits.max_image_height = Some(MAX_DIMENSION);
+
+        // The ICO decoder picks the largest frame with the highest bit depth.
+        let mut reader = ImageReader::new(Cursor::new(data)).with_guessed_format()?;
+        reader.limits(limits);
+
+        Ok(Self::Raster(reader.decode()?))
+    }
+
+    /// Width or height of the source, whichever is larger. `None` for SVG.
+    fn raster_size(&self) -> Option<u32> {
+        match self {
+            Self::Raster(image) => Some(image.width().max(image.height())),
+            Self::Svg(..) => None,
+        }
+    }
+
+    /// Render the source centered on a transparent `size` x `size` canvas.
+    fn render(&self, size: u32) -> PipelineResult<RgbaImage> {
+        match self {
+            Self::Raster(image) => {
+                let (width, height) = (image.width().max(1), image.height().max(1));
+                let scale = size as f64 / width.max(height) as f64;
+                let scaled_width = ((width as f64 * scale).round() as u32).clamp(1, size);
+                let scaled_height = ((height as f64 * scale).round() as u32).clamp(1, size);
+
+                let scaled = image::imageops::resize(
+                    &image.to_rgba8(),
+                    scaled_width,
+                    scaled_height,
+                    FilterType::Lanczos3,
+                );
+
+                let mut canvas = RgbaImage::new(size, size);
+                image::imageops::overlay(
+                    &mut canvas,
+                    &scaled,
+                    i64::from((size - scaled_width) / 2),
+                    i64::from((size - scaled_height) / 2),
+                );
+                Ok(canvas)
+            }
+            Self::Svg(_, tree) => {
+                let mut pixmap = resvg::tiny_skia::Pixmap::new(size, size)
+                    .ok_or("failed to allocate icon canvas")?;
+
+                let svg_size = tree.size();
+                let scale = size as f32 / svg_size.width().max(svg_size.height());
+                let dx = (size as f32 - svg_size.width() * scale) / 2.0;
+                let dy = (size as f32 - svg_size.height() * scale) / 2.0;
+                let transform =
+                    resvg::tiny_skia::Transform::from_row(scale, 0.0, 0.0, scale, dx, dy);
+                resvg::render(tree, transform, &mut pixmap.as_mut());
+
+                let mut canvas = RgbaImage::new(size, size);
+                for (pixel, color) in canvas.pixels_mut().zip(pixmap.pixels()) {
+                    let color = color.demultiply();
+                    *pixel = image::Rgba([color.red(), color.green(), color.blue(), color.alpha()]);
+                }
+                Ok(canvas)
+            }
+        }
+    }
+}
+
+fn looks_like_svg(data: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&data[..data.len().min(1024)]);
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+
+    (head.starts_with('<') && head.contains("<svg")) || data.starts_with(&[0x1f, 0x8b])
+}
+
+/// Path of the `size` px PNG of the normalized icon `name`.
+pub fn icon_path(name: &str, size: u32) -> Option<PathBuf> {
+    Some(
+        crate::icons_location()?
+            .join("hicolor")
+            .join(format!("{size}x{size}"))
+            .join("apps")
+            .join(format!("{name}.png")),
+    )
+}
+
+/// Path of the scalable SVG of the normalized icon `name`.
+pub fn scalable_path(name: &str) -> Option<PathBuf> {
+    Some(
+        crate::icons_location()?
+            .join("hicolor")
+            .join("scalable")
+            .join("apps")
+            .join(format!("{name}.svg")),
+    )
+}
+
+/// Decode `data`, write all hicolor sizes for `name` and return the path of the
+/// icon closest to [`INSTALL_SIZE`].
+///
+/// Raster images smaller than [`crate::ICON_SIZE`] are rejected.
+pub fn normalize(data: &[u8], name: &str) -> PipelineResult<PathBuf> {
+    if name.is_empty() || name.contains('/') || name.contains('\\') || name.contains("..") {
+        return Err(format!("invalid icon name {name:?}").into());
+    }
+
+    let source = Source::decode(data)?;
+    let max_size = source.raster_size().unwrap_or(u32::MAX);
+    if max_size < crate::ICON_SIZE {
+        return Err(format!("icon too small: {max_size}px").into());
+    }
+
+    for size in HICOLOR_SIZES {
+        let path = icon_path(name, size).ok_or("failed to resolve icons directory")?;
+
+        if size > max_size {
+            // Remove sizes left over from a previous, larger icon.
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        source
+            .render(size)?
+            .save_with_format(&path, ImageFormat::Png)?;
+    }
+
+    let scalable = scalable_path(name).ok_or("failed to resolve icons directory")?;
+    match &source {
+        Source::Svg(data, _) => {
+            if let Some(parent) = scalable.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&scalable, data)?;
+        }
+        Source::Raster(_) if scalable.exists() => fs::remove_file(&scalable)?,
+        Source::Raster(_) => {}
+    }
+
+    best_size(name, INSTALL_SIZE).ok_or_else(|| "no icon sizes written".into())
+}
+
+/// Like [`normalize`], reading the source from `path`.
+pub fn normalize_file(path: &Path, name: &str) -> PipelineResult<PathBuf> {
+    let metadata = fs::metadata(path)?;
+    if metadata.len() > MAX_SOURCE_SIZE {
+        return Err(format!("icon too large: {} bytes", metadata.len()).into());
+    }
+
+    normalize(&fs::read(path)?, name)
+}
+
+/// Whether the stored icon `name` was normalized from `data`.
+pub fn is_current(data: &[u8], name: &str) -> bool {
+    let Ok(source) = Source::decode(data) else {
+        return false;
+    };
+
+    if let Source::Svg(svg, _) = &source {
+        let stored = scalable_path(name).and_then(|path| fs::read(path).ok());
+        return stored.as_deref() == Some(svg.as_slice());
+    }
+
+    let max_size = source.raster_size().unwrap_or(0);
+    let Some(size) = HICOLOR_SIZES.iter().rev().find(|s| **s <= max_size) else {
+        return false;
+    };
+
+    let mut rendered = Vec::new();
+    let encoded = source.render(*size).map(|image| {
+        image
+            .write_to(&mut Cursor::new(&mut rendered), ImageFormat::Png)
+            .is_ok()
+    });
+
+    let stored = icon_path(name, *size).and_then(|path| fs::read(path).ok());
+    matches!(encoded, Ok(true)) && stored == Some(rendered)
+}
+
+/// Path of the stored icon `name` closest to [`INSTALL_SIZE`], if it exists.
+pub fn stored_icon(name: &str) -> Option<PathBuf> {
+    best_size(name, INSTALL_SIZE)
+}
+
+/// The existing PNG of `name` that best fits `size`: the smallest one at least
+/// as large, or else the largest one.
+fn best_size(name: &str, size: u32) -> Option<PathBuf> {
+    let existing: Vec<(u32, PathBuf)> = HICOLOR_SIZES
+        .iter()
+        .filter_map(|s| Some((*s, icon_path(name, *s)?)))
+        .filter(|(_, path)| path.is_file())
+        .collect();
+
+    existing
+        .iter()
+        .find(|(s, _)| *s >= size)
+        .or_else(|| existing.last())
+        .map(|(_, path)| path.clone())
+}
+
+/// File of a stored icon, which is a path or a themed icon name picked from
+/// the icon theme. Names are looked up in the active theme at `size`.
+pub fn icon_file(icon: &str, size: u32) -> PathBuf {
+    if icon.contains('/') {
+        return PathBuf::from(icon);
+    }
+
+    crate::icon_theme::lookup(icon, size).unwrap_or_else(|| PathBuf::from(icon))
+}
+
+/// Return the variant of a stored icon that fits `size`. Icons outside the
+/// normalized hicolor tree are returned unchanged.
+pub fn icon_for_size(icon: &str, size: u32) -> PathBuf {
+    let path = icon_file(icon, size);
+    let path = path.as_path();
+    let hicolor = crate::icons_location().map(|dir| dir.join("hicolor"));
+
+    let normalized = hicolor.is_some_and(|hicolor| {
+        path.parent()
+            .and_then(Path::parent)
+            .and_then(Path::parent)
+            .is_some_and(|root| root == hicolor)
+    });
+
+    normalized
+        .then(|| path.file_stem()?.to_str())
+        .flatten()
+        .and_then(|name| best_size(name, size))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// PNG data of a stored icon at `size` px with the optional `overlay`, rendered
+/// on the fly for icons that were saved before normalization existed.
+pub fn png_bytes(icon: &str, size: u32, overlay: Option<&IconOverlay>) -> PipelineResult<Vec<u8>> {
+    let path = icon_for_size(icon, size);
+    let source = Source::decode(&fs::read(&path)?)?;
+    let mut image = source.render(size)?;
+
+    if let Some(overlay) = overlay {
+        let badge = Source::decode(overlay.svg().as_bytes())?.render(size)?;
+        image::imageops::overlay(&mut image, &badge, 0, 0);
+    }
+
+    let mut buffer = Vec::new();
+    image.write_to(&mut Cursor::new(&mut buffer), ImageFormat::Png)?;
+
+    Ok(buffer)
+}
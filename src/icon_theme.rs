@@ -0,0 +1,457 @@
+//! Icon lookup following the freedesktop Icon Theme specification.
+//!
+//! Themes are searched in the base directories `$HOME/.icons`,
+//! `$XDG_DATA_HOME/icons`, `$XDG_DATA_DIRS/icons`, the app icons directory (where
+//! downloaded icon packs live) and `/usr/share/pixmaps`. The active theme is
+//! searched first, followed by the themes it inherits from and `hicolor`.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::desktop_entry::DesktopEntry;
+
+/// Maximum size of an `index.theme` or settings file (256 KB).
+const MAX_INDEX_SIZE: u64 = 256 * 1024;
+/// Maximum depth of the `Inherits` chain.
+const MAX_INHERIT_DEPTH: usize = 16;
+/// File extensions that can be displayed, in order of preference.
+const EXTENSIONS: [&str; 2] = ["svg", "png"];
+
+/// An icon found in a theme or an unthemed base directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IconMatch {
+    /// Themed icon name, usable as the `Icon` key of a desktop entry.
+    pub name: String,
+    pub path: PathBuf,
+    /// Theme the icon belongs to, `None` for unthemed icons.
+    pub theme: Option<String>,
+    /// Nominal size in pixels, 0 if unknown.
+    pub size: u32,
+    pub scalable: bool,
+}
+
+impl IconMatch {
+    fn is_better_than(&self, other: &IconMatch) -> bool {
+        (self.scalable, self.size) > (other.scalable, other.size)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DirType {
+    Fixed,
+    Scalable,
+    Threshold,
+}
+
+/// A subdirectory of a theme as described in its `index.theme`.
+#[derive(Debug, Clone)]
+struct ThemeDir {
+    path: String,
+    size: u32,
+    scale: u32,
+    kind: DirType,
+    min_size: u32,
+    max_size: u32,
+    threshold: u32,
+}
+
+impl ThemeDir {
+    fn parse(index: &DesktopEntry, path: &str) -> Option<Self> {
+        let int = |key: &str| index.get(path, key).and_then(|v| v.trim().parse().ok());
+
+        let size = int("Size")?;
+        let kind = match index.get(path, "Type").as_deref() {
+            Some("Fixed") => DirType::Fixed,
+            Some("Scalable") => DirType::Scalable,
+            _ => DirType::Threshold,
+        };
+
+        Some(Self {
+            path: path.to_string(),
+            size,
+            scale: int("Scale").unwrap_or(1).max(1),
+            kind,
+            min_size: int("MinSize").unwrap_or(size),
+            max_size: int("MaxSize").unwrap_or(size),
+            threshold: int("Threshold").unwrap_or(2),
+        })
+    }
+
+    /// `DirectoryMatchesSize` from the specification.
+    fn matches_size(&self, size: u32, scale: u32) -> bool {
+        if self.scale != scale {
+            return false;
+        }
+
+        match self.kind {
+            DirType::Fixed => self.size == size,
+            DirType::Scalable => self.min_size <= size && size <= self.max_size,
+            DirType::Threshold => {
+                self.size.saturating_sub(self.threshold) <= size
+                    && size <= self.size + self.threshold
+            }
+        }
+    }
+
+    /// `DirectorySizeDistance` from the specification.
+    fn size_distance(&self, size: u32, scale: u32) -> u32 {
+        let wanted = size * scale;
+
+        match self.kind {
+            DirType::Fixed => (self.size * self.scale).abs_diff(wanted),
+            DirType::Scalable => {
+                if wanted < self.min_size * self.scale {
+                    self.min_size * self.scale - wanted
+                } else {
+                    wanted.saturating_sub(self.max_size * self.scale)
+                }
+            }
+            DirType::Threshold => {
+                let low = self.size.saturating_sub(self.threshold) * self.scale;
+                let high = (self.size + self.threshold) * self.scale;
+                if wanted < low {
+                    (self.min_size * self.scale).saturating_sub(wanted)
+                } else if wanted > high {
+                    wanted.saturating_sub(self.max_size * self.scale)
+                } else {
+                    0
+                }
+            }
+        }
+    }
+
+    /// Largest size in pixels the icons of this directory can be shown at.
+    fn max_pixels(&self) -> u32 {
+        match self.kind {
+            DirType::Fixed => self.size * self.scale,
+            DirType::Scalable => self.max_size * self.scale,
+            DirType::Threshold => (self.size + self.threshold) * self.scale,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IconTheme {
+    name: String,
+    /// Every `<base dir>/<name>` directory that exists.
+    roots: Vec<PathBuf>,
+    dirs: Vec<ThemeDir>,
+    inherits: Vec<String>,
+}
+
+impl IconTheme {
+    fn load(name: &str, base_dirs: &[PathBuf]) -> Option<Self> {
+        if name.is_empty() || name.contains('/') || name.contains("..") {
+            return None;
+        }
+
+        let roots: Vec<PathBuf> = base_dirs
+            .iter()
+            .map(|base| base.join(name))
+            .filter(|root| root.is_dir())
+            .collect();
+
+        let index = roots
+            .iter()
+            .find_map(|root| read_ini(&root.join("index.theme")))?;
+
+        let mut subdirs = comma_list(&index, "Directories");
+        subdirs.extend(comma_list(&index, "ScaledDirectories"));
+
+        let dirs = subdirs
+            .iter()
+            .filter(|dir| !dir.contains(".."))
+            .filter_map(|dir| ThemeDir::parse(&index, dir))
+            .collect();
+
+        Some(Self {
+            name: name.to_string(),
+            roots,
+            dirs,
+            inherits: comma_list(&index, "Inherits"),
+        })
+    }
+
+    fn icon_path(&self, dir: &ThemeDir, icon: &str) -> Option<PathBuf> {
+        self.roots.iter().find_map(|root| {
+            EXTENSIONS
+                .iter()
+                .map(|ext| root.join(&dir.path).join(format!("{icon}.{ext}")))
+                .find(|path| path.is_file())
+        })
+    }
+}
+
+/// Base directories in lookup order.
+pub fn base_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join(".icons"));
+    }
+    if let Some(data) = dirs::data_dir() {
+        dirs.push(data.join("icons"));
+    }
+
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(
+        data_dirs
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| Path::new(dir).join("icons")),
+    );
+
+    if let Some(icons) = crate::icons_location() {
+        dirs.push(icons);
+    }
+    dirs.push(PathBuf::from("/usr/share/pixmaps"));
+
+    dirs.dedup();
+    dirs
+}
+
+/// Name of the icon theme selected in the COSMIC or GTK settings.
+pub fn active_theme() -> String {
+    let Some(config) = dirs::config_dir() else {
+        return "hicolor".to_string();
+    };
+
+    let cosmic = fs::read_to_string(
+        config
+            .join("cosmic")
+            .join("com.system76.CosmicTk")
+            .join("v1")
+            .join("icon_theme"),
+    )
+    .ok()
+    .map(|value| value.trim().trim_matches('"').to_string());
+
+    let gtk = || {
+        ["gtk-4.0", "gtk-3.0"].iter().find_map(|dir| {
+            read_ini(&config.join(dir).join("settings.ini"))?.get("Settings", "gtk-icon-theme-name")
+        })
+    };
+
+    cosmic
+        .filter(|name| !name.is_empty())
+        .or_else(gtk)
+        .unwrap_or_else(|| "hicolor".to_string())
+}
+
+/// The active theme, the themes it inherits from and `hicolor`, followed by
+/// icon packs installed into the app icons directory.
+fn theme_chain(base_dirs: &[PathBuf]) -> Vec<IconTheme> {
+    let mut chain: Vec<IconTheme> = Vec::new();
+    let mut pending = vec![(active_theme(), 0)];
+
+    while let Some((name, depth)) = pending.pop() {
+        if depth > MAX_INHERIT_DEPTH || chain.iter().any(|theme| theme.name == name) {
+            continue;
+        }
+
+        if let Some(theme) = IconTheme::load(&name, base_dirs) {
+            pending.extend(
+                theme
+                    .inherits
+                    .iter()
+                    .rev()
+                    .map(|parent| (parent.clone(), depth + 1)),
+            );
+            chain.push(theme);
+        }
+    }
+
+    let mut extra = vec!["hicolor".to_string()];
+    if let Some(Ok(entries)) = crate::icons_location().map(fs::read_dir) {
+        let mut packs: Vec<String> = entries
+            .flatten()
+            .filter(|entry| entry.path().join("index.theme").is_file())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        packs.sort();
+        extra.extend(packs);
+    }
+
+    for name in extra {
+        if chain.iter().any(|theme| theme.name == name) {
+            continue;
+        }
+        if let Some(theme) = IconTheme::load(&name, base_dirs) {
+            chain.push(theme);
+        }
+    }
+
+    chain
+}
+
+/// Resolve a themed icon name to the file that best fits `size`, following the
+/// `FindIcon` algorithm of the specification.
+pub fn lookup(icon: &str, size: u32) -> Option<PathBuf> {
+    if icon.is_empty() || icon.contains('/') {
+        return None;
+    }
+
+    let base_dirs = base_dirs();
+
+    for theme in theme_chain(&base_dirs) {
+        let exact = theme
+            .dirs
+            .iter()
+            .filter(|dir| dir.matches_size(size, 1))
+            .find_map(|dir| theme.icon_path(dir, icon));
+        if exact.is_some() {
+            return exact;
+        }
+
+        let closest = theme
+            .dirs
+            .iter()
+            .filter_map(|dir| Some((dir.size_distance(size, 1), theme.icon_path(dir, icon)?)))
+            .min_by_key(|(distance, _)| *distance);
+        if let Some((_, path)) = closest {
+            return Some(path);
+        }
+    }
+
+    base_dirs.iter().find_map(|base| {
+        EXTENSIONS
+            .iter()
+            .map(|ext| base.join(format!("{icon}.{ext}")))
+            .find(|path| path.is_file())
+    })
+}
+
+/// Find icons whose name contains `query`, case insensitive.
+///
+/// Each icon name is returned once, from the first theme of the lookup chain
+/// that provides it, at its largest size of at least `min_size` pixels. Exact
+/// matches come first, then prefix matches, then the rest.
+pub fn search(query: &str, min_size: u32, limit: usize) -> Vec<IconMatch> {
+    let query = query.to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let base_dirs = base_dirs();
+    let mut found: HashMap<String, (usize, IconMatch)> = HashMap::new();
+
+    for (rank, theme) in theme_chain(&base_dirs).iter().enumerate() {
+        for dir in &theme.dirs {
+            if dir.kind != DirType::Scalable && dir.max_pixels() < min_size {
+                continue;
+            }
+
+            for root in &theme.roots {
+                for (name, path, scalable) in matching_files(&root.join(&dir.path), &query) {
+                    let candidate = IconMatch {
+                        name: name.clone(),
+                        path,
+                        theme: Some(theme.name.clone()),
+                        size: dir.size * dir.scale,
+                        scalable: scalable || dir.kind == DirType::Scalable,
+                    };
+
+                    match found.get(&name) {
+                        Some((existing_rank, _)) if *existing_rank < rank => {}
+                        Some((_, existing)) if !candidate.is_better_than(existing) => {}
+                        _ => {
+                            found.insert(name, (rank, candidate));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    for base in &base_dirs {
+        for (name, path, scalable) in matching_files(base, &query) {
+            found.entry(name.clone()).or_insert((
+                usize::MAX,
+                IconMatch {
+                    name,
+                    path,
+                    theme: None,
+                    size: 0,
+                    scalable,
+                },
+            ));
+        }
+    }
+
+    let mut results: Vec<(usize, IconMatch)> = found.into_values().collect();
+    results.sort_by_key(|(rank, icon)| {
+        let name = icon.name.to_lowercase();
+        let quality = if name == query {
+            0
+        } else if name.starts_with(&query) {
+            1
+        } else {
+            2
+        };
+        (
+            quality,
+            *rank,
+            !icon.scalable,
+            std::cmp::Reverse(icon.size),
+            icon.name.clone(),
+        )
+    });
+
+    results
+        .into_iter()
+        .map(|(_, icon)| icon)
+        .take(limit)
+        .collect()
+}
+
+/// Icon files directly inside `dir` whose name contains `query`.
+fn matching_files(dir: &Path, query: &str) -> Vec<(String, PathBuf, bool)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let ext = path.extension()?.to_str()?.to_lowercase();
+            if !EXTENSIONS.contains(&ext.as_str()) {
+                return None;
+            }
+
+            let name = path.file_stem()?.to_str()?.to_string();
+            if !name.to_lowercase().contains(query) || !path.is_file() {
+                return None;
+            }
+
+            Some((name, path, ext == "svg"))
+        })
+        .collect()
+}
+
+/// Theme lists such as `Directories` and `Inherits` are separated by commas.
+fn comma_list(index: &DesktopEntry, key: &str) -> Vec<String> {
+    index
+        .get("Icon Theme", key)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn read_ini(path: &Path) -> Option<DesktopEntry> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_INDEX_SIZE {
+        return None;
+    }
+
+    Some(DesktopEntry::parse(&fs::read_to_string(path).ok()?))
+}
@@ -0,0 +1,437 @@
+//! Read-only view of the website data stored in a profile.
+//!
+//! Everything is read from the files WebKit writes into the profile directory:
+//! the cookie jar, the legacy `localstorage` and `databases/indexeddb`
+//! directories and the per-origin directories of `storage`. Data of a single
+//! domain can be deleted with [`clear_domain`].
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+use crate::{
+    instance, profile,
+    site_data::{self, COOKIES_FILE, SiteData},
+};
+
+type InspectorResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+const LOCAL_STORAGE_DIR: &str = "localstorage";
+const LOCAL_STORAGE_EXT: &str = ".localstorage";
+const INDEXED_DB_DIR: &str = "databases/indexeddb";
+const INDEXED_DB_FILE: &str = "IndexedDB.sqlite3";
+/// Per-origin storage of newer WebKit versions, `storage/<hash>/<hash>/`.
+const GENERAL_STORAGE_DIR: &str = "storage";
+const ORIGIN_FILE: &str = "origin";
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+/// Origin files only hold two origins, anything bigger is not one.
+const MAX_ORIGIN_FILE_SIZE: u64 = 64 * 1024;
+
+/// A cookie in the cookie jar of a profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie {
+    pub domain: String,
+    pub name: String,
+    pub path: String,
+    /// Unix time the cookie expires at, `None` for session cookies.
+    pub expires: Option<u64>,
+    pub secure: bool,
+    pub http_only: bool,
+}
+
+/// Storage of one origin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OriginData {
+    pub origin: String,
+    pub host: String,
+    pub size: u64,
+    /// Number of IndexedDB databases, 0 for local storage.
+    pub databases: usize,
+}
+
+/// Everything a profile stores, as found on disk.
+#[derive(Debug, Clone, Default)]
+pub struct Inspection {
+    pub cookies: Vec<Cookie>,
+    pub local_storage: Vec<OriginData>,
+    pub indexed_db: Vec<OriginData>,
+    pub cache_size: u64,
+}
+
+impl Inspection {
+    /// Domains that have cookies or storage, sorted.
+    pub fn domains(&self) -> Vec<String> {
+        let hosts = self
+            .local_storage
+            .iter()
+            .chain(&self.indexed_db)
+            .map(|data| data.host.clone());
+
+        self.cookies
+            .iter()
+            .map(|cookie| cookie.domain.trim_start_matches('.').to_string())
+            .chain(hosts)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    pub fn cookies_of<'a>(&'a self, domain: &'a str) -> impl Iterator<Item = &'a Cookie> {
+        self.cookies
+            .iter()
+            .filter(move |cookie| cookie.domain.trim_start_matches('.') == domain)
+    }
+
+    pub fn local_storage_of<'a>(&'a self, domain: &'a str) -> impl Iterator<Item = &'a OriginData> {
+        self.local_storage
+            .iter()
+            .filter(move |data| data.host == domain)
+    }
+
+    pub fn indexed_db_of<'a>(&'a self, domain: &'a str) -> impl Iterator<Item = &'a OriginData> {
+        self.indexed_db
+            .iter()
+            .filter(move |data| data.host == domain)
+    }
+}
+
+/// Whether `host` is `domain` or one of its subdomains.
+pub fn matches_domain(host: &str, domain: &str) -> bool {
+    let host = host.trim_start_matches('.');
+    let domain = domain.trim_start_matches('.');
+
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|sub| sub.ends_with('.'))
+}
+
+/// Read the website data of the profile `id`.
+pub fn inspect(id: &str) -> Inspection {
+    let Some(dir) = profile::path(id) else {
+        return Inspection::default();
+    };
+
+    let mut local_storage = legacy_local_storage(&dir.join(LOCAL_STORAGE_DIR));
+    let mut indexed_db = legacy_indexed_db(&dir.join(INDEXED_DB_DIR));
+    for origin_dir in origin_dirs(&dir.join(GENERAL_STORAGE_DIR)) {
+        let Some((_, origin)) = read_origin_file(&origin_dir.join(ORIGIN_FILE)) else {
+            continue;
+        };
+
+        let local = origin_dir.join("LocalStorage");
+        if local.is_dir() {
+            merge(&mut local_storage, &origin, dir_size(&local), 0);
+        }
+
+        let databases = subdirs(&origin_dir.join("IndexedDB"));
+        if !databases.is_empty() {
+            let size = databases.iter().map(|db| dir_size(db)).sum();
+            merge(&mut indexed_db, &origin, size, databases.len());
+        }
+    }
+
+    local_storage.sort_by(|a, b| a.origin.cmp(&b.origin));
+    indexed_db.sort_by(|a, b| a.origin.cmp(&b.origin));
+
+    Inspection {
+        cookies: read_cookies(&dir.join(COOKIES_FILE)),
+        local_storage,
+        indexed_db,
+        cache_size: site_data::size(id, SiteData::Cache),
+    }
+}
+
+/// Delete the cookies and storage of `domain` and its subdomains from the
+/// profile `id`.
+pub async fn clear_domain(id: &str, domain: &str) -> InspectorResult<()> {
+    let domain = domain.trim().trim_start_matches('.').to_lowercase();
+    if domain.is_empty() {
+        return Err("domain is empty".into());
+    }
+
+    // WebKit keeps cookies and storage in memory, a running webview has to
+    // delete them itself
+    if instance::clear_domain(id, &domain).await? {
+        return Ok(());
+    }
+
+    let Some(dir) = profile::path(id) else {
+        return Ok(());
+    };
+
+    tokio::task::spawn_blocking(move || remove_domain_files(&dir, &domain)).await??;
+
+    Ok(())
+}
+
+fn remove_domain_files(dir: &Path, domain: &str) -> std::io::Result<()> {
+    let cookies = dir.join(COOKIES_FILE);
+    if let Ok(content) = fs::read_to_string(&cookies) {
+        let kept: Vec<&str> = content
+            .lines()
+            .filter(|line| {
+                parse_cookie(line).is_none_or(|cookie| !matches_domain(&cookie.domain, domain))
+            })
+            .collect();
+        fs::write(&cookies, kept.join("\n") + "\n")?;
+    }
+
+    // Local storage files come with -wal and -shm companions
+    for entry in read_dir(&dir.join(LOCAL_STORAGE_DIR)) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let identifier = name.split(LOCAL_STORAGE_EXT).next().unwrap_or_default();
+        if parse_identifier(identifier).is_some_and(|(_, host)| matches_domain(&host, domain)) {
+            fs::remove_file(entry.path())?;
+        }
+    }
+
+    // Legacy IndexedDB nests the opening origin in the top origin since v1
+    let databases: Vec<PathBuf> = WalkDir::new(dir.join(INDEXED_DB_DIR))
+        .min_depth(1)
+        .max_depth(3)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir())
+        .map(|e| e.into_path())
+        .filter(|path| origin_of_dir(path).is_some_and(|(_, host)| matches_domain(&host, domain)))
+        .collect();
+    for path in databases {
+        if path.exists() {
+            fs::remove_dir_all(path)?;
+        }
+    }
+
+    for origin_dir in origin_dirs(&dir.join(GENERAL_STORAGE_DIR)) {
+        let matches = read_origin_file(&origin_dir.join(ORIGIN_FILE)).is_some_and(
+            |((_, top), (_, client))| {
+                matches_domain(&top, domain) || matches_domain(&client, domain)
+            },
+        );
+        if matches {
+            fs::remove_dir_all(origin_dir)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn read_cookies(path: &Path) -> Vec<Cookie> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    let mut cookies: Vec<Cookie> = content.lines().filter_map(parse_cookie).collect();
+    cookies.sort_by(|a, b| a.domain.cmp(&b.domain).then(a.name.cmp(&b.name)));
+    cookies
+}
+
+/// Parse a line of the Mozilla-style cookie jar written by libsoup.
+fn parse_cookie(line: &str) -> Option<Cookie> {
+    let (line, http_only) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+        Some(line) => (line, true),
+        None if line.starts_with('#') => return None,
+        None => (line, false),
+    };
+
+    let mut fields = line.split('\t');
+    let domain = fields.next()?.to_lowercase();
+    let _host_only = fields.next()?;
+    let path = fields.next()?.to_string();
+    let secure = fields.next()? == "TRUE";
+    let expires = fields.next()?.parse::<u64>().ok().filter(|&time| time > 0);
+    let name = fields.next()?.to_string();
+
+    (!domain.is_empty()).then_some(Cookie {
+        domain,
+        name,
+        path,
+        expires,
+        secure,
+        http_only,
+    })
+}
+
+fn legacy_local_storage(dir: &Path) -> Vec<OriginData> {
+    let mut origins = Vec::new();
+    for entry in read_dir(dir) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(identifier) = name.split(LOCAL_STORAGE_EXT).next() else {
+            continue;
+        };
+        if let Some(origin) = parse_identifier(identifier) {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            merge(&mut origins, &origin, size, 0);
+        }
+    }
+
+    origins
+}
+
+fn legacy_indexed_db(dir: &Path) -> Vec<OriginData> {
+    let mut origins = Vec::new();
+    let files = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name() == INDEXED_DB_FILE);
+
+    for file in files {
+        let Some(database) = file.path().parent() else {
+            continue;
+        };
+        if let Some(origin) = database.parent().and_then(origin_of_dir) {
+            merge(&mut origins, &origin, dir_size(database), 1);
+        }
+    }
+
+    origins
+}
+
+/// Add the storage of `origin` to `origins`, summing up repeated origins.
+fn merge(origins: &mut Vec<OriginData>, origin: &(String, String), size: u64, databases: usize) {
+    match origins.iter_mut().find(|data| data.origin == origin.0) {
+        Some(data) => {
+            data.size += size;
+            data.databases += databases;
+        }
+        None => origins.push(OriginData {
+            origin: origin.0.clone(),
+            host: origin.1.clone(),
+            size,
+            databases,
+        }),
+    }
+}
+
+fn origin_of_dir(dir: &Path) -> Option<(String, String)> {
+    parse_identifier(dir.file_name()?.to_str()?)
+}
+
+/// Parse a WebKit origin identifier like `https_example.com_0` into the origin
+/// and its host.
+fn parse_identifier(identifier: &str) -> Option<(String, String)> {
+    let (protocol, rest) = identifier.split_once('_')?;
+    let (host, port) = rest.rsplit_once('_')?;
+    let port: u16 = port.parse().ok()?;
+
+    if protocol.is_empty() || host.is_empty() {
+        return None;
+    }
+
+    Some(origin(protocol, host, (port != 0).then_some(port)))
+}
+
+fn origin(protocol: &str, host: &str, port: Option<u16>) -> (String, String) {
+    let host = host.to_lowercase();
+    let origin = match port {
+        Some(port) => format!("{protocol}://{host}:{port}"),
+        None => format!("{protocol}://{host}"),
+    };
+
+    (origin, host)
+}
+
+/// Directories of `storage/<top origin hash>/<origin hash>`.
+fn origin_dirs(dir: &Path) -> Vec<PathBuf> {
+    subdirs(dir).iter().flat_map(|top| subdirs(top)).collect()
+}
+
+/// Read the top and the client origin from an origin file of the general
+/// storage directory, a WebKit persistence encoding of both.
+fn read_origin_file(path: &Path) -> Option<((String, String), (String, String))> {
+    if fs::metadata(path).ok()?.len() > MAX_ORIGIN_FILE_SIZE {
+        return None;
+    }
+
+    let data = fs::read(path).ok()?;
+    let mut decoder = Decoder(&data);
+    let top = decoder.origin()?;
+    let client = decoder.origin()?;
+
+    Some((top, client))
+}
+
+struct Decoder<'a>(&'a [u8]);
+
+impl Decoder<'_> {
+    fn bytes(&mut self, len: usize) -> Option<&[u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|bytes| bytes[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.bytes(2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.bytes(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = self.u32()?;
+        if len == u32::MAX {
+            return Some(String::new());
+        }
+
+        let len = len as usize;
+        if self.u8()? != 0 {
+            Some(self.bytes(len)?.iter().map(|&b| b as char).collect())
+        } else {
+            let units: Vec<u16> = self
+                .bytes(len.checked_mul(2)?)?
+                .chunks_exact(2)
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .collect();
+            String::from_utf16(&units).ok()
+        }
+    }
+
+    fn origin(&mut self) -> Option<(String, String)> {
+        let protocol = self.string()?;
+        let host = self.string()?;
+        let port = if self.u8()? != 0 {
+            Some(self.u16()?)
+        } else {
+            None
+        };
+
+        (!protocol.is_empty() && !host.is_empty()).then(|| origin(&protocol, &host, port))
+    }
+}
+
+fn read_dir(dir: &Path) -> Vec<fs::DirEntry> {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().collect())
+        .unwrap_or_default()
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    read_dir(dir)
+        .into_iter()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
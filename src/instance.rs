@@ -0,0 +1,292 @@
+//! One webview process per profile.
+//!
+//! WebKit cannot share a data directory between processes, so all apps using
+//! the same persistent profile run in a single webview process. The first
+//! process of a profile owns the D-Bus name returned by [`bus_name`] and opens
+//! later launches as extra windows. Later processes forward their app to it
+//! and exit. The owner also clears website data on behalf of other processes,
+//! see [`clear_data`] and [`clear_domain`], and prints the pages of its apps.
+//!
+//! The owner lists the apps it shows in the runtime directory, so
+//! [`crate::running_webview_app_ids`] finds forwarded apps as well.
+
+use sha2::{Digest, Sha256};
+use std::{fs, path::PathBuf, sync::Arc};
+use tokio::sync::oneshot;
+use zbus::{fdo::RequestNameFlags, names::BusName};
+
+use crate::{APP_ID, browser::sanitize_app_id, site_data::SiteData};
+
+const OBJECT_PATH: &str = "/dev/heppen/webapps/Webview";
+const INTERFACE: &str = "dev.heppen.webapps.Webview";
+/// Directory in `$XDG_RUNTIME_DIR/<APP_ID>` listing the apps of each owner.
+const INSTANCES_DIR: &str = "instances";
+/// Bus names are limited to 255 characters, longer profile IDs are hashed.
+const MAX_NAME_ELEMENT_LEN: usize = 128;
+/// How often to retry when the owner exits while a launch is forwarded.
+const ATTEMPTS: usize = 3;
+
+type InstanceResult<T> = Result<T, Box<dyn std::error::Error>>;
+type RequestHandler = Arc<dyn Fn(Request) + Send + Sync>;
+
+/// A request sent to the process owning a profile.
+#[derive(Debug)]
+pub enum Request {
+    /// Open the app with this ID in a new window.
+    Open(String),
+    /// Clear website data of the profile and report the result.
+    ClearData(Vec<SiteData>, oneshot::Sender<Result<(), String>>),
+    /// Delete the website data of a domain and report the result.
+    ClearDomain(String, oneshot::Sender<Result<(), String>>),
+    /// Show the print dialog for the page of the app with this ID.
+    Print(String),
+    /// Save the page of the app as PDF and report the path of the file.
+    SavePdf(String, oneshot::Sender<Result<String, String>>),
+}
+
+pub enum Instance {
+    /// This process owns the profile and opens forwarded apps.
+    Primary(Primary),
+    /// The app was handed to the process owning the profile.
+    Forwarded,
+}
+
+/// Ownership of a profile. The name is released when this is dropped.
+pub struct Primary {
+    connection: Option<zbus::Connection>,
+    runtime: tokio::runtime::Runtime,
+    apps_file: Option<PathBuf>,
+}
+
+struct Service {
+    handler: RequestHandler,
+}
+
+#[zbus::interface(name = "dev.heppen.webapps.Webview")]
+impl Service {
+    fn open(&self, app_id: String) {
+        let app_id = sanitize_app_id(&app_id);
+        if !app_id.is_empty() {
+            (self.handler)(Request::Open(app_id));
+        }
+    }
+
+    async fn clear_data(&self, data: Vec<String>) -> zbus::fdo::Result<()> {
+        let data = data
+            .iter()
+            .filter_map(|name| SiteData::from_name(name))
+            .collect();
+        let (done, result) = oneshot::channel();
+        (self.handler)(Request::ClearData(data, done));
+
+        reply(result).await
+    }
+
+    async fn clear_domain(&self, domain: String) -> zbus::fdo::Result<()> {
+        let (done, result) = oneshot::channel();
+        (self.handler)(Request::ClearDomain(domain, done));
+
+        reply(result).await
+    }
+
+    fn print(&self, app_id: String) {
+        (self.handler)(Request::Print(sanitize_app_id(&app_id)));
+    }
+
+    async fn save_pdf(&self, app_id: String) -> zbus::fdo::Result<String> {
+        let (done, result) = oneshot::channel();
+        (self.handler)(Request::SavePdf(sanitize_app_id(&app_id), done));
+
+        reply(result).await
+    }
+}
+
+async fn reply<T>(result: oneshot::Receiver<Result<T, String>>) -> zbus::fdo::Result<T> {
+    match result.await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(zbus::fdo::Error::Failed(e)),
+        Err(_) => Err(zbus::fdo::Error::Failed("webview exited".to_string())),
+    }
+}
+
+/// Well-known bus name of the process owning `profile`.
+pub fn bus_name(profile: &str) -> String {
+    let mut element = String::new();
+    for byte in profile.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' {
+            element.push(byte as char);
+        } else {
+            element.push_str(&format!("_{byte:02x}"));
+        }
+    }
+
+    if element.len() > MAX_NAME_ELEMENT_LEN {
+        element = format!("{:x}", Sha256::digest(profile.as_bytes()));
+    }
+
+    format!("{APP_ID}.webview.Profile_{element}")
+}
+
+/// Become the owner of `profile`, or forward `app_id` to the current owner.
+/// `handler` is called with every request sent to this process.
+pub fn acquire(
+    profile: &str,
+    app_id: &str,
+    handler: impl Fn(Request) + Send + Sync + 'static,
+) -> InstanceResult<Instance> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let name = bus_name(profile);
+    let handler: RequestHandler = Arc::new(handler);
+
+    for _ in 0..ATTEMPTS {
+        let service = Service {
+            handler: handler.clone(),
+        };
+        let served = runtime.block_on(async {
+            let connection = zbus::connection::Builder::session()?
+                .serve_at(OBJECT_PATH, service)?
+                .build()
+                .await?;
+            // without DoNotQueue a taken name queues this process instead of failing
+            connection
+                .request_name_with_flags(name.as_str(), RequestNameFlags::DoNotQueue.into())
+                .await?;
+
+            Ok::<_, zbus::Error>(connection)
+        });
+
+        match served {
+            Ok(connection) => {
+                return Ok(Instance::Primary(Primary {
+                    connection: Some(connection),
+                    runtime,
+                    apps_file: apps_file(std::process::id()),
+                }));
+            }
+            Err(zbus::Error::NameTaken) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        match runtime.block_on(forward(&name, app_id)) {
+            Ok(()) => return Ok(Instance::Forwarded),
+            Err(e) => tracing::warn!("Failed to forward '{app_id}' to {name}: {e}"),
+        }
+    }
+
+    Err(format!("failed to reach the webview process of profile '{profile}'").into())
+}
+
+async fn forward(name: &str, app_id: &str) -> zbus::Result<()> {
+    let connection = zbus::Connection::session().await?;
+    connection
+        .call_method(Some(name), OBJECT_PATH, Some(INTERFACE), "Open", &(app_id,))
+        .await?;
+
+    Ok(())
+}
+
+/// Ask the process owning `profile` to clear `data`. Returns `false` if no
+/// process owns it, the data can then be removed from disk.
+pub async fn clear_data(profile: &str, data: &[SiteData]) -> zbus::Result<bool> {
+    let data: Vec<&str> = data.iter().map(|data| data.as_str()).collect();
+    call_owner(profile, "ClearData", &(data,)).await
+}
+
+/// Ask the process owning `profile` to delete the data of `domain`. Returns
+/// `false` if no process owns it.
+pub async fn clear_domain(profile: &str, domain: &str) -> zbus::Result<bool> {
+    call_owner(profile, "ClearDomain", &(domain,)).await
+}
+
+/// Call `method` on the owner of `profile`, if there is one.
+async fn call_owner<B>(profile: &str, method: &str, body: &B) -> zbus::Result<bool>
+where
+    B: serde::Serialize + zbus::zvariant::DynamicType,
+{
+    let Ok(connection) = zbus::Connection::session().await else {
+        return Ok(false);
+    };
+
+    let name = bus_name(profile);
+    let dbus = zbus::fdo::DBusProxy::new(&connection).await?;
+    if !dbus
+        .name_has_owner(BusName::try_from(name.as_str())?)
+        .await?
+    {
+        return Ok(false);
+    }
+
+    connection
+        .call_method(
+            Some(name.as_str()),
+            OBJECT_PATH,
+            Some(INTERFACE),
+            method,
+            body,
+        )
+        .await?;
+
+    Ok(true)
+}
+
+impl Primary {
+    /// Record the apps shown by this process.
+    pub fn set_apps<'a>(&self, apps: impl IntoIterator<Item = &'a str>) {
+        let Some(path) = &self.apps_file else {
+            return;
+        };
+
+        let content: Vec<&str> = apps.into_iter().collect();
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(path, content.join("\n")));
+        if let Err(e) = written {
+            tracing::warn!("Failed to record running apps: {e}");
+        }
+    }
+}
+
+impl Drop for Primary {
+    fn drop(&mut self) {
+        if let Some(path) = &self.apps_file {
+            let _ = fs::remove_file(path);
+        }
+
+        // zbus tears the connection down on the runtime it was created on
+        let _guard = self.runtime.enter();
+        self.connection.take();
+    }
+}
+
+fn apps_file(pid: u32) -> Option<PathBuf> {
+    dirs::runtime_dir().map(|dir| dir.join(APP_ID).join(INSTANCES_DIR).join(pid.to_string()))
+}
+
+/// App IDs shown by owners of a profile that are still running.
+pub(crate) fn running_app_ids() -> Vec<String> {
+    let Some(dir) = dirs::runtime_dir().map(|dir| dir.join(APP_ID).join(INSTANCES_DIR)) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|pid| PathBuf::from("/proc").join(pid).exists())
+        })
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .map(sanitize_app_id)
+                .filter(|id| !id.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
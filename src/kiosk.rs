@@ -0,0 +1,98 @@
+//! Kiosk mode for web apps on shared screens.
+//!
+//! A kiosk fills the screen without decorations and keeps to the site of its
+//! start page. The webview ignores the context menu and keyboard shortcuts,
+//! only the exit shortcut closes the window. After the page crashed, or when
+//! nobody used it for a while, the kiosk loads its start page again.
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::inspector::matches_domain;
+
+/// Exit shortcut of kiosks that do not set their own.
+pub const DEFAULT_EXIT_SHORTCUT: &str = "Ctrl+Alt+Q";
+
+/// Settings of an app shown as a kiosk.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct KioskConfig {
+    /// Shortcut closing the kiosk, see [`Shortcut::parse`].
+    pub exit_shortcut: String,
+    /// Minutes without input after which the start page is loaded again.
+    pub idle_reset: Option<u32>,
+    /// Domains the kiosk may show besides the one of its start page.
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+}
+
+impl Default for KioskConfig {
+    fn default() -> Self {
+        Self {
+            exit_shortcut: DEFAULT_EXIT_SHORTCUT.to_string(),
+            idle_reset: None,
+            allowed_domains: Vec::new(),
+        }
+    }
+}
+
+impl KioskConfig {
+    /// Whether a kiosk starting at `home` may navigate to `url`. Only web
+    /// pages of the host of `home`, its subdomains and the allowed domains
+    /// are in scope.
+    pub fn allows(&self, home: &str, url: &str) -> bool {
+        let (Ok(home), Ok(url)) = (Url::parse(home), Url::parse(url)) else {
+            return false;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            return false;
+        }
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+
+        home.host_str()
+            .into_iter()
+            .chain(self.allowed_domains.iter().map(String::as_str))
+            .any(|domain| matches_domain(host, domain))
+    }
+}
+
+/// A key and the modifiers held with it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Shortcut {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub super_key: bool,
+    /// Lower case GDK name of the key, e.g. `q` or `escape`.
+    pub key: String,
+}
+
+impl Shortcut {
+    /// Parse a shortcut like `Ctrl+Alt+Q`. It needs Ctrl, Alt or Super, so
+    /// typing on the page never triggers it.
+    pub fn parse(value: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut shortcut = Shortcut::default();
+        let mut parts: Vec<&str> = value.split('+').map(str::trim).collect();
+        let key = parts.pop().unwrap_or_default();
+
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => shortcut.ctrl = true,
+                "alt" => shortcut.alt = true,
+                "shift" => shortcut.shift = true,
+                "super" | "meta" | "logo" => shortcut.super_key = true,
+                other => return Err(format!("unknown modifier '{other}'").into()),
+            }
+        }
+        if key.is_empty() {
+            return Err("shortcut has no key".into());
+        }
+        if !(shortcut.ctrl || shortcut.alt || shortcut.super_key) {
+            return Err("shortcut needs Ctrl, Alt or Super".into());
+        }
+        shortcut.key = key.to_lowercase();
+
+        Ok(shortcut)
+    }
+}
@@ -9,20 +9,10 @@ use std::{
 };
 use tokio::fs::remove_file;
 
-use crate::APP_ID;
-
-/// Sanitize a string for use in a desktop entry field.
-/// Strips newlines, carriage returns, tabs, backslashes, semicolons,
-/// and all ASCII control characters to prevent key injection and value manipulation.
-fn sanitize_desktop_field(s: &str) -> String {
-    s.chars()
-        .filter(|c| {
-            !c.is_ascii_control() // strips \n, \r, \t, and all chars < 0x20
-                && *c != '\\'
-                && *c != ';'
-        })
-        .collect()
-}
+use crate::{
+    APP_ID, WebviewArgs,
+    desktop_entry::{escape_exec, escape_string},
+};
 
 pub fn webapplauncher_is_valid(
     icon: &str,
@@ -47,6 +37,18 @@ pub fn webapplauncher_is_valid(
 /// Maximum size for a single RON database file (64 KB).
 const MAX_RON_FILE_SIZE: u64 = 64 * 1024;
 
+/// Command starting the webview with `args`, in the
+/// [`crate::browser::Browser::webview_environment`] of the app.
+pub fn webview_command(args: WebviewArgs) -> std::process::Command {
+    let environment = WebAppLauncher::from_appid(args.as_ref())
+        .map(|launcher| launcher.browser.webview_environment())
+        .unwrap_or_default();
+
+    let mut command = std::process::Command::new(format!("{APP_ID}.webview"));
+    command.args(args).envs(environment);
+    command
+}
+
 pub fn installed_webapps() -> Vec<WebAppLauncher> {
     let mut webapps = Vec::new();
 
@@ -93,43 +95,93 @@ pub struct WebAppLauncher {
     pub name: String,
     pub icon: String,
     pub category: crate::Category,
+    /// Badge composited onto the installed icon.
+    pub icon_overlay: Option<crate::icon_pipeline::IconOverlay>,
 }
 
 impl WebAppLauncher {
-    pub async fn create(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let safe_name = sanitize_desktop_field(&self.name);
-        let safe_wm_class = sanitize_desktop_field(&self.browser.app_id.id);
-        let safe_exec = sanitize_desktop_field(&self.browser.get_exec());
+    /// Load a single web app from the database by its app id.
+    pub fn from_appid(id: &str) -> Option<Self> {
+        let safe_id = crate::browser::sanitize_app_id(id);
+        let db_path = crate::database_path(&format!("{safe_id}.ron"))?;
+
+        let metadata = fs::metadata(&db_path).ok()?;
+        if metadata.len() > MAX_RON_FILE_SIZE {
+            tracing::warn!("RON file too large: {}", db_path.display());
+            return None;
+        }
+
+        let content = fs::read_to_string(&db_path).ok()?;
+        ron::from_str(&content).ok()
+    }
+
+    /// Write this web app to the database.
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let location = crate::database_path(&format!("{}.ron", self.browser.app_id.as_ref()))
+            .ok_or("Failed to resolve database path")?;
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        fs::write(location, content)?;
+        Ok(())
+    }
+
+    /// Bump the usage statistics before launching the web app.
+    pub fn record_launch(&mut self) {
+        let count = self.browser.launch_count.unwrap_or(0);
+        self.browser.launch_count = Some(count + 1);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.browser.last_launched = Some(now);
+    }
+
+    /// Render the desktop entry installed for this web app.
+    pub fn desktop_entry(&self) -> String {
+        let name = escape_string(&self.name);
+        let wm_class = escape_string(&self.browser.app_id.id);
+        let exec_args = self.browser.exec_args();
+        let exec = escape_exec(&exec_args);
+        let private_exec = escape_exec(exec_args.iter().map(String::as_str).chain(["--private"]));
+        let browser_exec = escape_exec([
+            "xdg-open",
+            self.browser.url.as_deref().unwrap_or("about:blank"),
+        ]);
 
         let mut desktop_entry = String::new();
 
         desktop_entry.push_str("[Desktop Entry]\n");
         desktop_entry.push_str("Version=1.0\n");
         desktop_entry.push_str("Type=Application\n");
-        desktop_entry.push_str(&format!("Name={safe_name}\n"));
+        desktop_entry.push_str(&format!("Name={name}\n"));
         desktop_entry.push_str("Comment=Quick WebApp\n");
-        desktop_entry.push_str(&format!("Exec={safe_exec}\n"));
-        desktop_entry.push_str(&format!("StartupWMClass={safe_wm_class}\n"));
+        desktop_entry.push_str(&format!("Exec={exec}\n"));
+        desktop_entry.push_str(&format!("StartupWMClass={wm_class}\n"));
         desktop_entry.push_str(&format!("Categories={}\n", self.category.as_ref()));
         desktop_entry.push_str("Actions=new-window;new-private-window;open-in-browser;\n");
         desktop_entry.push_str("\n[Desktop Action new-window]\n");
         desktop_entry.push_str("Name=New Window\n");
-        desktop_entry.push_str(&format!("Exec={safe_exec}\n"));
+        desktop_entry.push_str(&format!("Exec={exec}\n"));
         desktop_entry.push_str("\n[Desktop Action new-private-window]\n");
         desktop_entry.push_str("Name=New Private Window\n");
-        desktop_entry.push_str(&format!("Exec={safe_exec} --private\n"));
+        desktop_entry.push_str(&format!("Exec={private_exec}\n"));
         desktop_entry.push_str("\n[Desktop Action open-in-browser]\n");
         desktop_entry.push_str("Name=Open in Browser\n");
-        if let Some(ref url) = self.browser.url {
-            let safe_url = sanitize_desktop_field(url);
-            desktop_entry.push_str(&format!("Exec=xdg-open {safe_url}\n"));
-        } else {
-            desktop_entry.push_str("Exec=xdg-open about:blank\n");
-        }
+        desktop_entry.push_str(&format!("Exec={browser_exec}\n"));
+
+        desktop_entry
+    }
+
+    pub async fn create(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let desktop_entry = self.desktop_entry();
 
         let proxy = DynamicLauncherProxy::new().await?;
 
-        let buffer = std::fs::read(&self.icon)?;
+        let buffer = crate::icon_pipeline::png_bytes(
+            &self.icon,
+            crate::icon_pipeline::INSTALL_SIZE,
+            self.icon_overlay.as_ref(),
+        )
+        .map_err(|e| e.to_string())?;
 
         let icon = Icon::Bytes(buffer);
         let response = proxy
@@ -165,6 +217,14 @@ impl WebAppLauncher {
 
         self.browser.delete();
 
+        // a keyring that is not running should not keep the app installed
+        let app_id = self.browser.app_id.id.clone();
+        if let Ok(Err(e)) =
+            tokio::task::spawn_blocking(move || crate::credentials::forget(&app_id)).await
+        {
+            tracing::warn!("Failed to remove saved passwords: {e}");
+        }
+
         Ok(())
     }
 }
@@ -179,12 +239,12 @@ pub fn export_all(path: &std::path::Path) -> Result<(), Box<dyn std::error::Erro
 }
 
 /// Maximum size for an import file (1 MB).
-const MAX_IMPORT_FILE_SIZE: u64 = 1024 * 1024;
+pub(crate) const MAX_IMPORT_FILE_SIZE: u64 = 1024 * 1024;
 /// Maximum number of apps allowed in a single import.
-const MAX_IMPORT_APPS: usize = 500;
+pub(crate) const MAX_IMPORT_APPS: usize = 500;
 
 /// Validate and sanitize an imported web app. Returns None if the app is invalid.
-fn validate_imported_app(mut app: WebAppLauncher) -> Option<WebAppLauncher> {
+pub(crate) fn validate_imported_app(mut app: WebAppLauncher) -> Option<WebAppLauncher> {
     // Sanitize app_id to prevent path traversal
     let safe_id = crate::browser::sanitize_app_id(&app.browser.app_id.id);
     if safe_id.is_empty() {
@@ -194,6 +254,7 @@ fn validate_imported_app(mut app: WebAppLauncher) -> Option<WebAppLauncher> {
     app.browser.app_id = crate::WebviewArgs {
         id: safe_id,
         private: false,
+        kiosk: false,
     };
 
     // Validate URL is http/https
@@ -234,29 +295,12 @@ fn validate_imported_app(mut app: WebAppLauncher) -> Option<WebAppLauncher> {
         return None;
     }
 
-    // Validate profile path is within expected directory (if set)
-    if let Some(ref profile) = app.browser.profile {
-        // Reject paths containing traversal components
-        let has_traversal = profile
-            .components()
-            .any(|c| matches!(c, std::path::Component::ParentDir));
-        if has_traversal {
-            tracing::warn!(
-                "Rejecting imported app '{}': profile path contains '..'",
-                app.name
-            );
-            app.browser.profile = None;
-        } else if let Some(xdg_data) = dirs::data_dir() {
-            let expected_prefix = xdg_data.join(crate::APP_ID).join("profiles");
-            if !profile.starts_with(&expected_prefix) {
-                tracing::warn!(
-                    "Rejecting imported app '{}': profile path outside expected directory",
-                    app.name
-                );
-                app.browser.profile = None;
-            }
-        }
-    }
+    // Profile IDs name a directory, keep them free of path separators
+    app.browser.profile = app
+        .browser
+        .profile
+        .map(|profile| crate::browser::sanitize_app_id(&profile))
+        .filter(|profile| !profile.is_empty());
 
     Some(app)
 }
@@ -320,3 +364,137 @@ pub fn save_imported(apps: &[WebAppLauncher]) -> (usize, usize) {
 
     (saved, total)
 }
+
+/// What to do with an imported app whose ID is already taken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConflictStrategy {
+    /// Keep the installed app and drop the imported one.
+    #[default]
+    Skip,
+    /// Replace the installed app with the imported one.
+    Overwrite,
+    /// Import the app under a newly generated ID.
+    Rename,
+}
+
+/// An imported app and whether its ID is already taken.
+#[derive(Debug, Clone)]
+pub struct PlannedImport {
+    pub app: WebAppLauncher,
+    pub conflict: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportOutcome {
+    Installed,
+    Overwritten,
+    /// Installed under the contained new app ID.
+    Renamed(String),
+    Skipped,
+    Failed(String),
+}
+
+/// Result of importing a single app.
+#[derive(Debug, Clone)]
+pub struct ImportResult {
+    pub name: String,
+    pub app_id: String,
+    pub outcome: ImportOutcome,
+}
+
+/// Mark validated apps whose ID is already installed, or used by an earlier app
+/// of the same import.
+pub fn plan_import(apps: Vec<WebAppLauncher>) -> Vec<PlannedImport> {
+    let mut taken: std::collections::HashSet<String> = installed_webapps()
+        .into_iter()
+        .map(|app| app.browser.app_id.id)
+        .collect();
+
+    apps.into_iter()
+        .map(|app| {
+            let conflict = !taken.insert(app.browser.app_id.id.clone());
+            PlannedImport { app, conflict }
+        })
+        .collect()
+}
+
+/// Install the desktop entry of each planned app and save it to the database,
+/// resolving ID conflicts with `strategy`. Icons staged by [`crate::migrate`]
+/// or inside an unpacked [`crate::bundle::Bundle`] are stored under the final
+/// app ID.
+pub async fn install_imported(
+    plan: Vec<PlannedImport>,
+    strategy: ConflictStrategy,
+    install_desktop_entries: bool,
+) -> Vec<ImportResult> {
+    let mut taken: std::collections::HashSet<String> = installed_webapps()
+        .into_iter()
+        .map(|app| app.browser.app_id.id)
+        .collect();
+    taken.extend(plan.iter().map(|p| p.app.browser.app_id.id.clone()));
+
+    let mut results = Vec::with_capacity(plan.len());
+
+    for PlannedImport { mut app, conflict } in plan {
+        let name = app.name.clone();
+        let app_id = app.browser.app_id.id.clone();
+
+        let success = match (conflict, strategy) {
+            (false, _) => ImportOutcome::Installed,
+            (true, ConflictStrategy::Skip) => {
+                results.push(ImportResult {
+                    name,
+                    app_id,
+                    outcome: ImportOutcome::Skipped,
+                });
+                continue;
+            }
+            (true, ConflictStrategy::Overwrite) => ImportOutcome::Overwritten,
+            (true, ConflictStrategy::Rename) => {
+                let mut new_id = crate::browser::generate_app_id(&app.name);
+                while !taken.insert(new_id.clone()) {
+                    new_id = crate::browser::generate_app_id(&app.name);
+                }
+                if app.browser.profile.as_deref() == Some(app_id.as_str()) {
+                    app.browser.profile = Some(new_id.clone());
+                }
+                app.browser.app_id.id = new_id.clone();
+                ImportOutcome::Renamed(new_id)
+            }
+        };
+
+        // icons of discovered apps are named after the final ID
+        let staged = crate::migrate::staged_icon(&app.icon);
+        let bundled = crate::bundle::staged_icon(&app.icon);
+        let stored = match staged.as_ref().or(bundled.as_ref()) {
+            Some(icon) => crate::move_icon(&icon.to_string_lossy(), &app.browser.app_id.id)
+                .map(|path| app.icon = path.to_string_lossy().to_string())
+                .ok_or_else(|| "failed to store the icon".to_string()),
+            None => Ok(()),
+        };
+
+        let installed = match stored {
+            Ok(()) if install_desktop_entries => app.create().await.map_err(|e| e.to_string()),
+            stored => stored,
+        };
+
+        let outcome = match installed.and_then(|()| app.save().map_err(|e| e.to_string())) {
+            Ok(()) => success,
+            Err(e) => {
+                tracing::error!("Failed to import '{}': {e}", app.name);
+                ImportOutcome::Failed(e)
+            }
+        };
+        if let Some(icon) = staged {
+            let _ = std::fs::remove_file(icon);
+        }
+
+        results.push(ImportResult {
+            name,
+            app_id,
+            outcome,
+        });
+    }
+
+    results
+}
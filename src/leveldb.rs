@@ -0,0 +1,396 @@
+//! Minimal read-only LevelDB reader.
+//!
+//! Just enough to read the web app database of Chromium based browsers, see
+//! [`crate::migrate`]. The log and table files of a database are read without
+//! its manifest, and the newest value of every key wins. Only Snappy block
+//! compression is supported. The checksums of log records and table blocks are
+//! verified, a mismatch skips the rest of the file.
+
+use std::{collections::HashMap, error::Error, fs, path::Path};
+
+/// Maximum size of a single log or table file (64 MB).
+const MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Maximum number of files read from one database.
+const MAX_FILES: usize = 512;
+
+/// Size of the blocks of a log file.
+const LOG_BLOCK_SIZE: usize = 32 * 1024;
+
+/// Size of the header of a log record: checksum, length and type.
+const LOG_HEADER_SIZE: usize = 7;
+
+/// Size of the footer of a table file.
+const TABLE_FOOTER_SIZE: usize = 48;
+
+/// Magic number at the end of a table file.
+const TABLE_MAGIC: u64 = 0xdb47_7524_8b80_fb57;
+
+/// Added to rotated checksums, so the checksum of data containing checksums
+/// is not trivially zero.
+const CRC_MASK_DELTA: u32 = 0xa282_ead8;
+
+/// Lookup table of the CRC-32C (Castagnoli) polynomial.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0x82f6_3b78
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Sequence number and value of a key, `None` once the key is deleted.
+type Entry = (u64, Option<Vec<u8>>);
+
+/// Key and value of an entry of a table block.
+type BlockEntry = (Vec<u8>, Vec<u8>);
+
+/// Read the values of all keys starting with `prefix` in the database `dir`.
+///
+/// Files that cannot be read, for example because the browser compacted the
+/// database meanwhile, are skipped with a warning.
+pub fn read(dir: &Path, prefix: &[u8]) -> Result<HashMap<Vec<u8>, Vec<u8>>, Box<dyn Error>> {
+    let files = fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("log" | "ldb" | "sst")
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if files.len() > MAX_FILES {
+        return Err(format!("{dir:?} has too many files ({})", files.len()).into());
+    }
+
+    let mut entries: HashMap<Vec<u8>, Entry> = HashMap::new();
+    let mut insert = |key: &[u8], sequence: u64, value: Option<&[u8]>| {
+        if !key.starts_with(prefix) {
+            return;
+        }
+        if entries
+            .get(key)
+            .is_some_and(|(newest, _)| *newest >= sequence)
+        {
+            return;
+        }
+        entries.insert(key.to_vec(), (sequence, value.map(<[u8]>::to_vec)));
+    };
+
+    for path in files {
+        let read = read_file(&path).and_then(|data| {
+            if path.extension().is_some_and(|ext| ext == "log") {
+                read_log(&data, &mut insert)
+            } else {
+                read_table(&data, &mut insert)
+            }
+        });
+
+        if let Err(e) = read {
+            tracing::warn!("Skipping LevelDB file {path:?}: {e}");
+        }
+    }
+
+    Ok(entries
+        .into_iter()
+        .filter_map(|(key, (_, value))| Some((key, value?)))
+        .collect())
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, Box<dyn Error>> {
+    let size = fs::metadata(path)?.len();
+    if size > MAX_FILE_SIZE {
+        return Err(format!("file is too large ({size} bytes)").into());
+    }
+
+    Ok(fs::read(path)?)
+}
+
+/// Read the write batches of a log file. A record cut off at the end of the
+/// file, left by a write in progress, ends the log.
+fn read_log(
+    data: &[u8],
+    insert: &mut impl FnMut(&[u8], u64, Option<&[u8]>),
+) -> Result<(), Box<dyn Error>> {
+    let mut record = Vec::new();
+    let mut pos = 0;
+
+    while pos + LOG_HEADER_SIZE <= data.len() {
+        let block_left = LOG_BLOCK_SIZE - pos % LOG_BLOCK_SIZE;
+        if block_left < LOG_HEADER_SIZE {
+            pos += block_left;
+            continue;
+        }
+
+        let checksum = u32::from_le_bytes(data[pos..pos + 4].try_into()?);
+        let len = u16::from_le_bytes([data[pos + 4], data[pos + 5]]) as usize;
+        let kind = data[pos + 6];
+        let start = pos + LOG_HEADER_SIZE;
+        let Some(fragment) = data.get(start..start + len) else {
+            break;
+        };
+        pos = start + len;
+
+        // Zero padding of a preallocated file.
+        if kind == 0 && len == 0 {
+            continue;
+        }
+        if unmask_crc(checksum) != crc32c(&[&[kind], fragment]) {
+            return Err("log record checksum mismatch".into());
+        }
+
+        match kind {
+            // Full record.
+            1 => read_batch(fragment, insert)?,
+            // First fragment.
+            2 => record = fragment.to_vec(),
+            // Middle fragment.
+            3 => record.extend_from_slice(fragment),
+            // Last fragment.
+            4 => {
+                record.extend_from_slice(fragment);
+                read_batch(&record, insert)?;
+                record.clear();
+            }
+            _ => return Err(format!("unknown log record type {kind}").into()),
+        }
+    }
+
+    Ok(())
+}
+
+fn read_batch(
+    mut batch: &[u8],
+    insert: &mut impl FnMut(&[u8], u64, Option<&[u8]>),
+) -> Result<(), Box<dyn Error>> {
+    let sequence = u64::from_le_bytes(take(&mut batch, 8)?.try_into()?);
+    let count = u32::from_le_bytes(take(&mut batch, 4)?.try_into()?);
+
+    for i in 0..u64::from(count) {
+        let tag = take(&mut batch, 1)?[0];
+        let key = take_slice(&mut batch)?;
+        match tag {
+            0 => insert(key, sequence + i, None),
+            1 => insert(key, sequence + i, Some(take_slice(&mut batch)?)),
+            _ => return Err(format!("unknown write batch tag {tag}").into()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the entries of all data blocks of a table file.
+fn read_table(
+    data: &[u8],
+    insert: &mut impl FnMut(&[u8], u64, Option<&[u8]>),
+) -> Result<(), Box<dyn Error>> {
+    let mut footer = data
+        .len()
+        .checked_sub(TABLE_FOOTER_SIZE)
+        .map(|at| &data[at..])
+        .ok_or("table is truncated")?;
+
+    if u64::from_le_bytes(footer[TABLE_FOOTER_SIZE - 8..].try_into()?) != TABLE_MAGIC {
+        return Err("not a table".into());
+    }
+
+    let _metaindex = take_handle(&mut footer)?;
+    let index = read_block(data, take_handle(&mut footer)?)?;
+
+    for (_, handle) in block_entries(&index)? {
+        let block = read_block(data, take_handle(&mut handle.as_slice())?)?;
+
+        for (key, value) in block_entries(&block)? {
+            let at = key.len().checked_sub(8).ok_or("table key is truncated")?;
+            let (user_key, trailer) = key.split_at(at);
+            let trailer = u64::from_le_bytes(trailer.try_into()?);
+            // The low byte of the trailer is the type of the entry, 1 for a
+            // value and 0 for a deletion, the rest its sequence number.
+            insert(
+                user_key,
+                trailer >> 8,
+                (trailer & 0xff == 1).then_some(value.as_slice()),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the block at `(offset, size)`, followed by its compression type and
+/// checksum.
+fn read_block(data: &[u8], (offset, size): (usize, usize)) -> Result<Vec<u8>, Box<dyn Error>> {
+    let block = offset
+        .checked_add(size)
+        .and_then(|end| data.get(offset..end.checked_add(5)?))
+        .ok_or("block is out of bounds")?;
+
+    let checksum = u32::from_le_bytes(block[size + 1..].try_into()?);
+    if unmask_crc(checksum) != crc32c(&[&block[..=size]]) {
+        return Err("block checksum mismatch".into());
+    }
+
+    match block[size] {
+        0 => Ok(block[..size].to_vec()),
+        1 => {
+            let len = snap::raw::decompress_len(&block[..size])?;
+            if len as u64 > MAX_FILE_SIZE {
+                return Err(format!("block is too large ({len} bytes)").into());
+            }
+            Ok(snap::raw::Decoder::new().decompress_vec(&block[..size])?)
+        }
+        kind => Err(format!("unsupported block compression {kind}").into()),
+    }
+}
+
+/// Keys and values of a block. Keys share a prefix with the key before them.
+fn block_entries(block: &[u8]) -> Result<Vec<BlockEntry>, Box<dyn Error>> {
+    let restarts_at = block.len().checked_sub(4).ok_or("block is truncated")?;
+    let restarts = u32::from_le_bytes(block[restarts_at..].try_into()?) as usize;
+    let end = restarts
+        .checked_mul(4)
+        .and_then(|len| restarts_at.checked_sub(len))
+        .ok_or("block is truncated")?;
+
+    let mut data = &block[..end];
+    let mut entries: Vec<BlockEntry> = Vec::new();
+
+    while !data.is_empty() {
+        let shared = take_varint(&mut data)? as usize;
+        let unshared = take_varint(&mut data)? as usize;
+        let value_len = take_varint(&mut data)? as usize;
+
+        let mut key = match entries.last() {
+            Some((last, _)) => last.get(..shared).ok_or("block key is invalid")?.to_vec(),
+            None if shared == 0 => Vec::new(),
+            None => return Err("block key is invalid".into()),
+        };
+        key.extend_from_slice(take(&mut data, unshared)?);
+        let value = take(&mut data, value_len)?.to_vec();
+
+        entries.push((key, value));
+    }
+
+    Ok(entries)
+}
+
+/// CRC-32C of the concatenated `parts`.
+fn crc32c(parts: &[&[u8]]) -> u32 {
+    let mut crc = !0u32;
+    for byte in parts.iter().copied().flatten() {
+        crc = CRC_TABLE[((crc ^ u32::from(*byte)) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// Undo the rotation LevelDB applies to stored checksums.
+fn unmask_crc(masked: u32) -> u32 {
+    masked.wrapping_sub(CRC_MASK_DELTA).rotate_left(15)
+}
+
+fn take_handle(data: &mut &[u8]) -> Result<(usize, usize), Box<dyn Error>> {
+    let offset = take_varint(data)?;
+    let size = take_varint(data)?;
+    Ok((usize::try_from(offset)?, usize::try_from(size)?))
+}
+
+fn take_slice<'a>(data: &mut &'a [u8]) -> Result<&'a [u8], Box<dyn Error>> {
+    let len = take_varint(data)?;
+    take(data, usize::try_from(len)?)
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], Box<dyn Error>> {
+    if data.len() < len {
+        return Err("unexpected end of data".into());
+    }
+
+    let (taken, rest) = data.split_at(len);
+    *data = rest;
+    Ok(taken)
+}
+
+/// Take a base 128 varint, as used by LevelDB and protocol buffers.
+pub(crate) fn take_varint(data: &mut &[u8]) -> Result<u64, Box<dyn Error>> {
+    let mut value = 0u64;
+
+    for shift in (0..64).step_by(7) {
+        let byte = take(data, 1)?[0];
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err("varint is too long".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Databases written by `tests/fixtures/leveldb/generate.py`.
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/leveldb")
+            .join(name)
+    }
+
+    #[test]
+    fn reads_logs_and_tables() {
+        let entries = read(&fixture("db"), b"app:").expect("readable database");
+
+        // The log overrides a table value and deletes another one.
+        assert_eq!(entries[b"app:a".as_slice()], b"log-a");
+        assert!(!entries.contains_key(b"app:b".as_slice()));
+        // Snappy compressed table block.
+        assert_eq!(entries[b"app:c".as_slice()], b"snappy! ".repeat(8));
+        // Record split into a first, middle and last fragment.
+        let big = (0..70000).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+        assert_eq!(entries[b"app:big".as_slice()], big);
+        // Record cut off at the end of the log, and a key without the prefix.
+        assert!(!entries.contains_key(b"app:cut".as_slice()));
+        assert!(!entries.contains_key(b"other:x".as_slice()));
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn stops_at_checksum_mismatch() {
+        let entries = read(&fixture("corrupt"), b"app:").expect("readable database");
+
+        assert_eq!(entries[b"app:good".as_slice()], b"good");
+        assert!(!entries.contains_key(b"app:bad".as_slice()));
+        assert!(!entries.contains_key(b"app:table".as_slice()));
+    }
+
+    #[test]
+    fn rejects_truncated_tables() {
+        let data = fs::read(fixture("db").join("000005.ldb")).expect("fixture");
+        let mut insert = |_: &[u8], _: u64, _: Option<&[u8]>| {};
+
+        assert!(read_table(&data, &mut insert).is_ok());
+        assert!(read_table(&data[..data.len() - 1], &mut insert).is_err());
+        assert!(read_table(&data[..TABLE_FOOTER_SIZE - 1], &mut insert).is_err());
+    }
+
+    #[test]
+    fn crc32c_matches_known_values() {
+        assert_eq!(crc32c(&[b"123456789"]), 0xe306_9283);
+        assert_eq!(crc32c(&[b"1234", b"56789"]), 0xe306_9283);
+        assert_eq!(unmask_crc(0xa282_ead8), 0);
+    }
+}
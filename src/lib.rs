@@ -6,19 +6,34 @@ use std::{
     fmt::Display,
     fs::{self, create_dir_all},
     io::{Cursor, Read},
-    os::unix::fs::PermissionsExt as _,
     path::PathBuf,
 };
-use tokio::{fs::File, io::AsyncWriteExt as _, process::Child};
 
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 use url::Url;
-use walkdir::WalkDir;
 
+pub mod backup;
 pub mod browser;
+pub mod bundle;
+pub mod credentials;
+pub mod desktop_entry;
+pub mod icon_pack;
+pub mod icon_pipeline;
+pub mod icon_theme;
+pub mod inspector;
+pub mod instance;
+pub mod kiosk;
 pub mod launcher;
+pub mod leveldb;
 pub mod localize;
+pub mod manifest;
+pub mod migrate;
+pub mod monogram;
+pub mod profile;
+pub mod proxy;
+pub mod site_data;
+pub mod tls;
 
 pub const DEFAULT_WINDOW_WIDTH: WindowWidth = 800.0;
 pub const DEFAULT_WINDOW_HEIGHT: WindowHeight = 600.0;
@@ -117,7 +132,7 @@ fn is_valid_image_bytes(data: &[u8]) -> bool {
 
 /// Sanitize a domain string for safe use in filenames.
 /// Only allows alphanumeric, dots, and hyphens.
-fn sanitize_domain_for_filename(domain: &str) -> String {
+pub(crate) fn sanitize_domain_for_filename(domain: &str) -> String {
     domain
         .chars()
         .filter(|c| c.is_alphanumeric() || *c == '.' || *c == '-')
@@ -136,7 +151,7 @@ fn html_decode_basic(s: &str) -> String {
 }
 
 /// Extract content from a meta tag with given property.
-fn extract_meta_content(html: &str, property: &str) -> Option<String> {
+pub(crate) fn extract_meta_content(html: &str, property: &str) -> Option<String> {
     // Search for <meta property="og:title" content="...">
     // or <meta name="description" content="...">
     let patterns = [
@@ -162,9 +177,8 @@ fn extract_meta_content(html: &str, property: &str) -> Option<String> {
     None
 }
 
-/// Fetch basic site metadata (title) from a URL.
-/// Returns the page title if found.
-pub async fn fetch_site_title(url_str: &str) -> Option<String> {
+/// Fetch the first 64KB of a page as text.
+pub(crate) async fn fetch_page(url_str: &str) -> Option<String> {
     if !url_valid(url_str) {
         return None;
     }
@@ -185,9 +199,16 @@ pub async fn fetch_site_title(url_str: &str) -> Option<String> {
     }
 
     // Limit to first 64KB to avoid processing huge pages
-    let html = String::from_utf8_lossy(
-        &response.stdout[..response.stdout.len().min(64 * 1024)]
-    );
+    Some(
+        String::from_utf8_lossy(&response.stdout[..response.stdout.len().min(64 * 1024)])
+            .into_owned(),
+    )
+}
+
+/// Fetch basic site metadata (title) from a URL.
+/// Returns the page title if found.
+pub async fn fetch_site_title(url_str: &str) -> Option<String> {
+    let html = fetch_page(url_str).await?;
 
     // Try og:title first
     if let Some(title) = extract_meta_content(&html, "og:title") {
@@ -257,19 +278,28 @@ pub async fn download_favicon(url_str: &str) -> Option<String> {
         return None;
     }
 
-    let icons_dir = icons_location()?;
-    if let Err(e) = tokio::fs::create_dir_all(&icons_dir).await {
-        tracing::error!("Failed to create icons directory: {e}");
-        return None;
-    }
+    let name = format!("favicon-{}", sanitize_domain_for_filename(domain));
+    let favicon_path =
+        tokio::task::spawn_blocking(move || icon_pipeline::normalize(&response.stdout, &name))
+            .await
+            .ok()?;
 
-    let safe_domain = sanitize_domain_for_filename(domain);
-    let favicon_path = icons_dir.join(format!("favicon-{safe_domain}.png"));
-    tokio::fs::write(&favicon_path, &response.stdout)
-        .await
-        .ok()?;
+    match favicon_path {
+        Ok(path) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            tracing::warn!("Rejecting favicon of {domain}: {e}");
+            None
+        }
+    }
+}
 
-    Some(favicon_path.to_string_lossy().to_string())
+/// Download the site favicon, or generate a monogram icon from `name` if the
+/// site has no usable favicon. Returns the path to the icon on success.
+pub async fn site_icon(url_str: &str, name: &str) -> Option<String> {
+    match download_favicon(url_str).await {
+        Some(icon) => Some(icon),
+        None => monogram::generate(name, url_str).await,
+    }
 }
 
 /// Get the path for a cached thumbnail file.
@@ -356,40 +386,18 @@ pub fn icons_location() -> Option<PathBuf> {
     None
 }
 
-/// Copy an icon file to the app icons directory.
+/// Normalize an icon file into the app icons directory and return the path of
+/// the icon to store in the web app record, see [`icon_pipeline::normalize`].
 /// This performs synchronous file I/O. If called from an async context,
 /// wrap in `tokio::task::spawn_blocking`.
-pub fn move_icon(path: &str, icon_name: &str, extension: &str) -> Option<PathBuf> {
-    if icon_name.contains('/')
-        || icon_name.contains('\\')
-        || icon_name.contains("..")
-        || extension.contains('/')
-        || extension.contains('\\')
-        || extension.contains("..")
-    {
-        tracing::warn!("Invalid icon name or extension: {icon_name}.{extension}");
-        return None;
-    }
-
-    if let Some(icons_dir) = icons_location() {
-        if !icons_dir.exists() {
-            if let Err(e) = create_dir_all(&icons_dir) {
-                tracing::error!("Failed to create icons directory: {e}");
-                return None;
-            }
+pub fn move_icon(path: &str, icon_name: &str) -> Option<PathBuf> {
+    match icon_pipeline::normalize_file(std::path::Path::new(path), icon_name) {
+        Ok(icon) => Some(icon),
+        Err(e) => {
+            tracing::error!("Failed to import icon {path}: {e}");
+            None
         }
-
-        let dest_path = icons_dir.join(format!("{}.{}", icon_name, extension));
-
-        if let Err(e) = fs::copy(path, &dest_path) {
-            tracing::error!("Failed to copy icon: {e}");
-            return None;
-        }
-
-        return Some(dest_path);
     }
-
-    None
 }
 
 pub fn icon_pack_installed() -> bool {
@@ -406,93 +414,19 @@ pub fn icon_pack_installed() -> bool {
     packs.iter().any(|theme| icons_dir.join(theme).exists())
 }
 
-pub async fn add_icon_packs_install_script()
--> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let install_script = include_bytes!("../resources/scripts/icon-installer.sh");
-    let temp_file = format!("/tmp/{}.sh", APP_ID);
-
-    let mut file = File::create(&temp_file).await?;
-    file.write_all(install_script).await?;
-
-    let mut perms = file.metadata().await?.permissions();
-    perms.set_mode(0o755);
-    file.set_permissions(perms).await?;
-
-    Ok(temp_file)
-}
-
-pub async fn execute_script(script: String) -> Result<Child, std::io::Error> {
-    tokio::process::Command::new(script)
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-}
-
-/// Maximum directory depth when searching for icons.
-const MAX_ICON_SEARCH_DEPTH: usize = 8;
 /// Maximum number of icon results to return.
 const MAX_ICON_RESULTS: usize = 200;
 
-pub async fn find_icon(path: PathBuf, icon_name: String) -> Vec<String> {
+/// Search the active icon theme, its parents and the downloaded icon packs for
+/// icons matching `icon_name`, best matches first.
+pub async fn find_icons(icon_name: String) -> Vec<icon_theme::IconMatch> {
     tokio::task::spawn_blocking(move || {
-        let mut icons: Vec<String> = Vec::new();
-
-        for entry in WalkDir::new(&path)
-            .max_depth(MAX_ICON_SEARCH_DEPTH)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            if icons.len() >= MAX_ICON_RESULTS {
-                break;
-            }
-
-            if let Some(filename) = entry.file_name().to_str() {
-                if filename.contains(&icon_name) {
-                    if is_svg(filename) {
-                        if let Some(path) = entry.path().to_str() {
-                            if let Ok(buffer) = std::fs::read_to_string(path) {
-                                let options = usvg::Options::default();
-                                if let Ok(parsed) = usvg::Tree::from_str(&buffer, &options) {
-                                    let size = parsed.size();
-                                    if size.width() >= ICON_SIZE as f32
-                                        && size.height() >= ICON_SIZE as f32
-                                        && !icons.contains(&path.to_string())
-                                    {
-                                        icons.push(path.to_string())
-                                    }
-                                }
-                            }
-                        }
-                    } else if let Some(path) = entry.path().to_str() {
-                        if let Ok(image) = ImageReader::open(path) {
-                            if let Ok(img) = image.decode() {
-                                if img.width() >= ICON_SIZE
-                                    && img.height() >= ICON_SIZE
-                                    && !icons.contains(&path.to_string())
-                                {
-                                    icons.push(path.to_string())
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        icons
+        icon_theme::search(&icon_name, ICON_SIZE, MAX_ICON_RESULTS)
     })
     .await
     .unwrap_or_default()
 }
 
-pub async fn find_icons(icon_name: String) -> Vec<String> {
-    if let Some(path) = icons_location() {
-        find_icon(path, icon_name).await
-    } else {
-        Vec::new()
-    }
-}
-
 pub async fn image_handle(path: String) -> Option<Icon> {
     let result_path = PathBuf::from(&path);
 
@@ -638,6 +572,8 @@ pub enum IconType {
 pub struct Icon {
     pub icon: IconType,
     pub path: String,
+    /// Themed icon name, stored instead of `path` when the icon is picked.
+    pub name: Option<String>,
     pub is_favicon: bool,
 }
 
@@ -646,9 +582,16 @@ impl Icon {
         Self {
             icon,
             path,
+            name: None,
             is_favicon,
         }
     }
+
+    /// Name of the icon in the icon theme, see [`icon_theme::IconMatch`].
+    pub fn with_name(mut self, name: Option<String>) -> Self {
+        self.name = name;
+        self
+    }
 }
 
 pub type WindowWidth = f64;
@@ -678,6 +621,10 @@ pub struct WebviewArgs {
     #[arg(long, default_value_t = false)]
     #[serde(default)]
     pub private: bool,
+    /// Show the app as a kiosk, see [`kiosk`].
+    #[arg(long, default_value_t = false)]
+    #[serde(default)]
+    pub kiosk: bool,
 }
 
 impl AsRef<str> for WebviewArgs {
@@ -695,28 +642,13 @@ impl IntoIterator for WebviewArgs {
         if self.private {
             args.push("--private".to_string());
         }
+        if self.kiosk {
+            args.push("--kiosk".to_string());
+        }
         args.into_iter()
     }
 }
 
-/// Calculate the total size of a web app's profile directory.
-/// Returns the size in bytes, or 0 if the profile doesn't exist.
-pub fn profile_size(app_id: &str) -> u64 {
-    let Some(path) = profiles_path(app_id) else {
-        return 0;
-    };
-    if !path.exists() {
-        return 0;
-    }
-    WalkDir::new(&path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter_map(|e| e.metadata().ok())
-        .filter(|m| m.is_file())
-        .map(|m| m.len())
-        .sum()
-}
-
 /// Format bytes as a human-readable string.
 pub fn format_bytes(bytes: u64) -> String {
     if bytes < 1024 {
@@ -731,6 +663,7 @@ pub fn format_bytes(bytes: u64) -> String {
 }
 
 /// Scan `/proc/*/cmdline` to find running webview processes and extract their app_id arguments.
+/// Apps opened in the webview process of a shared profile are added from [`instance`].
 /// Returns a set of app_id strings for currently running webview instances.
 pub fn running_webview_app_ids() -> HashSet<String> {
     let mut ids = HashSet::new();
@@ -760,6 +693,7 @@ pub fn running_webview_app_ids() -> HashSet<String> {
         }
     }
 
+    ids.extend(instance::running_app_ids());
     ids
 }
 
@@ -784,16 +718,21 @@ pub fn format_timestamp(ts: u64) -> String {
     }
 }
 
-/// Clear all website data (cookies, cache, storage) for a web app by removing its profile directory.
-/// Returns Ok(()) on success, or an error if removal failed.
-pub fn clear_profile_data(app_id: &str) -> Result<(), std::io::Error> {
-    let Some(path) = profiles_path(app_id) else {
-        return Ok(());
-    };
-    if path.exists() {
-        std::fs::remove_dir_all(&path)?;
-        // Recreate empty profile dir so the app can still use persistent profile
-        std::fs::create_dir_all(&path)?;
+/// Format a Unix timestamp in the future as the time left until it.
+pub fn format_time_left(ts: u64) -> String {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    let dt = UNIX_EPOCH + Duration::from_secs(ts);
+    match dt.duration_since(SystemTime::now()) {
+        Ok(left) => {
+            let secs = left.as_secs();
+            if secs < 3600 {
+                format!("in {}m", secs / 60)
+            } else if secs < 86400 {
+                format!("in {}h", secs / 3600)
+            } else {
+                format!("in {}d", secs / 86400)
+            }
+        }
+        Err(_) => "expired".to_string(),
     }
-    Ok(())
 }
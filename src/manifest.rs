@@ -0,0 +1,327 @@
+//! Declarative web app manifests.
+//!
+//! A manifest is a RON file listing every web app that should exist. [`sync`]
+//! creates missing apps, updates changed ones and optionally removes apps that are
+//! no longer listed, so running it twice in a row is a no-op.
+//!
+//! ```ron
+//! (
+//!     apps: [
+//!         (
+//!             id: "Mail",
+//!             name: "Mail",
+//!             url: "https://mail.example.com",
+//!             icon: Path("icons/mail.png"),
+//!             category: Network,
+//!             persistent: true,
+//!             browser: (
+//!                 zoom_level: 1.25,
+//!                 window_decorations: false,
+//!             ),
+//!         ),
+//!     ],
+//! )
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    Category,
+    browser::{Browser, sanitize_app_id},
+    icon_pipeline::{self, IconOverlay},
+    launcher::{
+        MAX_IMPORT_APPS, MAX_IMPORT_FILE_SIZE, WebAppLauncher, installed_webapps, save_imported,
+        validate_imported_app,
+    },
+};
+
+/// Where the icon of a manifest app comes from.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub enum IconSource {
+    /// An image file, relative paths are resolved against the manifest directory.
+    Path(PathBuf),
+    /// The favicon of the app URL, downloaded once.
+    Favicon,
+}
+
+/// A single web app in a manifest.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ManifestApp {
+    /// Stable app ID used to match the app across syncs.
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub icon: IconSource,
+    pub category: Category,
+    /// Keep browser data in a persistent profile.
+    #[serde(default)]
+    pub persistent: bool,
+    /// ID of a profile shared with other apps, takes precedence over
+    /// `persistent`.
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Browser options. `app_id`, `url`, `profile` and usage statistics are
+    /// managed by the sync and ignored here.
+    #[serde(default)]
+    pub browser: Option<Browser>,
+    /// Badge composited onto the installed icon.
+    #[serde(default)]
+    pub icon_overlay: Option<IconOverlay>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Manifest {
+    pub apps: Vec<ManifestApp>,
+}
+
+impl Manifest {
+    /// Read a manifest from a RON file. `Some(..)` may be omitted around optional values.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let metadata = std::fs::metadata(path)?;
+        if metadata.len() > MAX_IMPORT_FILE_SIZE {
+            return Err(format!(
+                "Manifest too large: {} bytes (max {} bytes)",
+                metadata.len(),
+                MAX_IMPORT_FILE_SIZE
+            )
+            .into());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let manifest: Manifest = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_str(&content)?;
+
+        if manifest.apps.len() > MAX_IMPORT_APPS {
+            return Err(format!(
+                "Manifest contains too many apps: {} (max {})",
+                manifest.apps.len(),
+                MAX_IMPORT_APPS
+            )
+            .into());
+        }
+
+        Ok(manifest)
+    }
+}
+
+/// Options for [`sync`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyncOptions {
+    /// Remove installed apps that are not listed in the manifest.
+    pub prune: bool,
+    /// Only report what would change.
+    pub dry_run: bool,
+    /// Skip installing and removing desktop entries through the portal.
+    pub skip_desktop_entries: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncStatus {
+    Created,
+    Updated,
+    Unchanged,
+    Removed,
+    Failed(String),
+}
+
+/// Outcome of a sync, one entry per app ID.
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    pub entries: Vec<(String, SyncStatus)>,
+}
+
+impl SyncReport {
+    pub fn has_failures(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|(_, status)| matches!(status, SyncStatus::Failed(_)))
+    }
+
+    fn push(&mut self, id: &str, status: SyncStatus) {
+        self.entries.push((id.to_string(), status));
+    }
+}
+
+/// Bring the installed web apps in line with `manifest`.
+///
+/// `base_dir` is used to resolve relative icon paths, usually the directory of
+/// the manifest file.
+pub async fn sync(manifest: &Manifest, base_dir: &Path, options: SyncOptions) -> SyncReport {
+    let mut report = SyncReport::default();
+    let mut listed: Vec<String> = Vec::new();
+
+    for app in &manifest.apps {
+        let id = sanitize_app_id(&app.id);
+
+        if id.is_empty() || listed.contains(&id) {
+            report.push(&app.id, SyncStatus::Failed("empty or duplicate id".into()));
+            continue;
+        }
+        listed.push(id.clone());
+
+        let status = match sync_app(app, &id, base_dir, options).await {
+            Ok(status) => status,
+            Err(e) => SyncStatus::Failed(e.to_string()),
+        };
+        report.push(&id, status);
+    }
+
+    if options.prune {
+        for installed in installed_webapps() {
+            let id = installed.browser.app_id.as_ref().to_string();
+            if listed.contains(&id) {
+                continue;
+            }
+
+            if options.dry_run {
+                report.push(&id, SyncStatus::Removed);
+                continue;
+            }
+
+            let status = match remove_app(&installed, options).await {
+                Ok(()) => SyncStatus::Removed,
+                Err(e) => SyncStatus::Failed(e.to_string()),
+            };
+            report.push(&id, status);
+        }
+    }
+
+    report
+}
+
+async fn sync_app(
+    app: &ManifestApp,
+    id: &str,
+    base_dir: &Path,
+    options: SyncOptions,
+) -> Result<SyncStatus, Box<dyn std::error::Error>> {
+    let existing = WebAppLauncher::from_appid(id);
+    let (icon, icon_changed) = resolve_icon(app, id, existing.as_ref(), base_dir, options).await?;
+
+    let mut browser = app
+        .browser
+        .clone()
+        .unwrap_or_else(|| Browser::new(id, false));
+    let defaults = Browser::new(id, app.persistent);
+    browser.app_id = defaults.app_id;
+    browser.profile = app.profile.clone().or(defaults.profile);
+    browser.url = Some(app.url.clone());
+    if browser.window_title.is_none() {
+        browser.window_title = Some(app.name.clone());
+    }
+    if let Some(existing) = &existing {
+        browser.last_url = existing.browser.last_url.clone();
+        browser.launch_count = existing.browser.launch_count;
+        browser.last_launched = existing.browser.last_launched;
+    }
+
+    let desired = validate_imported_app(WebAppLauncher {
+        browser,
+        name: app.name.clone(),
+        icon,
+        category: app.category.clone(),
+        icon_overlay: app.icon_overlay.clone(),
+    })
+    .ok_or("invalid app definition")?;
+
+    let status = match &existing {
+        None => SyncStatus::Created,
+        Some(existing) if !icon_changed && same_app(existing, &desired) => {
+            return Ok(SyncStatus::Unchanged);
+        }
+        Some(_) => SyncStatus::Updated,
+    };
+
+    if options.dry_run {
+        return Ok(status);
+    }
+
+    if !options.skip_desktop_entries {
+        desired.create().await?;
+    }
+
+    let (saved, _) = save_imported(std::slice::from_ref(&desired));
+    if saved == 0 {
+        return Err("failed to save app".into());
+    }
+
+    Ok(status)
+}
+
+/// Resolve the icon path of `app`, copying or downloading it unless this is a
+/// dry run. Returns the path and whether the icon changed.
+async fn resolve_icon(
+    app: &ManifestApp,
+    id: &str,
+    existing: Option<&WebAppLauncher>,
+    base_dir: &Path,
+    options: SyncOptions,
+) -> Result<(String, bool), Box<dyn std::error::Error>> {
+    match &app.icon {
+        IconSource::Path(path) => {
+            let source = if path.is_absolute() {
+                path.clone()
+            } else {
+                base_dir.join(path)
+            };
+            let source_bytes = std::fs::read(&source)
+                .map_err(|e| format!("failed to read icon {}: {e}", source.display()))?;
+
+            let changed = !icon_pipeline::is_current(&source_bytes, id);
+            if changed && !options.dry_run {
+                let path = icon_pipeline::normalize(&source_bytes, id)
+                    .map_err(|e| format!("failed to import icon {}: {e}", source.display()))?;
+                return Ok((path.to_string_lossy().to_string(), true));
+            }
+
+            let path = icon_pipeline::stored_icon(id).unwrap_or(source);
+            Ok((path.to_string_lossy().to_string(), changed))
+        }
+        IconSource::Favicon => {
+            if let Some(existing) = existing {
+                let same_url = existing.browser.url.as_deref() == Some(app.url.as_str());
+                if same_url && Path::new(&existing.icon).is_file() {
+                    return Ok((existing.icon.clone(), false));
+                }
+            }
+
+            if options.dry_run {
+                return Ok((app.url.clone(), true));
+            }
+
+            let icon = crate::site_icon(&app.url, &app.name)
+                .await
+                .ok_or("failed to create an icon for the site")?;
+            Ok((icon, true))
+        }
+    }
+}
+
+fn same_app(a: &WebAppLauncher, b: &WebAppLauncher) -> bool {
+    let config = ron::ser::PrettyConfig::default();
+    match (
+        ron::ser::to_string_pretty(a, config.clone()),
+        ron::ser::to_string_pretty(b, config),
+    ) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+async fn remove_app(
+    app: &WebAppLauncher,
+    options: SyncOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !options.skip_desktop_entries {
+        return app.delete().await;
+    }
+
+    if let Some(path) = crate::database_path(&format!("{}.ron", app.browser.app_id.as_ref())) {
+        tokio::fs::remove_file(path).await?;
+    }
+    app.browser.delete();
+
+    Ok(())
+}
@@ -0,0 +1,688 @@
+//! Importers for web apps created by other tools.
+//!
+//! Supported sources:
+//!
+//! - Chromium based browsers: `--app=` and `--app-id=` desktop entries. URLs of
+//!   `--app-id=` apps are read from the web app database of the browser
+//!   profile, or from the app manifests in its `Preferences` file.
+//! - Firefox PWA (`firefoxpwa`) site configurations.
+//! - GNOME Web (Epiphany) `epiphany-*.desktop` web apps.
+//!
+//! Browser data is not migrated. Apps found in the same profile of a tool
+//! share a named profile, see [`Discovery::profiles`], so the user signs in
+//! once for all of them and stays signed in.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    APP_ID, Category,
+    browser::{Browser, sanitize_app_id},
+    desktop_entry::DesktopEntry,
+    launcher::{MAX_IMPORT_APPS, WebAppLauncher, validate_imported_app},
+    leveldb,
+    profile::{self, Profile},
+};
+
+/// Maximum size of a desktop entry file read during discovery (64 KB).
+const MAX_DESKTOP_ENTRY_SIZE: u64 = 64 * 1024;
+
+/// Maximum size of a browser configuration file such as `Preferences` (16 MB).
+const MAX_CONFIG_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Maximum size of an icon copied during discovery (10 MB).
+const MAX_ICON_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Directory in `$XDG_CACHE_HOME/<APP_ID>` holding icons of discovered apps
+/// until they are imported.
+const STAGING_DIR: &str = "import";
+
+/// Configuration directories of Chromium based browsers, relative to
+/// `~/.config`, and the names of the browsers.
+const CHROMIUM_CONFIG_DIRS: &[(&str, &str)] = &[
+    ("chromium", "Chromium"),
+    ("google-chrome", "Google Chrome"),
+    ("google-chrome-beta", "Google Chrome Beta"),
+    ("google-chrome-unstable", "Google Chrome Dev"),
+    ("BraveSoftware/Brave-Browser", "Brave"),
+    ("microsoft-edge", "Microsoft Edge"),
+    ("vivaldi", "Vivaldi"),
+];
+
+/// A tool that web apps can be imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Source {
+    Chromium,
+    FirefoxPwa,
+    Epiphany,
+}
+
+impl Source {
+    pub const ALL: [Source; 3] = [Source::Chromium, Source::FirefoxPwa, Source::Epiphany];
+}
+
+/// Web apps found in other tools by [`discover`].
+#[derive(Debug, Clone, Default)]
+pub struct Discovery {
+    pub apps: Vec<WebAppLauncher>,
+    /// Shared profiles of the apps, one for each profile of the tool they were
+    /// found in. They are named by [`register_profiles`] after the import.
+    pub profiles: Vec<Profile>,
+}
+
+/// Key prefix of the web apps in the web app database of a Chromium profile,
+/// followed by the app ID.
+const CHROMIUM_WEB_APPS_PREFIX: &str = "web_apps-dt-";
+
+/// Start URLs of web apps by app ID, read from a web app database.
+type WebAppUrls = HashMap<String, String>;
+
+/// A web app found in another tool, before it is mapped to a [`WebAppLauncher`].
+#[derive(Debug, Clone)]
+struct ForeignApp {
+    name: String,
+    url: String,
+    icon: Option<PathBuf>,
+    category: Category,
+    /// Profile of the tool the app runs in, if it is known.
+    profile: Option<SourceProfile>,
+}
+
+/// A profile of another tool.
+#[derive(Debug, Clone)]
+struct SourceProfile {
+    /// Identifies the profile among the profiles of all tools.
+    key: String,
+    /// Name of the shared profile the apps of this profile are imported to.
+    name: String,
+}
+
+impl SourceProfile {
+    /// ID of the shared profile the apps of this profile are imported to. It
+    /// stays the same, so apps imported later join the apps imported before.
+    fn profile_id(&self) -> String {
+        sanitize_app_id(&format!("import-{}", self.key))
+    }
+}
+
+/// Find web apps of the given sources and map them to validated launchers.
+///
+/// Icons are copied to a staging directory and only stored under the app ID by
+/// [`crate::launcher::install_imported`], once the ID is final. Apps without a
+/// usable icon fall back to the site favicon and are dropped if that fails as
+/// well. The app ID is derived from the name, so importing the same app twice
+/// is reported as a conflict by [`crate::launcher::plan_import`].
+pub async fn discover(sources: &[Source]) -> Discovery {
+    let sources = sources.to_vec();
+    let found = tokio::task::spawn_blocking(move || {
+        clear_staged_icons();
+        scan(&sources)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, app)| {
+                let staged = app.icon.as_deref().and_then(|icon| stage_icon(icon, idx));
+                (app, staged)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+
+    let mut discovery = Discovery::default();
+
+    for (app, staged) in found {
+        let id = sanitize_app_id(&app.name.replace(' ', ""));
+        if id.is_empty() {
+            tracing::warn!("Skipping '{}': no usable app id", app.name);
+            continue;
+        }
+
+        let icon = match staged {
+            Some(icon) => icon,
+            None => match crate::site_icon(&app.url, &app.name).await {
+                Some(icon) => icon,
+                None => {
+                    tracing::warn!("Skipping '{}': no icon found", app.name);
+                    continue;
+                }
+            },
+        };
+
+        let mut browser = Browser::new(&id, app.profile.is_none());
+        browser.url = Some(app.url);
+        browser.window_title = Some(app.name.clone());
+        if let Some(source) = app.profile {
+            let id = source.profile_id();
+            if !discovery.profiles.iter().any(|p| p.id == id) {
+                discovery.profiles.push(Profile {
+                    id: id.clone(),
+                    name: source.name,
+                });
+            }
+            browser.profile = Some(id);
+        }
+
+        let launcher = WebAppLauncher {
+            browser,
+            name: app.name,
+            icon,
+            category: app.category,
+            icon_overlay: None,
+        };
+
+        if let Some(launcher) = validate_imported_app(launcher) {
+            discovery.apps.push(launcher);
+        }
+    }
+
+    discovery
+}
+
+/// Name the shared `profiles` of a [`Discovery`] that imported apps use and
+/// return them.
+pub fn register_profiles(profiles: &[Profile]) -> Vec<Profile> {
+    profiles
+        .iter()
+        .filter(|p| !profile::users(&p.id).is_empty())
+        .filter(|p| match profile::register(p) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::error!("Failed to name profile '{}': {e}", p.name);
+                false
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+fn scan(sources: &[Source]) -> Vec<ForeignApp> {
+    let mut found: Vec<ForeignApp> = Vec::new();
+
+    for source in sources {
+        let apps = match source {
+            Source::Chromium => chromium_apps(),
+            Source::FirefoxPwa => firefoxpwa_apps(),
+            Source::Epiphany => epiphany_apps(),
+        };
+
+        for app in apps {
+            if found.len() >= MAX_IMPORT_APPS {
+                tracing::warn!("Found more than {MAX_IMPORT_APPS} web apps, ignoring the rest");
+                return found;
+            }
+
+            if !crate::url_valid(&app.url) || found.iter().any(|f| f.url == app.url) {
+                continue;
+            }
+
+            found.push(app);
+        }
+    }
+
+    found
+}
+
+/// Chromium, Chrome, Brave, Edge and Vivaldi web apps.
+fn chromium_apps() -> Vec<ForeignApp> {
+    let mut apps = Vec::new();
+    let mut databases = HashMap::new();
+
+    for (path, entry) in desktop_entries() {
+        let Some(args) = entry.exec_args("Desktop Entry") else {
+            continue;
+        };
+        let Some(name) = entry.get("Desktop Entry", "Name") else {
+            continue;
+        };
+
+        let flag = |prefix: &str| {
+            args.iter()
+                .find_map(|arg| arg.strip_prefix(prefix))
+                .map(str::to_string)
+        };
+
+        let url = match (flag("--app="), flag("--app-id=")) {
+            (Some(url), _) => url,
+            (None, Some(app_id)) => {
+                let profile = flag("--profile-directory=").unwrap_or_else(|| "Default".into());
+                match chromium_app_url(&app_id, &profile, &mut databases) {
+                    Some(url) => url,
+                    None => {
+                        tracing::warn!("No start URL found for Chromium app {path:?}");
+                        continue;
+                    }
+                }
+            }
+            (None, None) => continue,
+        };
+
+        let icon = entry
+            .get("Desktop Entry", "Icon")
+            .and_then(|icon| resolve_icon(&icon))
+            .or_else(|| {
+                let app_id = flag("--app-id=")?;
+                let profile = flag("--profile-directory=").unwrap_or_else(|| "Default".into());
+                chromium_app_icon(&app_id, &profile)
+            });
+
+        let profile = flag("--profile-directory=").unwrap_or_else(|| "Default".into());
+        let program = args.first().map(String::as_str).unwrap_or_default();
+        let source = chromium_browser(program, flag("--app-id=").as_deref(), &profile).map(
+            |(dir, browser)| SourceProfile {
+                key: format!("{dir}-{profile}"),
+                name: format!("{browser} ({})", chromium_profile_name(dir, &profile)),
+            },
+        );
+
+        apps.push(ForeignApp {
+            name,
+            url,
+            icon,
+            category: entry_category(&entry),
+            profile: source,
+        });
+    }
+
+    apps
+}
+
+/// Config directory and name of the browser running a Chromium app: the one
+/// the desktop entry starts, or else the one keeping the data of `app_id`.
+fn chromium_browser(
+    program: &str,
+    app_id: Option<&str>,
+    profile: &str,
+) -> Option<(&'static str, &'static str)> {
+    let program = Path::new(program)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    let started = CHROMIUM_CONFIG_DIRS
+        .iter()
+        .filter(|(dir, _)| {
+            let base = dir.rsplit('/').next().unwrap_or(dir);
+            program.starts_with(&base.to_ascii_lowercase())
+        })
+        .max_by_key(|(dir, _)| dir.len());
+
+    started
+        .or_else(|| {
+            let app_id = app_id?;
+            let config_dir = dirs::config_dir()?;
+            CHROMIUM_CONFIG_DIRS.iter().find(|(dir, _)| {
+                config_dir
+                    .join(dir)
+                    .join(profile)
+                    .join("Web Applications")
+                    .join("Manifest Resources")
+                    .join(app_id)
+                    .is_dir()
+            })
+        })
+        .copied()
+}
+
+/// Name the user gave the Chromium `profile` in the browser in `dir`.
+fn chromium_profile_name(dir: &str, profile: &str) -> String {
+    dirs::config_dir()
+        .and_then(|config_dir| read_json(&config_dir.join(dir).join("Local State")))
+        .and_then(|state| {
+            state
+                .get("profile")?
+                .get("info_cache")?
+                .get(profile)?
+                .get("name")?
+                .as_str()
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| profile.to_string())
+}
+
+/// Read the start URL of an installed Chromium app from the web app database
+/// of `profile`. Apps installed by older browser versions are only found in
+/// the app manifests stored in its `Preferences` file. Databases already read
+/// are kept in `databases`.
+fn chromium_app_url(
+    app_id: &str,
+    profile: &str,
+    databases: &mut HashMap<PathBuf, WebAppUrls>,
+) -> Option<String> {
+    let config_dir = dirs::config_dir()?;
+
+    CHROMIUM_CONFIG_DIRS.iter().find_map(|(dir, _)| {
+        let profile_dir = config_dir.join(dir).join(profile);
+
+        let database = profile_dir.join("Sync Data").join("LevelDB");
+        let urls = databases
+            .entry(database)
+            .or_insert_with_key(|database| chromium_web_app_urls(database));
+        if let Some(url) = urls.get(app_id) {
+            return Some(url.clone());
+        }
+
+        let preferences = read_json(&profile_dir.join("Preferences"))?;
+        preferences
+            .get("extensions")?
+            .get("settings")?
+            .get(app_id)?
+            .pointer("/manifest/app/launch/web_url")?
+            .as_str()
+            .map(str::to_string)
+    })
+}
+
+/// Start URLs of the web apps in the web app database of a Chromium profile.
+fn chromium_web_app_urls(database: &Path) -> WebAppUrls {
+    if !database.is_dir() {
+        return WebAppUrls::new();
+    }
+
+    let entries = match leveldb::read(database, CHROMIUM_WEB_APPS_PREFIX.as_bytes()) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!("Failed to read web app database {database:?}: {e}");
+            return WebAppUrls::new();
+        }
+    };
+
+    entries
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let app_id = String::from_utf8(key)
+                .ok()?
+                .strip_prefix(CHROMIUM_WEB_APPS_PREFIX)?
+                .to_string();
+            // `WebAppProto.sync_data` holds `WebAppSpecifics.start_url`.
+            let sync_data = protobuf_field(&value, 1)?;
+            let start_url = protobuf_field(sync_data, 1)?;
+            Some((app_id, String::from_utf8(start_url.to_vec()).ok()?))
+        })
+        .collect()
+}
+
+/// The first length delimited field `number` of a protocol buffers message.
+fn protobuf_field(mut message: &[u8], number: u64) -> Option<&[u8]> {
+    while !message.is_empty() {
+        let key = leveldb::take_varint(&mut message).ok()?;
+        let len = match key & 0x7 {
+            0 => {
+                leveldb::take_varint(&mut message).ok()?;
+                0
+            }
+            1 => 8,
+            2 => usize::try_from(leveldb::take_varint(&mut message).ok()?).ok()?,
+            5 => 4,
+            _ => return None,
+        };
+
+        let field = message.get(..len)?;
+        message = &message[len..];
+        if key >> 3 == number && key & 0x7 == 2 {
+            return Some(field);
+        }
+    }
+
+    None
+}
+
+/// The largest icon in the `Web Applications` resources of a Chromium app.
+fn chromium_app_icon(app_id: &str, profile: &str) -> Option<PathBuf> {
+    let config_dir = dirs::config_dir()?;
+
+    CHROMIUM_CONFIG_DIRS.iter().find_map(|(dir, _)| {
+        let icons = config_dir
+            .join(dir)
+            .join(profile)
+            .join("Web Applications")
+            .join("Manifest Resources")
+            .join(app_id)
+            .join("Icons");
+
+        fs::read_dir(icons)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter_map(|path| {
+                let size: u32 = path.file_stem()?.to_str()?.parse().ok()?;
+                Some((size, path))
+            })
+            .max_by_key(|(size, _)| *size)
+            .map(|(_, path)| path)
+    })
+}
+
+/// Sites installed with the Firefox PWA extension.
+fn firefoxpwa_apps() -> Vec<ForeignApp> {
+    let Some(config) = dirs::data_dir()
+        .map(|dir| dir.join("firefoxpwa").join("config.json"))
+        .and_then(|path| read_json(&path))
+    else {
+        return Vec::new();
+    };
+
+    let Some(sites) = config.get("sites").and_then(|s| s.as_object()) else {
+        return Vec::new();
+    };
+
+    let text = |site: &serde_json::Value, pointer: &str| {
+        site.pointer(pointer)
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    let profile_name = |ulid: &str| {
+        config
+            .get("profiles")?
+            .get(ulid)?
+            .get("name")?
+            .as_str()
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+    };
+
+    let mut apps = Vec::new();
+
+    for (ulid, site) in sites {
+        let Some(url) = text(site, "/config/start_url")
+            .or_else(|| text(site, "/manifest/start_url"))
+            .or_else(|| text(site, "/config/document_url"))
+        else {
+            continue;
+        };
+
+        let name = text(site, "/config/name")
+            .or_else(|| text(site, "/manifest/name"))
+            .or_else(|| text(site, "/manifest/short_name"))
+            .or_else(|| url::Url::parse(&url).ok()?.host_str().map(str::to_string))
+            .unwrap_or_else(|| ulid.clone());
+
+        let category = ["/config/categories", "/manifest/categories"]
+            .iter()
+            .filter_map(|pointer| site.pointer(pointer)?.as_array())
+            .flatten()
+            .filter_map(|c| c.as_str())
+            .find_map(parse_category)
+            .unwrap_or(Category::Network);
+
+        let profile = text(site, "/profile").map(|profile| SourceProfile {
+            name: format!(
+                "Firefox PWA ({})",
+                profile_name(&profile).unwrap_or_else(|| profile.clone())
+            ),
+            key: format!("firefoxpwa-{profile}"),
+        });
+
+        apps.push(ForeignApp {
+            name,
+            url,
+            icon: resolve_icon(&format!("FFPWA-{ulid}")),
+            category,
+            profile,
+        });
+    }
+
+    apps
+}
+
+/// GNOME Web web apps, installed as `epiphany-*.desktop` or
+/// `org.gnome.Epiphany.WebApp_*.desktop`.
+fn epiphany_apps() -> Vec<ForeignApp> {
+    let mut apps = Vec::new();
+
+    for (path, entry) in desktop_entries() {
+        let is_epiphany = path.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+            n.starts_with("epiphany-") || n.starts_with("org.gnome.Epiphany.WebApp")
+        });
+        if !is_epiphany {
+            continue;
+        }
+
+        let Some(args) = entry.exec_args("Desktop Entry") else {
+            continue;
+        };
+        let Some(name) = entry.get("Desktop Entry", "Name") else {
+            continue;
+        };
+        let Some(url) = args.iter().rev().find(|arg| crate::url_valid(arg)).cloned() else {
+            continue;
+        };
+
+        let profile_dir = args.iter().find_map(|arg| arg.strip_prefix("--profile="));
+        let profile_icon = profile_dir
+            .map(|profile| Path::new(profile).join("app-icon.png"))
+            .filter(|icon| icon.is_file());
+        let profile = profile_dir
+            .and_then(|dir| Path::new(dir).file_name()?.to_str())
+            .map(|dir| SourceProfile {
+                key: format!("epiphany-{dir}"),
+                name: format!("GNOME Web ({name})"),
+            });
+
+        let icon = entry
+            .get("Desktop Entry", "Icon")
+            .and_then(|icon| resolve_icon(&icon))
+            .or(profile_icon);
+
+        apps.push(ForeignApp {
+            name,
+            url,
+            icon,
+            category: entry_category(&entry),
+            profile,
+        });
+    }
+
+    apps
+}
+
+/// Parsed desktop entries of the user applications directory.
+fn desktop_entries() -> Vec<(PathBuf, DesktopEntry)> {
+    let Some(dir) = dirs::data_dir().map(|dir| dir.join("applications")) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "desktop"))
+        .filter(|path| {
+            fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() <= MAX_DESKTOP_ENTRY_SIZE)
+        })
+        .filter_map(|path| {
+            let content = fs::read_to_string(&path).ok()?;
+            Some((path, DesktopEntry::parse(&content)))
+        })
+        .collect()
+}
+
+/// Resolve an `Icon` value: absolute paths are used as is, names are looked up
+/// in the icon theme at the largest common launcher size.
+fn resolve_icon(icon: &str) -> Option<PathBuf> {
+    let path = Path::new(icon);
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    crate::icon_theme::lookup(icon, 256)
+}
+
+fn staging_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join(APP_ID).join(STAGING_DIR))
+}
+
+/// Copy the icon of the `idx`th discovered app to the staging directory, so it
+/// is still there when the user confirms the import.
+fn stage_icon(icon: &Path, idx: usize) -> Option<String> {
+    if fs::metadata(icon).ok()?.len() > MAX_ICON_FILE_SIZE {
+        tracing::warn!("Skipping oversized icon {icon:?}");
+        return None;
+    }
+
+    let dir = staging_dir()?;
+    let extension = icon
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("png");
+    let staged = dir.join(format!("{idx}.{extension}"));
+    fs::create_dir_all(&dir)
+        .and_then(|()| fs::copy(icon, &staged))
+        .inspect_err(|e| tracing::warn!("Failed to copy icon {icon:?}: {e}"))
+        .ok()?;
+
+    Some(staged.to_string_lossy().to_string())
+}
+
+/// Remove icons staged by an earlier discovery.
+fn clear_staged_icons() {
+    let Some(dir) = staging_dir().filter(|dir| dir.exists()) else {
+        return;
+    };
+    if let Err(e) = fs::remove_dir_all(dir) {
+        tracing::warn!("Failed to clear staged icons: {e}");
+    }
+}
+
+/// The staged icon `icon` refers to, if it is one.
+pub(crate) fn staged_icon(icon: &str) -> Option<PathBuf> {
+    let path = Path::new(icon);
+    (staging_dir().is_some_and(|dir| path.parent() == Some(dir.as_path())) && path.is_file())
+        .then(|| path.to_path_buf())
+}
+
+fn entry_category(entry: &DesktopEntry) -> Category {
+    entry
+        .get_list("Desktop Entry", "Categories")
+        .unwrap_or_default()
+        .iter()
+        .find_map(|c| parse_category(c))
+        .unwrap_or(Category::Network)
+}
+
+fn parse_category(value: &str) -> Option<Category> {
+    Category::iter()
+        .filter(|c| c != &Category::None)
+        .find(|c| c.as_ref().eq_ignore_ascii_case(value))
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    let metadata = fs::metadata(path).ok()?;
+    if metadata.len() > MAX_CONFIG_FILE_SIZE {
+        tracing::warn!(
+            "Skipping oversized file {path:?} ({} bytes)",
+            metadata.len()
+        );
+        return None;
+    }
+
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
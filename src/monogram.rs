@@ -0,0 +1,233 @@
+//! Generated fallback icons.
+//!
+//! Sites without a usable favicon get a monogram icon: the initials of the app
+//! name on a rounded square. The background uses the `theme-color` of the site,
+//! either from its `<meta>` tag or its web app manifest, and otherwise a colour
+//! derived from a hash of the domain, so the same site always gets the same
+//! colour.
+
+/// Maximum number of letters in a monogram.
+const MAX_INITIALS: usize = 2;
+/// Saturation and lightness of colours derived from a hash.
+const DOMAIN_SATURATION: f32 = 0.55;
+const DOMAIN_LIGHTNESS: f32 = 0.42;
+/// Backgrounds brighter than this get dark text.
+const LIGHT_BACKGROUND: f32 = 0.6;
+/// Fonts tried in order for the letters.
+pub(crate) const FONT_FAMILIES: &str =
+    "'Open Sans', 'Noto Sans', Cantarell, 'Fira Sans', 'DejaVu Sans', sans-serif";
+
+pub(crate) type Rgb = [u8; 3];
+
+/// Render a monogram icon for the app `name` of `url` into the icons directory
+/// and return its path.
+pub async fn generate(name: &str, url: &str) -> Option<String> {
+    let domain = site_domain(url)?;
+    let color = match theme_color(url).await {
+        Some(color) => color,
+        None => hash_color(&domain),
+    };
+
+    let text = monogram_text(name, &domain);
+
+    let icon_name = format!(
+        "monogram-{}-{}",
+        crate::sanitize_domain_for_filename(&domain),
+        text.to_lowercase()
+    );
+    let svg = svg(&text, color);
+
+    let result = tokio::task::spawn_blocking(move || {
+        crate::icon_pipeline::normalize(svg.as_bytes(), &icon_name)
+    })
+    .await
+    .ok()?;
+
+    match result {
+        Ok(path) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            tracing::error!("Failed to generate icon for {domain}: {e}");
+            None
+        }
+    }
+}
+
+/// SVG of a monogram icon for the app `name` of `url`, coloured by the hash of
+/// the domain. Unlike [`generate`] it does not fetch the site.
+pub fn offline_svg(name: &str, url: &str) -> String {
+    let domain = site_domain(url).unwrap_or_default();
+    svg(&monogram_text(name, &domain), hash_color(&domain))
+}
+
+/// Initials of the app `name`, or else of the `domain`.
+fn monogram_text(name: &str, domain: &str) -> String {
+    initials(name)
+        .or_else(|| initials(domain.split('.').next().unwrap_or_default()))
+        .unwrap_or_else(|| "?".to_string())
+}
+
+/// Host of `url` without a leading `www.`.
+fn site_domain(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    let host = parsed.host_str()?;
+
+    Some(host.strip_prefix("www.").unwrap_or(host).to_string())
+}
+
+/// Up to [`MAX_INITIALS`] uppercase letters, taken from the first words of `name`.
+pub(crate) fn initials(name: &str) -> Option<String> {
+    let initials: String = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter_map(|word| word.chars().next())
+        .take(MAX_INITIALS)
+        .flat_map(char::to_uppercase)
+        .collect();
+
+    (!initials.is_empty()).then_some(initials)
+}
+
+fn svg(text: &str, background: Rgb) -> String {
+    let [r, g, b] = background;
+    let foreground = text_color(background);
+    let font_size = if text.chars().count() > 1 { 104 } else { 136 };
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="256" height="256" viewBox="0 0 256 256">
+  <rect x="8" y="8" width="240" height="240" rx="56" fill="#{r:02x}{g:02x}{b:02x}"/>
+  <text x="128" y="128" dominant-baseline="central" text-anchor="middle" font-family="{FONT_FAMILIES}" font-size="{font_size}" font-weight="bold" fill="{foreground}">{text}</text>
+</svg>
+"##
+    )
+}
+
+/// Dark or white text, whichever is readable on `background`.
+pub(crate) fn text_color(background: Rgb) -> &'static str {
+    if luminance(background) > LIGHT_BACKGROUND {
+        "#1f1f1f"
+    } else {
+        "#ffffff"
+    }
+}
+
+/// Relative luminance of an sRGB colour, between 0 and 1.
+fn luminance([r, g, b]: Rgb) -> f32 {
+    (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32) / 255.0
+}
+
+/// The `theme-color` of the page at `url`, or the `theme_color` of the web app
+/// manifest it links to.
+async fn theme_color(url: &str) -> Option<Rgb> {
+    let html = crate::fetch_page(url).await?;
+
+    let meta = crate::extract_meta_content(&html, "theme-color");
+    if let Some(color) = meta.as_deref().and_then(parse_color) {
+        return Some(color);
+    }
+
+    let href = manifest_href(&html)?;
+    let manifest_url = url::Url::parse(url).ok()?.join(&href).ok()?;
+    let manifest = crate::fetch_page(manifest_url.as_str()).await?;
+    let manifest: serde_json::Value = serde_json::from_str(&manifest).ok()?;
+
+    parse_color(manifest.get("theme_color")?.as_str()?)
+}
+
+/// The `href` of the first `<link rel="manifest">` tag.
+fn manifest_href(html: &str) -> Option<String> {
+    html.split("<link")
+        .skip(1)
+        .filter_map(|tag| tag.split('>').next())
+        .filter(|tag| {
+            attribute(tag, "rel").is_some_and(|rel| {
+                rel.split_whitespace()
+                    .any(|r| r.eq_ignore_ascii_case("manifest"))
+            })
+        })
+        .find_map(|tag| attribute(tag, "href"))
+}
+
+/// Value of the attribute `name` inside the contents of an HTML tag.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+
+    while let Some(pos) = rest.find(name) {
+        let standalone = pos == 0 || rest[..pos].ends_with(char::is_whitespace);
+        rest = &rest[pos + name.len()..];
+
+        let value = match rest.trim_start().strip_prefix('=') {
+            Some(value) if standalone => value.trim_start(),
+            _ => continue,
+        };
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next()?,
+            _ => value.split(char::is_whitespace).next()?,
+        };
+        return Some(value.to_string());
+    }
+
+    None
+}
+
+/// Parse a CSS colour in `#rgb`, `#rrggbb`, `#rrggbbaa` or `rgb()` notation.
+pub(crate) fn parse_color(value: &str) -> Option<Rgb> {
+    let value = value.trim();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let channel = |i: usize, len: usize| u8::from_str_radix(&hex[i * len..(i + 1) * len], 16);
+        return match hex.len() {
+            3 | 4 => Some([
+                channel(0, 1).ok()? * 17,
+                channel(1, 1).ok()? * 17,
+                channel(2, 1).ok()? * 17,
+            ]),
+            6 | 8 => Some([
+                channel(0, 2).ok()?,
+                channel(1, 2).ok()?,
+                channel(2, 2).ok()?,
+            ]),
+            _ => None,
+        };
+    }
+
+    let arguments = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))?
+        .strip_suffix(')')?;
+    let mut channels = arguments
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|c| !c.is_empty())
+        .map(|c| c.parse::<f32>().ok().map(|c| c.clamp(0.0, 255.0) as u8));
+
+    Some([channels.next()??, channels.next()??, channels.next()??])
+}
+
+/// A stable colour for `value`, using FNV-1a so it does not change between
+/// releases.
+pub(crate) fn hash_color(value: &str) -> Rgb {
+    let hash = value.bytes().fold(0x811c_9dc5u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+
+    hsl_to_rgb((hash % 360) as f32, DOMAIN_SATURATION, DOMAIN_LIGHTNESS)
+}
+
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> Rgb {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = lightness - chroma / 2.0;
+
+    let (r, g, b) = match hue as u32 {
+        0..60 => (chroma, x, 0.0),
+        60..120 => (x, chroma, 0.0),
+        120..180 => (0.0, chroma, x),
+        180..240 => (0.0, x, chroma),
+        240..300 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    [r, g, b].map(|c| ((c + m) * 255.0).round() as u8)
+}
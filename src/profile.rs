@@ -0,0 +1,277 @@
+//! Named browser profiles.
+//!
+//! A profile is a WebKit data directory in `profiles/<id>` holding cookies,
+//! storage and cache. Web apps reference a profile by ID through
+//! [`crate::browser::Browser::profile`], so several apps can share a login and
+//! one site can be installed twice with different logins.
+//!
+//! Profiles created by the user are listed with their name in `profiles.ron`.
+//! The own profile of an app has the app ID as its ID, needs no entry there
+//! and is named after the app until it is renamed.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{fs, path::PathBuf};
+use walkdir::WalkDir;
+
+use crate::{
+    APP_ID,
+    browser::{NetworkSettings, generate_app_id, sanitize_app_id},
+    launcher::{WebAppLauncher, installed_webapps},
+};
+
+const REGISTRY_FILE: &str = "profiles.ron";
+
+/// Maximum length of a profile name.
+const MAX_NAME_LEN: usize = 64;
+
+type ProfileResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+}
+
+/// Data directory of the profile `id`.
+pub fn path(id: &str) -> Option<PathBuf> {
+    let id = sanitize_app_id(id);
+    if id.is_empty() {
+        return None;
+    }
+
+    crate::profiles_path(&id)
+}
+
+/// All known profiles: the named ones, the own profiles of installed apps and
+/// profile directories no app uses anymore, sorted by name.
+pub fn all() -> Vec<Profile> {
+    let mut profiles = registry();
+
+    for app in installed_webapps() {
+        let Some(id) = app.browser.profile else {
+            continue;
+        };
+        if profiles.iter().any(|p| p.id == id) {
+            continue;
+        }
+
+        let name = if id == app.browser.app_id.as_ref() {
+            app.name
+        } else {
+            id.clone()
+        };
+        profiles.push(Profile { id, name });
+    }
+
+    let dirs = crate::profiles_path("").and_then(|dir| fs::read_dir(dir).ok());
+    for entry in dirs.into_iter().flatten().flatten() {
+        let Some(id) = entry.file_name().to_str().map(sanitize_app_id) else {
+            continue;
+        };
+        if entry.path().is_dir() && !id.is_empty() && !profiles.iter().any(|p| p.id == id) {
+            profiles.push(Profile {
+                name: id.clone(),
+                id,
+            });
+        }
+    }
+
+    profiles.sort_by_key(|p| p.name.to_lowercase());
+    profiles
+}
+
+/// Display name of the profile `id`.
+pub fn name(id: &str) -> String {
+    all()
+        .into_iter()
+        .find(|p| p.id == id)
+        .map(|p| p.name)
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// Create an empty named profile.
+pub fn create(name: &str) -> ProfileResult<Profile> {
+    let name = clean_name(name).ok_or("profile name is empty")?;
+    let taken = all();
+
+    let mut id = format!("profile-{}", generate_app_id(&name));
+    while taken.iter().any(|p| p.id == id) || path(&id).is_some_and(|dir| dir.exists()) {
+        id = format!("profile-{}", generate_app_id(&name));
+    }
+
+    fs::create_dir_all(path(&id).ok_or("failed to resolve profile directory")?)?;
+
+    let profile = Profile { id, name };
+    let mut profiles = registry();
+    profiles.push(profile.clone());
+    save_registry(&profiles)?;
+
+    Ok(profile)
+}
+
+/// Rename the profile `id`. The own profile of an app keeps its ID.
+pub fn rename(id: &str, name: &str) -> ProfileResult<()> {
+    let name = clean_name(name).ok_or("profile name is empty")?;
+    let mut profiles = registry();
+
+    match profiles.iter_mut().find(|p| p.id == id) {
+        Some(profile) => profile.name = name,
+        None => profiles.push(Profile {
+            id: id.to_string(),
+            name,
+        }),
+    }
+
+    save_registry(&profiles)
+}
+
+/// Remember the name of a profile, e.g. one imported from a bundle, unless it
+/// is known already.
+pub fn register(profile: &Profile) -> ProfileResult<()> {
+    let mut profiles = registry();
+    if profiles.iter().any(|p| p.id == profile.id) {
+        return Ok(());
+    }
+
+    let name = clean_name(&profile.name).unwrap_or_else(|| profile.id.clone());
+    profiles.push(Profile {
+        id: sanitize_app_id(&profile.id),
+        name,
+    });
+
+    save_registry(&profiles)
+}
+
+/// Delete the profile `id` with all its data. Fails while apps still use it.
+pub fn delete(id: &str) -> ProfileResult<()> {
+    let users = users(id);
+    if !users.is_empty() {
+        let names: Vec<String> = users.into_iter().map(|app| app.name).collect();
+        return Err(format!("profile is used by {}", names.join(", ")).into());
+    }
+
+    remove(id)
+}
+
+/// Remove the data directory of the profile `id` and its name.
+pub(crate) fn remove(id: &str) -> ProfileResult<()> {
+    remove_data(id)?;
+
+    let mut profiles = registry();
+    if profiles.iter().any(|p| p.id == id) {
+        profiles.retain(|p| p.id != id);
+        save_registry(&profiles)?;
+    }
+
+    Ok(())
+}
+
+/// Remove the data directory of the profile `id`.
+fn remove_data(id: &str) -> std::io::Result<()> {
+    match path(id) {
+        Some(dir) if dir.exists() => fs::remove_dir_all(dir),
+        _ => Ok(()),
+    }
+}
+
+/// Installed apps using the profile `id`.
+pub fn users(id: &str) -> Vec<WebAppLauncher> {
+    installed_webapps()
+        .into_iter()
+        .filter(|app| app.browser.profile.as_deref() == Some(id))
+        .collect()
+}
+
+/// Installed apps other than `app_id` sharing a webview process with it when
+/// it uses the profile `id`, those not in private mode.
+pub fn process_mates(id: &str, app_id: &str) -> Vec<WebAppLauncher> {
+    users(id)
+        .into_iter()
+        .filter(|app| app.browser.app_id.as_ref() != app_id)
+        .filter(|app| !app.browser.private_mode.unwrap_or(false))
+        .collect()
+}
+
+/// The first of `apps` needing network settings other than `settings`. They
+/// cannot run in one process with an app using `settings`.
+pub fn network_conflict<'a>(
+    apps: &'a [WebAppLauncher],
+    settings: &NetworkSettings,
+) -> Option<&'a WebAppLauncher> {
+    apps.iter()
+        .find(|app| app.browser.network_settings() != *settings)
+}
+
+/// Total size of the files in the profile `id` in bytes, 0 if it has no data.
+pub fn size(id: &str) -> u64 {
+    let Some(dir) = path(id) else {
+        return 0;
+    };
+
+    WalkDir::new(&dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Clear all website data (cookies, cache, storage) of the profile `id`,
+/// keeping the profile itself.
+pub fn clear(id: &str) -> std::io::Result<()> {
+    let Some(dir) = path(id) else {
+        return Ok(());
+    };
+
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+        fs::create_dir_all(&dir)?;
+    }
+
+    Ok(())
+}
+
+/// Read a profile ID, accepting the profile directory paths stored by older
+/// versions.
+pub(crate) fn deserialize_id<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<String> = Option::deserialize(deserializer)?;
+
+    Ok(value
+        .map(|value| match value.rsplit_once('/') {
+            Some((_, id)) => sanitize_app_id(id),
+            None => sanitize_app_id(&value),
+        })
+        .filter(|id| !id.is_empty()))
+}
+
+fn clean_name(name: &str) -> Option<String> {
+    let name: String = name.trim().chars().take(MAX_NAME_LEN).collect();
+    (!name.is_empty()).then_some(name)
+}
+
+fn registry_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_ID).join(REGISTRY_FILE))
+}
+
+fn registry() -> Vec<Profile> {
+    registry_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| ron::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_registry(profiles: &[Profile]) -> ProfileResult<()> {
+    let path = registry_path().ok_or("failed to resolve data directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let content = ron::ser::to_string_pretty(profiles, ron::ser::PrettyConfig::default())?;
+    fs::write(path, content)?;
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+//! Website data of a profile, by category.
+//!
+//! A running webview clears data through WebKit's website data manager, see
+//! [`crate::instance::clear_data`]. When no app of the profile is running the
+//! files are removed from the profile directory and the WebKit cache
+//! directories instead. Sizes are always read from disk.
+
+use std::{fs, path::PathBuf};
+use walkdir::WalkDir;
+
+use crate::{instance, profile};
+
+type SiteDataResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Cookie jar written by wry into the profile directory.
+pub const COOKIES_FILE: &str = "cookies";
+/// WebKit subdirectories of the profile directory holding page storage.
+const STORAGE_DIRS: [&str; 3] = ["localstorage", "databases", "storage"];
+/// WebKit subdirectories of the profile directory holding service workers.
+const SERVICE_WORKER_DIRS: [&str; 1] = ["serviceworkers"];
+/// WebKit subdirectories of the cache directory holding the HTTP cache.
+const CACHE_DIRS: [&str; 2] = ["WebKitCache", "applications"];
+/// WebKit subdirectory of the cache directory holding service worker caches.
+const CACHE_STORAGE_DIR: &str = "CacheStorage";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub enum SiteData {
+    /// The HTTP cache.
+    Cache,
+    /// Cookies, clearing them signs out of all sites.
+    Cookies,
+    /// Local storage, session storage and IndexedDB.
+    Storage,
+    /// Service worker registrations and their caches.
+    ServiceWorkers,
+}
+
+impl SiteData {
+    pub const ALL: [SiteData; 4] = [
+        SiteData::Cache,
+        SiteData::Cookies,
+        SiteData::Storage,
+        SiteData::ServiceWorkers,
+    ];
+
+    /// Stable name used on the command line and over D-Bus.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SiteData::Cache => "cache",
+            SiteData::Cookies => "cookies",
+            SiteData::Storage => "storage",
+            SiteData::ServiceWorkers => "service-workers",
+        }
+    }
+
+    /// The category called `name` by [`SiteData::as_str`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|data| data.as_str() == name)
+    }
+
+    /// Localized name shown in the editor.
+    pub fn name(self) -> String {
+        match self {
+            SiteData::Cache => fl!("site-data-cache"),
+            SiteData::Cookies => fl!("site-data-cookies"),
+            SiteData::Storage => fl!("site-data-storage"),
+            SiteData::ServiceWorkers => fl!("site-data-service-workers"),
+        }
+    }
+
+    /// Files and directories holding this data for the profile `id`.
+    fn paths(self, id: &str) -> Vec<PathBuf> {
+        let Some(dir) = profile::path(id) else {
+            return Vec::new();
+        };
+
+        match self {
+            SiteData::Cache => cache_dirs(id)
+                .into_iter()
+                .flat_map(|cache| CACHE_DIRS.map(|sub| cache.join(sub)))
+                .collect(),
+            SiteData::Cookies => vec![dir.join(COOKIES_FILE)],
+            SiteData::Storage => STORAGE_DIRS.map(|sub| dir.join(sub)).to_vec(),
+            SiteData::ServiceWorkers => SERVICE_WORKER_DIRS
+                .map(|sub| dir.join(sub))
+                .into_iter()
+                .chain(
+                    cache_dirs(id)
+                        .into_iter()
+                        .map(|cache| cache.join(CACHE_STORAGE_DIR)),
+                )
+                .collect(),
+        }
+    }
+}
+
+/// WebKit keeps the cache of a profile under the program name of the webview
+/// that opened it, which is the ID of the first app launched with it.
+fn cache_dirs(id: &str) -> Vec<PathBuf> {
+    let Some(cache) = dirs::cache_dir() else {
+        return Vec::new();
+    };
+
+    let mut names = vec![id.to_string()];
+    for app in profile::users(id) {
+        let app_id = app.browser.app_id.id;
+        if !names.contains(&app_id) {
+            names.push(app_id);
+        }
+    }
+
+    names.into_iter().map(|name| cache.join(name)).collect()
+}
+
+/// Size of the `data` of the profile `id` in bytes.
+pub fn size(id: &str, data: SiteData) -> u64 {
+    data.paths(id)
+        .iter()
+        .flat_map(WalkDir::new)
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Size of every category of data of the profile `id`.
+pub fn sizes(id: &str) -> Vec<(SiteData, u64)> {
+    SiteData::ALL
+        .into_iter()
+        .map(|data| (data, size(id, data)))
+        .collect()
+}
+
+/// Clear the `data` of the profile `id`, keeping everything else.
+pub async fn clear(id: &str, data: &[SiteData]) -> SiteDataResult<()> {
+    if data.is_empty() {
+        return Ok(());
+    }
+
+    // WebKit keeps cookies and storage in memory, so a running webview has to
+    // clear them itself or it would write them back.
+    if instance::clear_data(id, data).await? {
+        return Ok(());
+    }
+
+    let id = id.to_string();
+    let data = data.to_vec();
+    tokio::task::spawn_blocking(move || {
+        for path in data.iter().flat_map(|data| data.paths(&id)) {
+            if path.is_dir() {
+                fs::remove_dir_all(&path)?;
+            } else if path.exists() {
+                fs::remove_file(&path)?;
+            }
+        }
+
+        Ok::<_, std::io::Error>(())
+    })
+    .await??;
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! Certificates trusted by a web app beyond the system trust store.
+//!
+//! An app can name a bundle of extra CA certificates, e.g. a corporate CA,
+//! and keep pins of single certificates the user chose to trust on the error
+//! page of the webview. Both only apply to the app that holds them. Sites
+//! asking for a client certificate get the one of a PKCS#12 file of the app.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{io::Read, path::Path};
+
+/// Largest CA bundle that is read.
+const MAX_BUNDLE_SIZE: u64 = 4 * 1024 * 1024;
+const PEM_CERTIFICATE: &str = "-----BEGIN CERTIFICATE-----";
+/// Largest client certificate file that is read.
+const MAX_CLIENT_CERTIFICATE_SIZE: u64 = 1024 * 1024;
+
+/// A certificate trusted for one host despite failing verification.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CertificatePin {
+    pub host: String,
+    /// Fingerprint of the certificate, see [`fingerprint`].
+    pub sha256: String,
+    /// The PEM encoded certificate.
+    pub pem: String,
+}
+
+/// SHA-256 fingerprint of a DER encoded certificate, as colon separated hex.
+pub fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Check that `path` is a readable file of PEM encoded certificates.
+pub fn validate_ca_bundle(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let path = Path::new(path.trim());
+    if !path.is_absolute() {
+        return Err("CA bundle path must be absolute".into());
+    }
+
+    let mut content = String::new();
+    std::fs::File::open(path)
+        .and_then(|file| file.take(MAX_BUNDLE_SIZE).read_to_string(&mut content))
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    if !content.contains(PEM_CERTIFICATE) {
+        return Err(format!("{} contains no PEM certificates", path.display()).into());
+    }
+
+    Ok(())
+}
+
+/// Check that `path` is a PKCS#12 file and return its content.
+pub fn read_client_certificate(path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let path = Path::new(path.trim());
+    if !path.is_absolute() {
+        return Err("client certificate path must be absolute".into());
+    }
+
+    let mut content = Vec::new();
+    std::fs::File::open(path)
+        .and_then(|file| {
+            file.take(MAX_CLIENT_CERTIFICATE_SIZE + 1)
+                .read_to_end(&mut content)
+        })
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    if content.len() as u64 > MAX_CLIENT_CERTIFICATE_SIZE {
+        return Err(format!("{} is too large", path.display()).into());
+    }
+    // PKCS#12 files are a DER encoded SEQUENCE
+    if content.first() != Some(&0x30) {
+        return Err(format!("{} is not a PKCS#12 file", path.display()).into());
+    }
+
+    Ok(content)
+}
@@ -0,0 +1,128 @@
+#!/usr/bin/env python3
+"""Write the LevelDB fixtures of src/leveldb.rs: python3 generate.py tests/fixtures/leveldb"""
+import struct, os, sys
+out = sys.argv[1]
+
+def crc32c(data):
+    crc = 0xffffffff
+    for b in data:
+        crc ^= b
+        for _ in range(8):
+            crc = (crc >> 1) ^ 0x82F63B78 if crc & 1 else crc >> 1
+    return crc ^ 0xffffffff
+
+def mask(crc):
+    return ((((crc >> 15) | (crc << 17)) & 0xffffffff) + 0xa282ead8) & 0xffffffff
+
+def varint(n):
+    b = bytearray()
+    while n >= 0x80:
+        b.append((n & 0x7f) | 0x80); n >>= 7
+    b.append(n)
+    return bytes(b)
+
+def batch(seq, ops):
+    b = struct.pack('<QI', seq, len(ops))
+    for key, value in ops:
+        if value is None:
+            b += b'\x00' + varint(len(key)) + key
+        else:
+            b += b'\x01' + varint(len(key)) + key + varint(len(value)) + value
+    return b
+
+BLOCK = 32768
+def log(records, corrupt=None):
+    """LevelDB log writer. `corrupt` is the index of a record whose last byte gets flipped."""
+    out = bytearray()
+    for n, rec in enumerate(records):
+        left, first = rec, True
+        while True:
+            room = BLOCK - len(out) % BLOCK
+            if room < 7:
+                out += b'\x00' * room; room = BLOCK
+            avail = room - 7
+            frag, left = left[:avail], left[avail:]
+            end = not left
+            kind = 1 if first and end else 2 if first else 4 if end else 3
+            data = bytearray(frag)
+            crc = mask(crc32c(bytes([kind]) + bytes(data)))
+            if corrupt == n:
+                data[-1] ^= 0xff
+            out += struct.pack('<IHB', crc, len(frag), kind) + data
+            first = False
+            if end: break
+    return bytes(out)
+
+def snappy(data):
+    """Snappy raw encoding: repeats 8 bytes back become copies, the rest literals."""
+    out, literal, pos = bytearray(varint(len(data))), bytearray(), 0
+    def flush():
+        if literal:
+            n = len(literal) - 1
+            out.extend((bytes([n << 2]) if n < 60 else bytes([60 << 2, n])) + literal)
+            literal.clear()
+    while pos < len(data):
+        n = 0
+        while pos >= 8 and n < 11 and pos + n < len(data) and data[pos + n] == data[pos + n - 8]:
+            n += 1
+        if n >= 4:
+            flush()
+            out += bytes([((n - 4) << 2) | 1, 8])
+            pos += n
+        else:
+            literal.append(data[pos]); pos += 1
+    flush()
+    return bytes(out)
+
+def block(entries):
+    b, last = bytearray(), b''
+    for key, value in entries:
+        shared = 0
+        while shared < min(len(key), len(last)) and key[shared] == last[shared]:
+            shared += 1
+        b += varint(shared) + varint(len(key) - shared) + varint(len(value)) + key[shared:] + value
+        last = key
+    return bytes(b) + struct.pack('<II', 0, 1)
+
+def ikey(key, seq, kind=1):
+    return key + struct.pack('<Q', seq << 8 | kind)
+
+def table(blocks, corrupt=False):
+    data, index = bytearray(), []
+    def put(raw, compress):
+        body = snappy(raw) if compress else raw
+        kind = 1 if compress else 0
+        crc = mask(crc32c(body + bytes([kind])))
+        handle = varint(len(data)) + varint(len(body))
+        if corrupt:
+            crc ^= 1
+        data.extend(body + bytes([kind]) + struct.pack('<I', crc))
+        return handle
+    for entries, compress in blocks:
+        index.append((entries[-1][0], put(block(entries), compress)))
+    meta = put(block([]), False)
+    idx = put(block(index), False)
+    footer = (meta + idx).ljust(40, b'\x00') + struct.pack('<Q', 0xdb4775248b80fb57)
+    return bytes(data) + footer
+
+def write(path, data):
+    os.makedirs(os.path.dirname(path), exist_ok=True)
+    open(path, 'wb').write(data)
+
+big = bytes(i % 251 for i in range(70000))
+write(f'{out}/db/000005.ldb', table([
+    ([(ikey(b'app:a', 1), b'table-a'), (ikey(b'app:b', 2), b'table-b')], False),
+    ([(ikey(b'app:c', 3), b'snappy! ' * 8), (ikey(b'other:x', 4), b'x')], True),
+]))
+tail = log([batch(13, [(b'app:cut', b'written while the browser was running')])])
+write(f'{out}/db/000006.log', log([
+    batch(10, [(b'app:a', b'log-a'), (b'app:b', None)]),
+    batch(12, [(b'app:big', big)]),
+]) + tail[:20])
+write(f'{out}/corrupt/000003.log', log([
+    batch(1, [(b'app:good', b'good')]),
+    batch(2, [(b'app:bad', b'bad')]),
+], corrupt=1))
+write(f'{out}/corrupt/000004.ldb', table([
+    ([(ikey(b'app:table', 3), b'table')], False),
+], corrupt=True))